@@ -1,5 +1,113 @@
-use wagyu_runtime;
+use std::{
+  env,
+  fs,
+  process::ExitCode,
+};
 
-fn main() {
-  println!("Hello, world!");
+use wagyu_runtime::{
+  compile,
+  instance::ModuleInstance,
+  linker::Linker,
+  module::value::{
+    V128Value,
+    ValType,
+    Value,
+  },
+};
+
+const USAGE: &str = "Usage: wagyu <file.wasm> [<function> [<arg>...]]";
+
+fn main() -> ExitCode {
+  let args: Vec<String> = env::args().skip(1).collect();
+  let Some((file_path, rest)) = args.split_first() else {
+    eprintln!("{USAGE}");
+    return ExitCode::FAILURE;
+  };
+
+  match run(file_path, rest) {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(message) => {
+      eprintln!("{message}");
+      ExitCode::FAILURE
+    }
+  }
+}
+
+/// Instantiates a module and either lists its named functions or calls an exported one.
+fn run(file_path: &str, args: &[String]) -> Result<(), String> {
+  let buffer = fs::read(file_path).map_err(|err| format!("{file_path}: {err}"))?;
+  let module = compile(&buffer).map_err(|err| err.to_string())?;
+  let mut instance = Linker::new().instantiate(module).map_err(|err| err.to_string())?;
+
+  let Some((func_name, func_args)) = args.split_first() else {
+    list_funcs(&instance);
+    return Ok(());
+  };
+
+  let func = instance
+    .get_func(func_name)
+    .ok_or_else(|| format!("no exported function \"{func_name}\""))?;
+  let (params, _) = func.ty(&instance).map_err(|err| err.to_string())?;
+  if params.len() != func_args.len() {
+    return Err(format!(
+      "\"{func_name}\" takes {} arguments, {} given",
+      params.len(),
+      func_args.len()
+    ));
+  }
+  let values = params
+    .iter()
+    .zip(func_args)
+    .map(|(valtype, arg)| parse_value(*valtype, arg))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  match func.call(&mut instance, &values) {
+    Ok(results) => {
+      for result in results {
+        println!("{}", format_value(result));
+      }
+
+      Ok(())
+    }
+    Err(trap) => Err(trap.to_string()),
+  }
+}
+
+fn list_funcs(instance: &ModuleInstance) {
+  let names = instance.names();
+  if let Some(module_name) = names.module_name() {
+    println!("module ${module_name}");
+  }
+  for (func_idx, _) in names.funcs() {
+    println!("func {}", instance.describe_func(func_idx));
+  }
+}
+
+fn parse_value(valtype: ValType, arg: &str) -> Result<Value, String> {
+  let invalid = || format!("invalid {valtype} argument \"{arg}\"");
+
+  match valtype {
+    ValType::I32 => arg.parse().map(Value::I32).map_err(|_| invalid()),
+    ValType::I64 => arg.parse().map(Value::I64).map_err(|_| invalid()),
+    ValType::F32 => arg.parse().map(Value::F32).map_err(|_| invalid()),
+    ValType::F64 => arg.parse().map(Value::F64).map_err(|_| invalid()),
+    // vectors are written as a single hexadecimal number, with lane 0 in the low bits
+    ValType::V128 => arg
+      .strip_prefix("0x")
+      .and_then(|digits| u128::from_str_radix(digits, 16).ok())
+      .map(|bits| Value::V128(V128Value::from_bits(bits)))
+      .ok_or_else(invalid),
+    _ => Err(format!("{valtype} arguments are not supported")),
+  }
+}
+
+fn format_value(value: Value) -> String {
+  match value {
+    Value::I32(v) => format!("{v}: i32"),
+    Value::I64(v) => format!("{v}: i64"),
+    Value::F32(v) => format!("{v}: f32"),
+    Value::F64(v) => format!("{v}: f64"),
+    Value::V128(v) => format!("0x{:032X}: v128", v.to_bits()),
+    other => format!("{other:?}"),
+  }
 }
@@ -0,0 +1,66 @@
+//! Settings of an instance, which are fixed at instantiation so that they already apply to the start function.
+
+use crate::stack::{
+  DEFAULT_MAX_CALL_DEPTH,
+  DEFAULT_MAX_CONTROL_DEPTH,
+};
+
+/// Configuration passed to [`Linker::with_config`](crate::linker::Linker::with_config) and applied to every instance
+/// created by that linker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+  pub(crate) max_control_depth: usize,
+  pub(crate) max_call_depth: usize,
+  pub(crate) canonicalize_nans: bool,
+  pub(crate) relaxed_simd: RelaxedSimd,
+}
+
+impl Config {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Limits the number of labels and nested calls, beyond which execution traps with a stack overflow.
+  pub fn stack_limits(&mut self, max_control_depth: usize, max_call_depth: usize) -> &mut Self {
+    self.max_control_depth = max_control_depth;
+    self.max_call_depth = max_call_depth;
+    self
+  }
+
+  /// Enables deterministic mode, in which every NaN produced by float arithmetic is the canonical NaN,
+  /// so results are bit-identical on every host. Operators that only move bits, such as `abs`, `neg`,
+  /// `copysign` and loads, are not affected.
+  pub fn nan_canonicalization(&mut self, enabled: bool) -> &mut Self {
+    self.canonicalize_nans = enabled;
+    self
+  }
+
+  /// Selects the results of the relaxed vector instructions, which are deterministic by default.
+  pub fn relaxed_simd(&mut self, relaxed_simd: RelaxedSimd) -> &mut Self {
+    self.relaxed_simd = relaxed_simd;
+    self
+  }
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      max_control_depth: DEFAULT_MAX_CONTROL_DEPTH,
+      max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+      canonicalize_nans: false,
+      relaxed_simd: RelaxedSimd::Deterministic,
+    }
+  }
+}
+
+/// Results of the relaxed vector instructions, which the specification lets vary between hosts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RelaxedSimd {
+  /// The deterministic results defined by the specification, identical on every host.
+  #[default]
+  Deterministic,
+  /// The results of the vector instructions of the host architecture, as a compiler targeting it would produce.
+  /// They only differ from the deterministic ones on x86, for out-of-range and NaN inputs, masks whose lanes are
+  /// not all ones or all zeros, and `relaxed_madd`, which is not fused.
+  Native,
+}
@@ -1,25 +1,1537 @@
+use alloc::{
+  rc::Rc,
+  string::String,
+  vec::Vec,
+};
 use core::fmt;
 
-use crate::stack::Stack;
+use crate::{
+  config::RelaxedSimd,
+  instance::{
+    Caller,
+    ModuleInstance,
+  },
+  instr::Instr,
+  module::{
+    types::Type,
+    value::{
+      BlockType,
+      FuncIdx,
+      HeapType,
+      IndexType,
+      V128Value,
+      Value,
+    },
+    Module,
+  },
+  numerics::{
+    self,
+    Float,
+  },
+  simd,
+  stack::{
+    Frame,
+    Label,
+    Stack,
+  },
+  trap::{
+    FrameInfo,
+    Trap,
+  },
+};
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
   OutOfBoundMemoryAccess,
+  OutOfBoundTableAccess,
+  UndefinedElement,
+  UninitializedElement,
+  IndirectCallTypeMismatch,
   MemoryExhaustion,
   StackOverflow,
+  StackUnderflow,
+  TypeMismatch,
+  Unreachable,
+  IntegerDivideByZero,
+  IntegerOverflow,
+  InvalidConversionToInteger,
+  NullReference,
+  /// A function handle was used with an instance other than the one it was obtained from.
+  ForeignFunc,
+  /// Failure reported by a host function.
+  Host(String),
+  Unsupported(&'static str),
 }
 
 impl fmt::Display for Error {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
       Self::OutOfBoundMemoryAccess => write!(f, "Runtime error: memory access out of bounds"),
-      Self::MemoryExhaustion => write!(f, "Runtime error: memory exhausion"),
-      Self::StackOverflow => write!(f, "Runtime error: stack overflow")
+      Self::OutOfBoundTableAccess => write!(f, "Runtime error: table access out of bounds"),
+      Self::UndefinedElement => write!(f, "Runtime error: undefined element"),
+      Self::UninitializedElement => write!(f, "Runtime error: uninitialized element"),
+      Self::IndirectCallTypeMismatch => write!(f, "Runtime error: indirect call type mismatch"),
+      Self::MemoryExhaustion => write!(f, "Runtime error: memory exhaustion"),
+      Self::StackOverflow => write!(f, "Runtime error: stack overflow"),
+      Self::StackUnderflow => write!(f, "Runtime error: stack underflow"),
+      Self::TypeMismatch => write!(f, "Runtime error: type mismatch"),
+      Self::Unreachable => write!(f, "Runtime error: unreachable executed"),
+      Self::IntegerDivideByZero => write!(f, "Runtime error: integer divide by zero"),
+      Self::IntegerOverflow => write!(f, "Runtime error: integer overflow"),
+      Self::InvalidConversionToInteger => write!(f, "Runtime error: invalid conversion to integer"),
+      Self::NullReference => write!(f, "Runtime error: null reference"),
+      Self::ForeignFunc => write!(f, "Runtime error: function belongs to another instance"),
+      Self::Host(message) => write!(f, "Runtime error: {message}"),
+      Self::Unsupported(what) => write!(f, "Runtime error: unsupported {what}"),
     }
   }
 }
 
-fn execute(func_body: &[u8], stack: &mut Stack) -> Result<(), Error> {
-  let mut pc: u32 = 0;
+/// Pops the arguments of a function from the operand stack and pushes its activation frame.
+fn enter(
+  module: &Module,
+  stack: &mut Stack,
+  func_idx: FuncIdx,
+  return_pc: usize,
+  n_imported: usize,
+) -> Result<(), Error> {
+  let func = &module.functions[(func_idx as usize) - n_imported];
+  let signature = &module.types[func.signature_idx as usize];
+
+  let mut locals = stack.operand.pop_n(signature.params.len())?;
+  locals.extend(func.locals.iter().map(Value::default_of));
+
+  stack.call.push(Frame {
+    func_idx,
+    locals,
+    return_pc,
+    arity: signature.results.len(),
+    height: stack.operand.len(),
+    label_height: stack.control.len(),
+  })
+}
+
+/// Pops the arguments of an imported function, calls it and pushes its results.
+fn call_host(instance: &ModuleInstance, stack: &mut Stack, func_idx: FuncIdx) -> Result<(), Error> {
+  let host_func = &instance.host_funcs[func_idx as usize];
+  let args = stack.operand.pop_n(host_func.ty.params.len())?;
+
+  let mut caller = Caller {
+    memories: &instance.memories,
+  };
+  for result in host_func.call(&mut caller, &args)? {
+    stack.operand.push(result);
+  }
 
   Ok(())
 }
+
+/// Pops the current activation frame, leaving only its results above the caller's operands,
+/// and returns the position to resume the caller at.
+fn leave(stack: &mut Stack) -> Result<usize, Error> {
+  let frame = stack.call.pop().ok_or(Error::StackUnderflow)?;
+
+  stack.operand.unwind(frame.height, frame.arity)?;
+  stack.control.truncate(frame.label_height);
+
+  Ok(frame.return_pc)
+}
+
+/// Unwinds to the label at `depth` and returns its continuation,
+/// or `None` if the branch targets the function body itself.
+fn branch(stack: &mut Stack, depth: usize) -> Result<Option<usize>, Error> {
+  let label_height = stack.call.current().ok_or(Error::StackUnderflow)?.label_height;
+  if depth >= stack.control.len() - label_height {
+    return Ok(None);
+  }
+
+  let label = stack.control.get(depth).ok_or(Error::StackUnderflow)?;
+  stack.operand.unwind(label.height, label.arity)?;
+  stack.control.truncate(stack.control.len() - depth - 1);
+
+  Ok(Some(label.continuation))
+}
+
+/// Returns the number of parameters and results of a block.
+fn block_arity(types: &[Type], block_type: &BlockType) -> (usize, usize) {
+  match block_type {
+    BlockType::Empty => (0, 0),
+    BlockType::Value(_) => (0, 1),
+    BlockType::TypeIdx(type_idx) => {
+      let signature = &types[*type_idx as usize];
+      (signature.params.len(), signature.results.len())
+    }
+  }
+}
+
+/// Executes a function on the given stack.
+///
+/// The arguments of the function are expected on top of the operand stack, and are replaced by its results
+/// once the function returns. On a trap, `pc` is left at the instruction that trapped.
+pub(crate) fn execute(
+  instance: &mut ModuleInstance,
+  stack: &mut Stack,
+  func_idx: FuncIdx,
+  pc: &mut usize,
+) -> Result<(), Error> {
+  let n_imported = instance.host_funcs.len();
+  if (func_idx as usize) < n_imported {
+    return call_host(instance, stack, func_idx);
+  }
+
+  let entry_depth = stack.call.len();
+
+  enter(&instance.module, stack, func_idx, 0, n_imported)?;
+
+  let mut func_pos = (func_idx as usize) - n_imported;
+  *pc = 0;
+  let canonicalize_nans = instance.canonicalize_nans;
+  // only the x86 vector instructions produce native results that differ from the deterministic ones
+  let x86_relaxed =
+    instance.relaxed_simd == RelaxedSimd::Native && cfg!(any(target_arch = "x86", target_arch = "x86_64"));
+
+  macro_rules! pop {
+    () => {
+      stack.operand.pop()?
+    };
+    ($ty:ty) => {
+      stack.operand.pop_as::<$ty>()?
+    };
+  }
+
+  macro_rules! push {
+    ($val:expr) => {
+      stack.operand.push(Value::from($val))
+    };
+  }
+
+  macro_rules! unop {
+    ($ty:ty, $f:expr) => {{
+      let a = pop!($ty);
+      push!($f(a));
+    }};
+  }
+
+  macro_rules! trapping_unop {
+    ($ty:ty, $f:expr) => {{
+      let a = pop!($ty);
+      push!($f(a)?);
+    }};
+  }
+
+  macro_rules! binop {
+    ($ty:ty, $f:expr) => {{
+      let b = pop!($ty);
+      let a = pop!($ty);
+      push!($f(a, b));
+    }};
+  }
+
+  /// Float operators whose NaN results are canonicalized in deterministic mode.
+  macro_rules! funop {
+    ($ty:ty, $f:expr) => {{
+      let a = pop!($ty);
+      push!(canonical!($f(a)));
+    }};
+  }
+
+  macro_rules! fbinop {
+    ($ty:ty, $f:expr) => {{
+      let b = pop!($ty);
+      let a = pop!($ty);
+      push!(canonical!($f(a, b)));
+    }};
+  }
+
+  macro_rules! canonical {
+    ($val:expr) => {{
+      let val = $val;
+      if canonicalize_nans {
+        val.canonicalize_nan()
+      } else {
+        val
+      }
+    }};
+  }
+
+  macro_rules! testop {
+    ($ty:ty, $f:expr) => {{
+      let a = pop!($ty);
+      push!($f(a) as i32);
+    }};
+  }
+
+  macro_rules! relop {
+    ($ty:ty, $f:expr) => {{
+      let b = pop!($ty);
+      let a = pop!($ty);
+      push!($f(a, b) as i32);
+    }};
+  }
+
+  macro_rules! do_return {
+    () => {{
+      *pc = leave(stack)?;
+      if stack.call.len() == entry_depth {
+        return Ok(());
+      }
+
+      func_pos = (stack.call.current().ok_or(Error::StackUnderflow)?.func_idx as usize) - n_imported;
+      continue;
+    }};
+  }
+
+  macro_rules! do_branch {
+    ($depth:expr) => {{
+      match branch(stack, $depth as usize)? {
+        Some(continuation) => {
+          *pc = continuation;
+          continue;
+        }
+        None => do_return!(),
+      }
+    }};
+  }
+
+  // pops an address, or a size in pages or bytes, of the type that addresses a memory
+  macro_rules! pop_addr {
+    ($mem_idx:expr) => {
+      match instance.memories[*$mem_idx as usize].0.borrow().ty.idx_type {
+        IndexType::I32 => pop!(i32) as u32 as u64,
+        IndexType::I64 => pop!(i64) as u64,
+      }
+    };
+  }
+
+  macro_rules! push_addr {
+    ($mem_idx:expr, $val:expr) => {
+      match instance.memories[*$mem_idx as usize].0.borrow().ty.idx_type {
+        IndexType::I32 => push!($val as i32),
+        IndexType::I64 => push!($val as i64),
+      }
+    };
+  }
+
+  macro_rules! load {
+    ($mem_idx:expr, $offset:expr, $n:literal, $conv:expr) => {{
+      let addr = pop_addr!($mem_idx);
+      let bytes = instance.memories[*$mem_idx as usize]
+        .0
+        .borrow()
+        .load::<$n>(addr, *$offset)?;
+      push!($conv(bytes));
+    }};
+  }
+
+  macro_rules! store {
+    ($mem_idx:expr, $offset:expr, $ty:ty, $conv:expr) => {{
+      let val = pop!($ty);
+      let addr = pop_addr!($mem_idx);
+      instance.memories[*$mem_idx as usize]
+        .0
+        .borrow_mut()
+        .store(addr, *$offset, $conv(val))?;
+    }};
+  }
+
+  // vector instructions view their operands as arrays of lanes of the given shape
+  macro_rules! vunop {
+    ($lanes:ty, $f:expr) => {{
+      let a: $lanes = pop!(V128Value).lanes();
+      push!(V128Value::from_lanes(a.map($f)));
+    }};
+  }
+
+  macro_rules! vbinop {
+    ($lanes:ty, $f:expr) => {{
+      let b: $lanes = pop!(V128Value).lanes();
+      let a: $lanes = pop!(V128Value).lanes();
+      push!(V128Value::from_lanes(simd::zip(a, b, $f)));
+    }};
+  }
+
+  macro_rules! vfunop {
+    ($lanes:ty, $f:expr) => {
+      vunop!($lanes, |a| canonical!($f(a)))
+    };
+  }
+
+  macro_rules! vfbinop {
+    ($lanes:ty, $f:expr) => {
+      vbinop!($lanes, |a, b| canonical!($f(a, b)))
+    };
+  }
+
+  macro_rules! vfternop {
+    ($lanes:ty, $f:expr) => {{
+      let c: $lanes = pop!(V128Value).lanes();
+      let b: $lanes = pop!(V128Value).lanes();
+      let a: $lanes = pop!(V128Value).lanes();
+      let r: $lanes = core::array::from_fn(|i| canonical!($f(a[i], b[i], c[i])));
+      push!(V128Value::from_lanes(r));
+    }};
+  }
+
+  /// Compares lanes into masks of all ones or all zeros, of the signed integer type of the lanes' width.
+  macro_rules! vrelop {
+    ($lanes:ty, $mask:ty, $f:expr) => {
+      vbinop!($lanes, |a, b| -<$mask>::from($f(a, b)))
+    };
+  }
+
+  macro_rules! vshift {
+    ($lanes:ty, $f:expr) => {{
+      let shift = pop!(i32) as u32;
+      vunop!($lanes, |a| $f(a, shift))
+    }};
+  }
+
+  macro_rules! vtest {
+    ($lanes:ty, $f:expr) => {{
+      let a: $lanes = pop!(V128Value).lanes();
+      push!(i32::from($f(a)));
+    }};
+  }
+
+  /// Vector operation that changes the shape, or does not operate lane by lane.
+  macro_rules! vconv {
+    ($from:ty => $to:ty, $f:expr) => {{
+      let a: $from = pop!(V128Value).lanes();
+      let r: $to = $f(a);
+      push!(V128Value::from_lanes(r));
+    }};
+  }
+
+  macro_rules! vbinconv {
+    ($from:ty => $to:ty, $f:expr) => {{
+      let b: $from = pop!(V128Value).lanes();
+      let a: $from = pop!(V128Value).lanes();
+      let r: $to = $f(a, b);
+      push!(V128Value::from_lanes(r));
+    }};
+  }
+
+  macro_rules! vsplat {
+    ($ty:ty, $lanes:ty, $f:expr) => {{
+      let x = pop!($ty);
+      let r: $lanes = core::array::from_fn(|_| $f(x));
+      push!(V128Value::from_lanes(r));
+    }};
+  }
+
+  macro_rules! vextract {
+    ($lanes:ty, $lane:expr, $f:expr) => {{
+      let a: $lanes = pop!(V128Value).lanes();
+      push!($f(a[*$lane as usize]));
+    }};
+  }
+
+  macro_rules! vreplace {
+    ($ty:ty, $lanes:ty, $lane:expr, $f:expr) => {{
+      let x = pop!($ty);
+      let mut a: $lanes = pop!(V128Value).lanes();
+      a[*$lane as usize] = $f(x);
+      push!(V128Value::from_lanes(a));
+    }};
+  }
+
+  macro_rules! vlaneselect {
+    ($width:literal) => {{
+      let mask = pop!(V128Value);
+      let b = pop!(V128Value);
+      let a = pop!(V128Value);
+      push!(simd::relaxed_laneselect::<$width>(a, b, mask, x86_relaxed));
+    }};
+  }
+
+  /// Loads 8 bytes and widens their lanes to fill a vector.
+  macro_rules! load_extend {
+    ($mem_idx:expr, $offset:expr, $from:ty => $to:ty) => {
+      load!($mem_idx, $offset, 8, |bytes| {
+        let a: $from = simd::zero_extend_bytes(bytes).lanes();
+        let r: $to = simd::extend(a, false);
+        V128Value::from_lanes(r)
+      })
+    };
+  }
+
+  macro_rules! load_lane {
+    ($mem_idx:expr, $offset:expr, $lane:expr, $n:literal) => {{
+      let mut bytes = pop!(V128Value).to_le_bytes();
+      let addr = pop_addr!($mem_idx);
+      let lane = instance.memories[*$mem_idx as usize]
+        .0
+        .borrow()
+        .load::<$n>(addr, *$offset)?;
+      let start = *$lane as usize * $n;
+      bytes[start..start + $n].copy_from_slice(&lane);
+      push!(V128Value::from_le_bytes(bytes));
+    }};
+  }
+
+  macro_rules! store_lane {
+    ($mem_idx:expr, $offset:expr, $lane:expr, $n:literal) => {{
+      let bytes = pop!(V128Value).to_le_bytes();
+      let addr = pop_addr!($mem_idx);
+      let start = *$lane as usize * $n;
+      let mut lane = [0; $n];
+      lane.copy_from_slice(&bytes[start..start + $n]);
+      instance.memories[*$mem_idx as usize]
+        .0
+        .borrow_mut()
+        .store(addr, *$offset, lane)?;
+    }};
+  }
+
+  loop {
+    let instrs = &instance.module.functions[func_pos].parsed_body.instrs;
+    if *pc >= instrs.len() {
+      do_return!();
+    }
+
+    match &instrs[*pc] {
+      // control instructions
+      Instr::Unreachable => return Err(Error::Unreachable),
+      Instr::Nop => {}
+      Instr::Block(block_type, end) => {
+        let (n_params, n_results) = block_arity(&instance.module.types, block_type);
+        stack.control.push(Label {
+          arity: n_results,
+          height: stack.operand.len().saturating_sub(n_params),
+          continuation: end + 1,
+        })?;
+      }
+      Instr::Loop(block_type) => {
+        let (n_params, _) = block_arity(&instance.module.types, block_type);
+        stack.control.push(Label {
+          arity: n_params,
+          height: stack.operand.len().saturating_sub(n_params),
+          continuation: *pc,
+        })?;
+      }
+      Instr::If(block_type, else_pos, end) => {
+        let cond = pop!(i32);
+        let (n_params, n_results) = block_arity(&instance.module.types, block_type);
+        let label = Label {
+          arity: n_results,
+          height: stack.operand.len().saturating_sub(n_params),
+          continuation: end + 1,
+        };
+
+        if cond != 0 {
+          stack.control.push(label)?;
+        } else if let Some(else_pos) = else_pos {
+          stack.control.push(label)?;
+          *pc = else_pos + 1;
+          continue;
+        } else {
+          *pc = end + 1;
+          continue;
+        }
+      }
+      Instr::Else(end) => {
+        // reaching `else` means the `then` arm finished, so skip to the matching `end`
+        stack.control.pop();
+        *pc = end + 1;
+        continue;
+      }
+      Instr::End => {
+        stack.control.pop();
+      }
+      Instr::Br(depth) => do_branch!(*depth),
+      Instr::BrIf(depth) => {
+        if pop!(i32) != 0 {
+          do_branch!(*depth);
+        }
+      }
+      Instr::BrTable(depths, default) => {
+        let i = pop!(i32) as u32 as usize;
+        do_branch!(*depths.get(i).unwrap_or(default));
+      }
+      Instr::Return => do_return!(),
+      Instr::Call(callee_idx) if (*callee_idx as usize) < n_imported => {
+        call_host(instance, stack, *callee_idx)?;
+      }
+      Instr::Call(callee_idx) => {
+        enter(&instance.module, stack, *callee_idx, *pc + 1, n_imported)?;
+        func_pos = (*callee_idx as usize) - n_imported;
+        *pc = 0;
+        continue;
+      }
+      Instr::CallIndirect(table_idx, type_idx) => {
+        let elem_idx = pop!(i32) as u32;
+        let callee_idx = match instance.tables[*table_idx as usize].0.borrow().get(elem_idx) {
+          Ok(Value::FuncRef(Some(callee_idx))) => callee_idx,
+          Ok(_) => return Err(Error::UninitializedElement),
+          Err(_) => return Err(Error::UndefinedElement),
+        };
+        if instance.module.func_type(callee_idx) != Some(&instance.module.types[*type_idx as usize]) {
+          return Err(Error::IndirectCallTypeMismatch);
+        }
+
+        if (callee_idx as usize) < n_imported {
+          call_host(instance, stack, callee_idx)?;
+        } else {
+          enter(&instance.module, stack, callee_idx, *pc + 1, n_imported)?;
+          func_pos = (callee_idx as usize) - n_imported;
+          *pc = 0;
+          continue;
+        }
+      }
+
+      // reference instructions
+      Instr::RefNull(heap_type) => stack.operand.push(match heap_type {
+        HeapType::Func => Value::FuncRef(None),
+        HeapType::Extern => Value::ExternRef(None),
+      }),
+      Instr::RefIsNull => {
+        let val = pop!();
+        push!(matches!(val, Value::FuncRef(None) | Value::ExternRef(None)) as i32);
+      }
+      Instr::RefFunc(func_idx) => stack.operand.push(Value::FuncRef(Some(*func_idx))),
+
+      // parametric instructions
+      Instr::Drop => {
+        pop!();
+      }
+      Instr::Select(_) => {
+        let cond = pop!(i32);
+        let b = pop!();
+        let a = pop!();
+        stack.operand.push(if cond != 0 { a } else { b });
+      }
+
+      // variable instructions
+      Instr::LocalGet(idx) => {
+        let frame = stack.call.current().ok_or(Error::StackUnderflow)?;
+        let val = frame.locals[*idx as usize];
+        stack.operand.push(val);
+      }
+      Instr::LocalSet(idx) => {
+        let val = pop!();
+        let frame = stack.call.current_mut().ok_or(Error::StackUnderflow)?;
+        frame.locals[*idx as usize] = val;
+      }
+      Instr::LocalTee(idx) => {
+        let val = stack.operand.peek()?;
+        let frame = stack.call.current_mut().ok_or(Error::StackUnderflow)?;
+        frame.locals[*idx as usize] = val;
+      }
+      Instr::GlobalGet(idx) => {
+        stack.operand.push(instance.globals[*idx as usize].0.borrow().get());
+      }
+      Instr::GlobalSet(idx) => {
+        let val = pop!();
+        instance.globals[*idx as usize].0.borrow_mut().set(val);
+      }
+
+      // table instructions
+      Instr::TableGet(table_idx) => {
+        let idx = pop!(i32) as u32;
+        let val = instance.tables[*table_idx as usize].0.borrow().get(idx)?;
+        stack.operand.push(val);
+      }
+      Instr::TableSet(table_idx) => {
+        let val = pop!();
+        let idx = pop!(i32) as u32;
+        instance.tables[*table_idx as usize].0.borrow_mut().set(idx, val)?;
+      }
+      Instr::TableSize(table_idx) => {
+        let size = instance.tables[*table_idx as usize].0.borrow().size();
+        push!(size as i32);
+      }
+      Instr::TableGrow(table_idx) => {
+        let delta = pop!(i32) as u32;
+        let init = pop!();
+        let old_size = instance.tables[*table_idx as usize].0.borrow_mut().grow(delta, init);
+        push!(old_size);
+      }
+      Instr::TableFill(table_idx) => {
+        let n = pop!(i32) as u32;
+        let val = pop!();
+        let dst = pop!(i32) as u32;
+        instance.tables[*table_idx as usize].0.borrow_mut().fill(dst, val, n)?;
+      }
+      Instr::TableCopy(dst_table_idx, src_table_idx) => {
+        let n = pop!(i32) as u32;
+        let src = pop!(i32) as u32;
+        let dst = pop!(i32) as u32;
+        let dst_table = &instance.tables[*dst_table_idx as usize];
+        let src_table = &instance.tables[*src_table_idx as usize];
+        // the same table may be imported under several indices
+        if Rc::ptr_eq(&dst_table.0, &src_table.0) {
+          dst_table.0.borrow_mut().copy(dst, src, n)?;
+        } else {
+          let src_table = src_table.0.borrow();
+          dst_table.0.borrow_mut().copy_from(&src_table, dst, src, n)?;
+        }
+      }
+      Instr::TableInit(table_idx, elem_idx) => {
+        let n = pop!(i32) as u32;
+        let src = pop!(i32) as u32;
+        let dst = pop!(i32) as u32;
+        let elements = &instance.module.elems[*elem_idx as usize].elements;
+        instance.tables[*table_idx as usize]
+          .0
+          .borrow_mut()
+          .init(elements, dst, src, n)?;
+      }
+      Instr::ElemDrop(elem_idx) => {
+        instance.module.elems[*elem_idx as usize].drop();
+      }
+
+      // memory instructions
+      Instr::I32Load(mem_idx, offset, _) => load!(mem_idx, offset, 4, i32::from_le_bytes),
+      Instr::I64Load(mem_idx, offset, _) => load!(mem_idx, offset, 8, i64::from_le_bytes),
+      Instr::F32Load(mem_idx, offset, _) => load!(mem_idx, offset, 4, f32::from_le_bytes),
+      Instr::F64Load(mem_idx, offset, _) => load!(mem_idx, offset, 8, f64::from_le_bytes),
+      Instr::I32Load8S(mem_idx, offset, _) => load!(mem_idx, offset, 1, |b| i8::from_le_bytes(b) as i32),
+      Instr::I32Load8U(mem_idx, offset, _) => load!(mem_idx, offset, 1, |b| u8::from_le_bytes(b) as i32),
+      Instr::I32Load16S(mem_idx, offset, _) => load!(mem_idx, offset, 2, |b| i16::from_le_bytes(b) as i32),
+      Instr::I32Load16U(mem_idx, offset, _) => load!(mem_idx, offset, 2, |b| u16::from_le_bytes(b) as i32),
+      Instr::I64Load8S(mem_idx, offset, _) => load!(mem_idx, offset, 1, |b| i8::from_le_bytes(b) as i64),
+      Instr::I64Load8U(mem_idx, offset, _) => load!(mem_idx, offset, 1, |b| u8::from_le_bytes(b) as i64),
+      Instr::I64Load16S(mem_idx, offset, _) => load!(mem_idx, offset, 2, |b| i16::from_le_bytes(b) as i64),
+      Instr::I64Load16U(mem_idx, offset, _) => load!(mem_idx, offset, 2, |b| u16::from_le_bytes(b) as i64),
+      Instr::I64Load32S(mem_idx, offset, _) => load!(mem_idx, offset, 4, |b| i32::from_le_bytes(b) as i64),
+      Instr::I64Load32U(mem_idx, offset, _) => load!(mem_idx, offset, 4, |b| u32::from_le_bytes(b) as i64),
+      Instr::I32Store(mem_idx, offset, _) => store!(mem_idx, offset, i32, i32::to_le_bytes),
+      Instr::I64Store(mem_idx, offset, _) => store!(mem_idx, offset, i64, i64::to_le_bytes),
+      Instr::F32Store(mem_idx, offset, _) => store!(mem_idx, offset, f32, f32::to_le_bytes),
+      Instr::F64Store(mem_idx, offset, _) => store!(mem_idx, offset, f64, f64::to_le_bytes),
+      Instr::I32Store8(mem_idx, offset, _) => store!(mem_idx, offset, i32, |v| (v as u8).to_le_bytes()),
+      Instr::I32Store16(mem_idx, offset, _) => store!(mem_idx, offset, i32, |v| (v as u16).to_le_bytes()),
+      Instr::I64Store8(mem_idx, offset, _) => store!(mem_idx, offset, i64, |v| (v as u8).to_le_bytes()),
+      Instr::I64Store16(mem_idx, offset, _) => store!(mem_idx, offset, i64, |v| (v as u16).to_le_bytes()),
+      Instr::I64Store32(mem_idx, offset, _) => store!(mem_idx, offset, i64, |v| (v as u32).to_le_bytes()),
+      Instr::MemorySize(mem_idx) => {
+        let size = instance.memories[*mem_idx as usize].0.borrow().size();
+        push_addr!(mem_idx, size);
+      }
+      Instr::MemoryGrow(mem_idx) => {
+        let delta = pop_addr!(mem_idx);
+        let old_size = instance.memories[*mem_idx as usize].0.borrow_mut().grow(delta);
+        // -1 in either address type
+        push_addr!(mem_idx, old_size.unwrap_or(u64::MAX));
+      }
+      Instr::MemoryFill(mem_idx) => {
+        let n = pop_addr!(mem_idx);
+        let val = pop!(i32);
+        let dst = pop_addr!(mem_idx);
+        instance.memories[*mem_idx as usize]
+          .0
+          .borrow_mut()
+          .fill(dst, val as u8, n)?;
+      }
+      Instr::MemoryCopy(dst_mem_idx, src_mem_idx) => {
+        let dst_memory = &instance.memories[*dst_mem_idx as usize];
+        let src_memory = &instance.memories[*src_mem_idx as usize];
+        // the size is 64-bit only when both memories are
+        let n = match (dst_memory.0.borrow().ty.idx_type, src_memory.0.borrow().ty.idx_type) {
+          (IndexType::I64, IndexType::I64) => pop!(i64) as u64,
+          _ => pop!(i32) as u32 as u64,
+        };
+        let src = pop_addr!(src_mem_idx);
+        let dst = pop_addr!(dst_mem_idx);
+        // the same memory may be imported under several indices
+        if Rc::ptr_eq(&dst_memory.0, &src_memory.0) {
+          dst_memory.0.borrow_mut().copy(dst, src, n)?;
+        } else {
+          let src_memory = src_memory.0.borrow();
+          dst_memory.0.borrow_mut().copy_from(&src_memory, dst, src, n)?;
+        }
+      }
+      Instr::MemoryInit(mem_idx, data_idx) => {
+        let n = pop!(i32) as u32;
+        let src = pop!(i32) as u32;
+        let dst = pop_addr!(mem_idx);
+        let data = &instance.module.data[*data_idx as usize].data;
+        instance.memories[*mem_idx as usize]
+          .0
+          .borrow_mut()
+          .init(data, dst, src, n)?;
+      }
+      Instr::DataDrop(data_idx) => {
+        instance.module.data[*data_idx as usize].drop();
+      }
+
+      // numeric instructions
+      Instr::I32Const(val) => push!(*val),
+      Instr::I64Const(val) => push!(*val),
+      Instr::F32Const(val) => push!(*val),
+      Instr::F64Const(val) => push!(*val),
+
+      Instr::I32Eqz => testop!(i32, |a| a == 0),
+      Instr::I32Eq => relop!(i32, |a, b| a == b),
+      Instr::I32Ne => relop!(i32, |a, b| a != b),
+      Instr::I32LtS => relop!(i32, |a, b| a < b),
+      Instr::I32LtU => relop!(i32, |a, b| (a as u32) < (b as u32)),
+      Instr::I32GtS => relop!(i32, |a, b| a > b),
+      Instr::I32GtU => relop!(i32, |a, b| (a as u32) > (b as u32)),
+      Instr::I32LeS => relop!(i32, |a, b| a <= b),
+      Instr::I32LeU => relop!(i32, |a, b| (a as u32) <= (b as u32)),
+      Instr::I32GeS => relop!(i32, |a, b| a >= b),
+      Instr::I32GeU => relop!(i32, |a, b| (a as u32) >= (b as u32)),
+
+      Instr::I64Eqz => testop!(i64, |a| a == 0),
+      Instr::I64Eq => relop!(i64, |a, b| a == b),
+      Instr::I64Ne => relop!(i64, |a, b| a != b),
+      Instr::I64LtS => relop!(i64, |a, b| a < b),
+      Instr::I64LtU => relop!(i64, |a, b| (a as u64) < (b as u64)),
+      Instr::I64GtS => relop!(i64, |a, b| a > b),
+      Instr::I64GtU => relop!(i64, |a, b| (a as u64) > (b as u64)),
+      Instr::I64LeS => relop!(i64, |a, b| a <= b),
+      Instr::I64LeU => relop!(i64, |a, b| (a as u64) <= (b as u64)),
+      Instr::I64GeS => relop!(i64, |a, b| a >= b),
+      Instr::I64GeU => relop!(i64, |a, b| (a as u64) >= (b as u64)),
+
+      Instr::F32Eq => relop!(f32, |a, b| a == b),
+      Instr::F32Ne => relop!(f32, |a, b| a != b),
+      Instr::F32Lt => relop!(f32, |a, b| a < b),
+      Instr::F32Gt => relop!(f32, |a, b| a > b),
+      Instr::F32Le => relop!(f32, |a, b| a <= b),
+      Instr::F32Ge => relop!(f32, |a, b| a >= b),
+
+      Instr::F64Eq => relop!(f64, |a, b| a == b),
+      Instr::F64Ne => relop!(f64, |a, b| a != b),
+      Instr::F64Lt => relop!(f64, |a, b| a < b),
+      Instr::F64Gt => relop!(f64, |a, b| a > b),
+      Instr::F64Le => relop!(f64, |a, b| a <= b),
+      Instr::F64Ge => relop!(f64, |a, b| a >= b),
+
+      Instr::I32Clz => unop!(i32, |a: i32| a.leading_zeros() as i32),
+      Instr::I32Ctz => unop!(i32, |a: i32| a.trailing_zeros() as i32),
+      Instr::I32Popcnt => unop!(i32, |a: i32| a.count_ones() as i32),
+      Instr::I32Add => binop!(i32, i32::wrapping_add),
+      Instr::I32Sub => binop!(i32, i32::wrapping_sub),
+      Instr::I32Mul => binop!(i32, i32::wrapping_mul),
+      Instr::I32DivS => {
+        let b = pop!(i32);
+        let a = pop!(i32);
+        if b == 0 {
+          return Err(Error::IntegerDivideByZero);
+        }
+        push!(a.checked_div(b).ok_or(Error::IntegerOverflow)?);
+      }
+      Instr::I32DivU => {
+        let b = pop!(i32) as u32;
+        let a = pop!(i32) as u32;
+        push!(a.checked_div(b).ok_or(Error::IntegerDivideByZero)? as i32);
+      }
+      Instr::I32RemS => {
+        let b = pop!(i32);
+        let a = pop!(i32);
+        if b == 0 {
+          return Err(Error::IntegerDivideByZero);
+        }
+        push!(a.wrapping_rem(b));
+      }
+      Instr::I32RemU => {
+        let b = pop!(i32) as u32;
+        let a = pop!(i32) as u32;
+        push!(a.checked_rem(b).ok_or(Error::IntegerDivideByZero)? as i32);
+      }
+      Instr::I32And => binop!(i32, |a, b| a & b),
+      Instr::I32Or => binop!(i32, |a, b| a | b),
+      Instr::I32Xor => binop!(i32, |a, b| a ^ b),
+      Instr::I32Shl => binop!(i32, |a: i32, b: i32| a.wrapping_shl(b as u32)),
+      Instr::I32ShrS => binop!(i32, |a: i32, b: i32| a.wrapping_shr(b as u32)),
+      Instr::I32ShrU => binop!(i32, |a: i32, b: i32| (a as u32).wrapping_shr(b as u32) as i32),
+      Instr::I32Rotl => binop!(i32, |a: i32, b: i32| a.rotate_left(b as u32)),
+      Instr::I32Rotr => binop!(i32, |a: i32, b: i32| a.rotate_right(b as u32)),
+
+      Instr::I64Clz => unop!(i64, |a: i64| a.leading_zeros() as i64),
+      Instr::I64Ctz => unop!(i64, |a: i64| a.trailing_zeros() as i64),
+      Instr::I64Popcnt => unop!(i64, |a: i64| a.count_ones() as i64),
+      Instr::I64Add => binop!(i64, i64::wrapping_add),
+      Instr::I64Sub => binop!(i64, i64::wrapping_sub),
+      Instr::I64Mul => binop!(i64, i64::wrapping_mul),
+      Instr::I64DivS => {
+        let b = pop!(i64);
+        let a = pop!(i64);
+        if b == 0 {
+          return Err(Error::IntegerDivideByZero);
+        }
+        push!(a.checked_div(b).ok_or(Error::IntegerOverflow)?);
+      }
+      Instr::I64DivU => {
+        let b = pop!(i64) as u64;
+        let a = pop!(i64) as u64;
+        push!(a.checked_div(b).ok_or(Error::IntegerDivideByZero)? as i64);
+      }
+      Instr::I64RemS => {
+        let b = pop!(i64);
+        let a = pop!(i64);
+        if b == 0 {
+          return Err(Error::IntegerDivideByZero);
+        }
+        push!(a.wrapping_rem(b));
+      }
+      Instr::I64RemU => {
+        let b = pop!(i64) as u64;
+        let a = pop!(i64) as u64;
+        push!(a.checked_rem(b).ok_or(Error::IntegerDivideByZero)? as i64);
+      }
+      Instr::I64And => binop!(i64, |a, b| a & b),
+      Instr::I64Or => binop!(i64, |a, b| a | b),
+      Instr::I64Xor => binop!(i64, |a, b| a ^ b),
+      Instr::I64Shl => binop!(i64, |a: i64, b: i64| a.wrapping_shl(b as u32)),
+      Instr::I64ShrS => binop!(i64, |a: i64, b: i64| a.wrapping_shr(b as u32)),
+      Instr::I64ShrU => binop!(i64, |a: i64, b: i64| (a as u64).wrapping_shr(b as u32) as i64),
+      Instr::I64Rotl => binop!(i64, |a: i64, b: i64| a.rotate_left(b as u32)),
+      Instr::I64Rotr => binop!(i64, |a: i64, b: i64| a.rotate_right(b as u32)),
+
+      Instr::F32Abs => unop!(f32, f32::abs),
+      Instr::F32Neg => unop!(f32, |a: f32| -a),
+      Instr::F32Ceil => funop!(f32, f32::fceil),
+      Instr::F32Floor => funop!(f32, f32::ffloor),
+      Instr::F32Trunc => funop!(f32, f32::ftrunc),
+      Instr::F32Nearest => funop!(f32, f32::fnearest),
+      Instr::F32Sqrt => funop!(f32, f32::fsqrt),
+      Instr::F32Add => fbinop!(f32, |a, b| a + b),
+      Instr::F32Sub => fbinop!(f32, |a, b| a - b),
+      Instr::F32Mul => fbinop!(f32, |a, b| a * b),
+      Instr::F32Div => fbinop!(f32, |a, b| a / b),
+      Instr::F32Min => fbinop!(f32, f32::fmin),
+      Instr::F32Max => fbinop!(f32, f32::fmax),
+      Instr::F32Copysign => binop!(f32, f32::copysign),
+
+      Instr::F64Abs => unop!(f64, f64::abs),
+      Instr::F64Neg => unop!(f64, |a: f64| -a),
+      Instr::F64Ceil => funop!(f64, f64::fceil),
+      Instr::F64Floor => funop!(f64, f64::ffloor),
+      Instr::F64Trunc => funop!(f64, f64::ftrunc),
+      Instr::F64Nearest => funop!(f64, f64::fnearest),
+      Instr::F64Sqrt => funop!(f64, f64::fsqrt),
+      Instr::F64Add => fbinop!(f64, |a, b| a + b),
+      Instr::F64Sub => fbinop!(f64, |a, b| a - b),
+      Instr::F64Mul => fbinop!(f64, |a, b| a * b),
+      Instr::F64Div => fbinop!(f64, |a, b| a / b),
+      Instr::F64Min => fbinop!(f64, f64::fmin),
+      Instr::F64Max => fbinop!(f64, f64::fmax),
+      Instr::F64Copysign => binop!(f64, f64::copysign),
+
+      Instr::I32WrapI64 => unop!(i64, |a| a as i32),
+      Instr::I32TruncF32S => trapping_unop!(f32, numerics::i32_trunc_f32_s),
+      Instr::I32TruncF32U => trapping_unop!(f32, |a| numerics::i32_trunc_f32_u(a).map(|v| v as i32)),
+      Instr::I32TruncF64S => trapping_unop!(f64, numerics::i32_trunc_f64_s),
+      Instr::I32TruncF64U => trapping_unop!(f64, |a| numerics::i32_trunc_f64_u(a).map(|v| v as i32)),
+      Instr::I32TruncSatF32S => unop!(f32, |a| a as i32),
+      Instr::I32TruncSatF32U => unop!(f32, |a| a as u32 as i32),
+      Instr::I32TruncSatF64S => unop!(f64, |a| a as i32),
+      Instr::I32TruncSatF64U => unop!(f64, |a| a as u32 as i32),
+      Instr::I64ExtendI32S => unop!(i32, i64::from),
+      Instr::I64ExtendI32U => unop!(i32, |a| i64::from(a as u32)),
+      Instr::I64TruncF32S => trapping_unop!(f32, numerics::i64_trunc_f32_s),
+      Instr::I64TruncF32U => trapping_unop!(f32, |a| numerics::i64_trunc_f32_u(a).map(|v| v as i64)),
+      Instr::I64TruncF64S => trapping_unop!(f64, numerics::i64_trunc_f64_s),
+      Instr::I64TruncF64U => trapping_unop!(f64, |a| numerics::i64_trunc_f64_u(a).map(|v| v as i64)),
+      Instr::I64TruncSatF32S => unop!(f32, |a| a as i64),
+      Instr::I64TruncSatF32U => unop!(f32, |a| a as u64 as i64),
+      Instr::I64TruncSatF64S => unop!(f64, |a| a as i64),
+      Instr::I64TruncSatF64U => unop!(f64, |a| a as u64 as i64),
+      Instr::F32ConvertI32S => unop!(i32, |a| a as f32),
+      Instr::F32ConvertI32U => unop!(i32, |a| a as u32 as f32),
+      Instr::F32ConvertI64S => unop!(i64, |a| a as f32),
+      Instr::F32ConvertI64U => unop!(i64, |a| a as u64 as f32),
+      Instr::F32DemoteF64 => funop!(f64, |a| (a as f32).quiet()),
+      Instr::F64ConvertI32S => unop!(i32, f64::from),
+      Instr::F64ConvertI32U => unop!(i32, |a| f64::from(a as u32)),
+      Instr::F64ConvertI64S => unop!(i64, |a| a as f64),
+      Instr::F64ConvertI64U => unop!(i64, |a| a as u64 as f64),
+      Instr::F64PromoteF32 => funop!(f32, |a| f64::from(a).quiet()),
+      Instr::I32ReinterpretF32 => unop!(f32, |a: f32| a.to_bits() as i32),
+      Instr::I64ReinterpretF64 => unop!(f64, |a: f64| a.to_bits() as i64),
+      Instr::F32ReinterpretI32 => unop!(i32, |a| f32::from_bits(a as u32)),
+      Instr::F64ReinterpretI64 => unop!(i64, |a| f64::from_bits(a as u64)),
+
+      Instr::I32Extend8S => unop!(i32, |a| i32::from(a as i8)),
+      Instr::I32Extend16S => unop!(i32, |a| i32::from(a as i16)),
+      Instr::I64Extend8S => unop!(i64, |a| i64::from(a as i8)),
+      Instr::I64Extend16S => unop!(i64, |a| i64::from(a as i16)),
+      Instr::I64Extend32S => unop!(i64, |a| i64::from(a as i32)),
+
+      // vector instructions
+      Instr::V128Load(mem_idx, offset, _) => load!(mem_idx, offset, 16, V128Value::from_le_bytes),
+      Instr::V128Load8X8S(mem_idx, offset, _) => load_extend!(mem_idx, offset, [i8; 16] => [i16; 8]),
+      Instr::V128Load8X8U(mem_idx, offset, _) => load_extend!(mem_idx, offset, [u8; 16] => [u16; 8]),
+      Instr::V128Load16X4S(mem_idx, offset, _) => load_extend!(mem_idx, offset, [i16; 8] => [i32; 4]),
+      Instr::V128Load16X4U(mem_idx, offset, _) => load_extend!(mem_idx, offset, [u16; 8] => [u32; 4]),
+      Instr::V128Load32X2S(mem_idx, offset, _) => load_extend!(mem_idx, offset, [i32; 4] => [i64; 2]),
+      Instr::V128Load32X2U(mem_idx, offset, _) => load_extend!(mem_idx, offset, [u32; 4] => [u64; 2]),
+      Instr::V128Load8Splat(mem_idx, offset, _) => load!(mem_idx, offset, 1, simd::splat_bytes),
+      Instr::V128Load16Splat(mem_idx, offset, _) => load!(mem_idx, offset, 2, simd::splat_bytes),
+      Instr::V128Load32Splat(mem_idx, offset, _) => load!(mem_idx, offset, 4, simd::splat_bytes),
+      Instr::V128Load64Splat(mem_idx, offset, _) => load!(mem_idx, offset, 8, simd::splat_bytes),
+      Instr::V128Load32Zero(mem_idx, offset, _) => load!(mem_idx, offset, 4, simd::zero_extend_bytes),
+      Instr::V128Load64Zero(mem_idx, offset, _) => load!(mem_idx, offset, 8, simd::zero_extend_bytes),
+      Instr::V128Store(mem_idx, offset, _) => store!(mem_idx, offset, V128Value, V128Value::to_le_bytes),
+      Instr::V128Load8Lane(mem_idx, offset, _, lane) => load_lane!(mem_idx, offset, lane, 1),
+      Instr::V128Load16Lane(mem_idx, offset, _, lane) => load_lane!(mem_idx, offset, lane, 2),
+      Instr::V128Load32Lane(mem_idx, offset, _, lane) => load_lane!(mem_idx, offset, lane, 4),
+      Instr::V128Load64Lane(mem_idx, offset, _, lane) => load_lane!(mem_idx, offset, lane, 8),
+      Instr::V128Store8Lane(mem_idx, offset, _, lane) => store_lane!(mem_idx, offset, lane, 1),
+      Instr::V128Store16Lane(mem_idx, offset, _, lane) => store_lane!(mem_idx, offset, lane, 2),
+      Instr::V128Store32Lane(mem_idx, offset, _, lane) => store_lane!(mem_idx, offset, lane, 4),
+      Instr::V128Store64Lane(mem_idx, offset, _, lane) => store_lane!(mem_idx, offset, lane, 8),
+
+      Instr::V128Const(val) => push!(*val),
+      Instr::I8X16Shuffle(lanes) => {
+        let b: [u8; 16] = pop!(V128Value).lanes();
+        let a: [u8; 16] = pop!(V128Value).lanes();
+        push!(V128Value::from_lanes(simd::shuffle(a, b, lanes)));
+      }
+      Instr::I8X16Swizzle => vbinconv!([u8; 16] => [u8; 16], simd::swizzle),
+
+      Instr::I8X16Splat => vsplat!(i32, [i8; 16], |x| x as i8),
+      Instr::I16X8Splat => vsplat!(i32, [i16; 8], |x| x as i16),
+      Instr::I32X4Splat => vsplat!(i32, [i32; 4], |x| x),
+      Instr::I64X2Splat => vsplat!(i64, [i64; 2], |x| x),
+      Instr::F32X4Splat => vsplat!(f32, [f32; 4], |x| x),
+      Instr::F64X2Splat => vsplat!(f64, [f64; 2], |x| x),
+
+      Instr::I8X16ExtractLaneS(lane) => vextract!([i8; 16], lane, i32::from),
+      Instr::I8X16ExtractLaneU(lane) => vextract!([u8; 16], lane, i32::from),
+      Instr::I8X16ReplaceLane(lane) => vreplace!(i32, [i8; 16], lane, |x| x as i8),
+      Instr::I16X8ExtractLaneS(lane) => vextract!([i16; 8], lane, i32::from),
+      Instr::I16X8ExtractLaneU(lane) => vextract!([u16; 8], lane, i32::from),
+      Instr::I16X8ReplaceLane(lane) => vreplace!(i32, [i16; 8], lane, |x| x as i16),
+      Instr::I32X4ExtractLane(lane) => vextract!([i32; 4], lane, |x| x),
+      Instr::I32X4ReplaceLane(lane) => vreplace!(i32, [i32; 4], lane, |x| x),
+      Instr::I64X2ExtractLane(lane) => vextract!([i64; 2], lane, |x| x),
+      Instr::I64X2ReplaceLane(lane) => vreplace!(i64, [i64; 2], lane, |x| x),
+      Instr::F32X4ExtractLane(lane) => vextract!([f32; 4], lane, |x| x),
+      Instr::F32X4ReplaceLane(lane) => vreplace!(f32, [f32; 4], lane, |x| x),
+      Instr::F64X2ExtractLane(lane) => vextract!([f64; 2], lane, |x| x),
+      Instr::F64X2ReplaceLane(lane) => vreplace!(f64, [f64; 2], lane, |x| x),
+
+      Instr::I8X16Eq => vrelop!([i8; 16], i8, |a, b| a == b),
+      Instr::I8X16Ne => vrelop!([i8; 16], i8, |a, b| a != b),
+      Instr::I8X16LtS => vrelop!([i8; 16], i8, |a, b| a < b),
+      Instr::I8X16LtU => vrelop!([u8; 16], i8, |a, b| a < b),
+      Instr::I8X16GtS => vrelop!([i8; 16], i8, |a, b| a > b),
+      Instr::I8X16GtU => vrelop!([u8; 16], i8, |a, b| a > b),
+      Instr::I8X16LeS => vrelop!([i8; 16], i8, |a, b| a <= b),
+      Instr::I8X16LeU => vrelop!([u8; 16], i8, |a, b| a <= b),
+      Instr::I8X16GeS => vrelop!([i8; 16], i8, |a, b| a >= b),
+      Instr::I8X16GeU => vrelop!([u8; 16], i8, |a, b| a >= b),
+
+      Instr::I16X8Eq => vrelop!([i16; 8], i16, |a, b| a == b),
+      Instr::I16X8Ne => vrelop!([i16; 8], i16, |a, b| a != b),
+      Instr::I16X8LtS => vrelop!([i16; 8], i16, |a, b| a < b),
+      Instr::I16X8LtU => vrelop!([u16; 8], i16, |a, b| a < b),
+      Instr::I16X8GtS => vrelop!([i16; 8], i16, |a, b| a > b),
+      Instr::I16X8GtU => vrelop!([u16; 8], i16, |a, b| a > b),
+      Instr::I16X8LeS => vrelop!([i16; 8], i16, |a, b| a <= b),
+      Instr::I16X8LeU => vrelop!([u16; 8], i16, |a, b| a <= b),
+      Instr::I16X8GeS => vrelop!([i16; 8], i16, |a, b| a >= b),
+      Instr::I16X8GeU => vrelop!([u16; 8], i16, |a, b| a >= b),
+
+      Instr::I32X4Eq => vrelop!([i32; 4], i32, |a, b| a == b),
+      Instr::I32X4Ne => vrelop!([i32; 4], i32, |a, b| a != b),
+      Instr::I32X4LtS => vrelop!([i32; 4], i32, |a, b| a < b),
+      Instr::I32X4LtU => vrelop!([u32; 4], i32, |a, b| a < b),
+      Instr::I32X4GtS => vrelop!([i32; 4], i32, |a, b| a > b),
+      Instr::I32X4GtU => vrelop!([u32; 4], i32, |a, b| a > b),
+      Instr::I32X4LeS => vrelop!([i32; 4], i32, |a, b| a <= b),
+      Instr::I32X4LeU => vrelop!([u32; 4], i32, |a, b| a <= b),
+      Instr::I32X4GeS => vrelop!([i32; 4], i32, |a, b| a >= b),
+      Instr::I32X4GeU => vrelop!([u32; 4], i32, |a, b| a >= b),
+
+      Instr::I64X2Eq => vrelop!([i64; 2], i64, |a, b| a == b),
+      Instr::I64X2Ne => vrelop!([i64; 2], i64, |a, b| a != b),
+      Instr::I64X2LtS => vrelop!([i64; 2], i64, |a, b| a < b),
+      Instr::I64X2GtS => vrelop!([i64; 2], i64, |a, b| a > b),
+      Instr::I64X2LeS => vrelop!([i64; 2], i64, |a, b| a <= b),
+      Instr::I64X2GeS => vrelop!([i64; 2], i64, |a, b| a >= b),
+
+      Instr::F32X4Eq => vrelop!([f32; 4], i32, |a, b| a == b),
+      Instr::F32X4Ne => vrelop!([f32; 4], i32, |a, b| a != b),
+      Instr::F32X4Lt => vrelop!([f32; 4], i32, |a, b| a < b),
+      Instr::F32X4Gt => vrelop!([f32; 4], i32, |a, b| a > b),
+      Instr::F32X4Le => vrelop!([f32; 4], i32, |a, b| a <= b),
+      Instr::F32X4Ge => vrelop!([f32; 4], i32, |a, b| a >= b),
+
+      Instr::F64X2Eq => vrelop!([f64; 2], i64, |a, b| a == b),
+      Instr::F64X2Ne => vrelop!([f64; 2], i64, |a, b| a != b),
+      Instr::F64X2Lt => vrelop!([f64; 2], i64, |a, b| a < b),
+      Instr::F64X2Gt => vrelop!([f64; 2], i64, |a, b| a > b),
+      Instr::F64X2Le => vrelop!([f64; 2], i64, |a, b| a <= b),
+      Instr::F64X2Ge => vrelop!([f64; 2], i64, |a, b| a >= b),
+
+      Instr::V128Not => vunop!([u64; 2], |a: u64| !a),
+      Instr::V128And => vbinop!([u64; 2], |a, b| a & b),
+      Instr::V128Andnot => vbinop!([u64; 2], |a, b| a & !b),
+      Instr::V128Or => vbinop!([u64; 2], |a, b| a | b),
+      Instr::V128Xor => vbinop!([u64; 2], |a, b| a ^ b),
+      Instr::V128Bitselect => {
+        let mask = pop!(V128Value).to_bits();
+        let b = pop!(V128Value).to_bits();
+        let a = pop!(V128Value).to_bits();
+        push!(V128Value::from_bits((a & mask) | (b & !mask)));
+      }
+      Instr::V128AnyTrue => {
+        let a = pop!(V128Value);
+        push!(i32::from(a.to_bits() != 0));
+      }
+
+      Instr::I8X16Abs => vunop!([i8; 16], i8::wrapping_abs),
+      Instr::I8X16Neg => vunop!([i8; 16], i8::wrapping_neg),
+      Instr::I8X16Popcnt => vunop!([u8; 16], |a: u8| a.count_ones() as u8),
+      Instr::I8X16AllTrue => vtest!([i8; 16], |a: [i8; 16]| a.iter().all(|lane| *lane != 0)),
+      Instr::I8X16Bitmask => vtest!([i8; 16], simd::bitmask),
+      Instr::I8X16NarrowI16X8S => vbinconv!([i16; 8] => [i8; 16], simd::i8x16_narrow_i16x8_s),
+      Instr::I8X16NarrowI16X8U => vbinconv!([i16; 8] => [u8; 16], simd::i8x16_narrow_i16x8_u),
+      Instr::I8X16Shl => vshift!([i8; 16], i8::wrapping_shl),
+      Instr::I8X16ShrS => vshift!([i8; 16], i8::wrapping_shr),
+      Instr::I8X16ShrU => vshift!([u8; 16], u8::wrapping_shr),
+      Instr::I8X16Add => vbinop!([i8; 16], i8::wrapping_add),
+      Instr::I8X16AddSatS => vbinop!([i8; 16], i8::saturating_add),
+      Instr::I8X16AddSatU => vbinop!([u8; 16], u8::saturating_add),
+      Instr::I8X16Sub => vbinop!([i8; 16], i8::wrapping_sub),
+      Instr::I8X16SubSatS => vbinop!([i8; 16], i8::saturating_sub),
+      Instr::I8X16SubSatU => vbinop!([u8; 16], u8::saturating_sub),
+      Instr::I8X16MinS => vbinop!([i8; 16], i8::min),
+      Instr::I8X16MinU => vbinop!([u8; 16], u8::min),
+      Instr::I8X16MaxS => vbinop!([i8; 16], i8::max),
+      Instr::I8X16MaxU => vbinop!([u8; 16], u8::max),
+      Instr::I8X16AvgrU => vbinop!([u8; 16], |a, b| (u16::from(a) + u16::from(b)).div_ceil(2) as u8),
+
+      Instr::I16X8Abs => vunop!([i16; 8], i16::wrapping_abs),
+      Instr::I16X8Neg => vunop!([i16; 8], i16::wrapping_neg),
+      Instr::I16X8AllTrue => vtest!([i16; 8], |a: [i16; 8]| a.iter().all(|lane| *lane != 0)),
+      Instr::I16X8Bitmask => vtest!([i16; 8], simd::bitmask),
+      Instr::I16X8NarrowI32X4S => vbinconv!([i32; 4] => [i16; 8], simd::i16x8_narrow_i32x4_s),
+      Instr::I16X8NarrowI32X4U => vbinconv!([i32; 4] => [u16; 8], simd::i16x8_narrow_i32x4_u),
+      Instr::I16X8ExtendLowI8X16S => vconv!([i8; 16] => [i16; 8], |a| simd::extend(a, false)),
+      Instr::I16X8ExtendHighI8X16S => vconv!([i8; 16] => [i16; 8], |a| simd::extend(a, true)),
+      Instr::I16X8ExtendLowI8X16U => vconv!([u8; 16] => [u16; 8], |a| simd::extend(a, false)),
+      Instr::I16X8ExtendHighI8X16U => vconv!([u8; 16] => [u16; 8], |a| simd::extend(a, true)),
+      Instr::I16X8Shl => vshift!([i16; 8], i16::wrapping_shl),
+      Instr::I16X8ShrS => vshift!([i16; 8], i16::wrapping_shr),
+      Instr::I16X8ShrU => vshift!([u16; 8], u16::wrapping_shr),
+      Instr::I16X8Add => vbinop!([i16; 8], i16::wrapping_add),
+      Instr::I16X8AddSatS => vbinop!([i16; 8], i16::saturating_add),
+      Instr::I16X8AddSatU => vbinop!([u16; 8], u16::saturating_add),
+      Instr::I16X8Sub => vbinop!([i16; 8], i16::wrapping_sub),
+      Instr::I16X8SubSatS => vbinop!([i16; 8], i16::saturating_sub),
+      Instr::I16X8SubSatU => vbinop!([u16; 8], u16::saturating_sub),
+      Instr::I16X8Mul => vbinop!([i16; 8], i16::wrapping_mul),
+      Instr::I16X8MinS => vbinop!([i16; 8], i16::min),
+      Instr::I16X8MinU => vbinop!([u16; 8], u16::min),
+      Instr::I16X8MaxS => vbinop!([i16; 8], i16::max),
+      Instr::I16X8MaxU => vbinop!([u16; 8], u16::max),
+      Instr::I16X8AvgrU => vbinop!([u16; 8], |a, b| (u32::from(a) + u32::from(b)).div_ceil(2) as u16),
+      Instr::I16X8Q15MulrSatS => vbinconv!([i16; 8] => [i16; 8], simd::i16x8_q15mulr_sat_s),
+      Instr::I16X8ExtmulLowI8X16S => vbinconv!([i8; 16] => [i16; 8], |a, b| simd::extmul(a, b, false)),
+      Instr::I16X8ExtmulHighI8X16S => vbinconv!([i8; 16] => [i16; 8], |a, b| simd::extmul(a, b, true)),
+      Instr::I16X8ExtmulLowI8X16U => vbinconv!([u8; 16] => [u16; 8], |a, b| simd::extmul(a, b, false)),
+      Instr::I16X8ExtmulHighI8X16U => vbinconv!([u8; 16] => [u16; 8], |a, b| simd::extmul(a, b, true)),
+      Instr::I16X8ExtaddPairwiseI8X16S => vconv!([i8; 16] => [i16; 8], simd::extadd_pairwise),
+      Instr::I16X8ExtaddPairwiseI8X16U => vconv!([u8; 16] => [u16; 8], simd::extadd_pairwise),
+
+      Instr::I32X4Abs => vunop!([i32; 4], i32::wrapping_abs),
+      Instr::I32X4Neg => vunop!([i32; 4], i32::wrapping_neg),
+      Instr::I32X4AllTrue => vtest!([i32; 4], |a: [i32; 4]| a.iter().all(|lane| *lane != 0)),
+      Instr::I32X4Bitmask => vtest!([i32; 4], simd::bitmask),
+      Instr::I32X4ExtaddPairwiseI16X8S => vconv!([i16; 8] => [i32; 4], simd::extadd_pairwise),
+      Instr::I32X4ExtaddPairwiseI16X8U => vconv!([u16; 8] => [u32; 4], simd::extadd_pairwise),
+      Instr::I32X4ExtendLowI16X8S => vconv!([i16; 8] => [i32; 4], |a| simd::extend(a, false)),
+      Instr::I32X4ExtendHighI16X8S => vconv!([i16; 8] => [i32; 4], |a| simd::extend(a, true)),
+      Instr::I32X4ExtendLowI16X8U => vconv!([u16; 8] => [u32; 4], |a| simd::extend(a, false)),
+      Instr::I32X4ExtendHighI16X8U => vconv!([u16; 8] => [u32; 4], |a| simd::extend(a, true)),
+      Instr::I32X4Shl => vshift!([i32; 4], i32::wrapping_shl),
+      Instr::I32X4ShrS => vshift!([i32; 4], i32::wrapping_shr),
+      Instr::I32X4ShrU => vshift!([u32; 4], u32::wrapping_shr),
+      Instr::I32X4Add => vbinop!([i32; 4], i32::wrapping_add),
+      Instr::I32X4Sub => vbinop!([i32; 4], i32::wrapping_sub),
+      Instr::I32X4Mul => vbinop!([i32; 4], i32::wrapping_mul),
+      Instr::I32X4MinS => vbinop!([i32; 4], i32::min),
+      Instr::I32X4MinU => vbinop!([u32; 4], u32::min),
+      Instr::I32X4MaxS => vbinop!([i32; 4], i32::max),
+      Instr::I32X4MaxU => vbinop!([u32; 4], u32::max),
+      Instr::I32X4DotI16X8S => vbinconv!([i16; 8] => [i32; 4], simd::i32x4_dot_i16x8_s),
+      Instr::I32X4ExtmulLowI16X8S => vbinconv!([i16; 8] => [i32; 4], |a, b| simd::extmul(a, b, false)),
+      Instr::I32X4ExtmulHighI16X8S => vbinconv!([i16; 8] => [i32; 4], |a, b| simd::extmul(a, b, true)),
+      Instr::I32X4ExtmulLowI16X8U => vbinconv!([u16; 8] => [u32; 4], |a, b| simd::extmul(a, b, false)),
+      Instr::I32X4ExtmulHighI16X8U => vbinconv!([u16; 8] => [u32; 4], |a, b| simd::extmul(a, b, true)),
+
+      Instr::I64X2Abs => vunop!([i64; 2], i64::wrapping_abs),
+      Instr::I64X2Neg => vunop!([i64; 2], i64::wrapping_neg),
+      Instr::I64X2AllTrue => vtest!([i64; 2], |a: [i64; 2]| a.iter().all(|lane| *lane != 0)),
+      Instr::I64X2Bitmask => vtest!([i64; 2], simd::bitmask),
+      Instr::I64X2ExtendLowI32X4S => vconv!([i32; 4] => [i64; 2], |a| simd::extend(a, false)),
+      Instr::I64X2ExtendHighI32X4S => vconv!([i32; 4] => [i64; 2], |a| simd::extend(a, true)),
+      Instr::I64X2ExtendLowI32X4U => vconv!([u32; 4] => [u64; 2], |a| simd::extend(a, false)),
+      Instr::I64X2ExtendHighI32X4U => vconv!([u32; 4] => [u64; 2], |a| simd::extend(a, true)),
+      Instr::I64X2Shl => vshift!([i64; 2], i64::wrapping_shl),
+      Instr::I64X2ShrS => vshift!([i64; 2], i64::wrapping_shr),
+      Instr::I64X2ShrU => vshift!([u64; 2], u64::wrapping_shr),
+      Instr::I64X2Add => vbinop!([i64; 2], i64::wrapping_add),
+      Instr::I64X2Sub => vbinop!([i64; 2], i64::wrapping_sub),
+      Instr::I64X2Mul => vbinop!([i64; 2], i64::wrapping_mul),
+      Instr::I64X2ExtmulLowI32X4S => vbinconv!([i32; 4] => [i64; 2], |a, b| simd::extmul(a, b, false)),
+      Instr::I64X2ExtmulHighI32X4S => vbinconv!([i32; 4] => [i64; 2], |a, b| simd::extmul(a, b, true)),
+      Instr::I64X2ExtmulLowI32X4U => vbinconv!([u32; 4] => [u64; 2], |a, b| simd::extmul(a, b, false)),
+      Instr::I64X2ExtmulHighI32X4U => vbinconv!([u32; 4] => [u64; 2], |a, b| simd::extmul(a, b, true)),
+
+      Instr::F32X4Abs => vunop!([f32; 4], f32::abs),
+      Instr::F32X4Neg => vunop!([f32; 4], |a: f32| -a),
+      Instr::F32X4Sqrt => vfunop!([f32; 4], f32::fsqrt),
+      Instr::F32X4Ceil => vfunop!([f32; 4], f32::fceil),
+      Instr::F32X4Floor => vfunop!([f32; 4], f32::ffloor),
+      Instr::F32X4Trunc => vfunop!([f32; 4], f32::ftrunc),
+      Instr::F32X4Nearest => vfunop!([f32; 4], f32::fnearest),
+      Instr::F32X4Add => vfbinop!([f32; 4], |a, b| a + b),
+      Instr::F32X4Sub => vfbinop!([f32; 4], |a, b| a - b),
+      Instr::F32X4Mul => vfbinop!([f32; 4], |a, b| a * b),
+      Instr::F32X4Div => vfbinop!([f32; 4], |a, b| a / b),
+      Instr::F32X4Min => vfbinop!([f32; 4], f32::fmin),
+      Instr::F32X4Max => vfbinop!([f32; 4], f32::fmax),
+      // the pseudo-minimum and maximum return one of their operands unchanged, as `b < a ? b : a` does
+      Instr::F32X4Pmin => vbinop!([f32; 4], |a: f32, b: f32| if b < a { b } else { a }),
+      Instr::F32X4Pmax => vbinop!([f32; 4], |a: f32, b: f32| if a < b { b } else { a }),
+
+      Instr::F64X2Abs => vunop!([f64; 2], f64::abs),
+      Instr::F64X2Neg => vunop!([f64; 2], |a: f64| -a),
+      Instr::F64X2Sqrt => vfunop!([f64; 2], f64::fsqrt),
+      Instr::F64X2Ceil => vfunop!([f64; 2], f64::fceil),
+      Instr::F64X2Floor => vfunop!([f64; 2], f64::ffloor),
+      Instr::F64X2Trunc => vfunop!([f64; 2], f64::ftrunc),
+      Instr::F64X2Nearest => vfunop!([f64; 2], f64::fnearest),
+      Instr::F64X2Add => vfbinop!([f64; 2], |a, b| a + b),
+      Instr::F64X2Sub => vfbinop!([f64; 2], |a, b| a - b),
+      Instr::F64X2Mul => vfbinop!([f64; 2], |a, b| a * b),
+      Instr::F64X2Div => vfbinop!([f64; 2], |a, b| a / b),
+      Instr::F64X2Min => vfbinop!([f64; 2], f64::fmin),
+      Instr::F64X2Max => vfbinop!([f64; 2], f64::fmax),
+      // the pseudo-minimum and maximum return one of their operands unchanged, as `b < a ? b : a` does
+      Instr::F64X2Pmin => vbinop!([f64; 2], |a: f64, b: f64| if b < a { b } else { a }),
+      Instr::F64X2Pmax => vbinop!([f64; 2], |a: f64, b: f64| if a < b { b } else { a }),
+
+      Instr::I32X4TruncSatF32X4S => vconv!([f32; 4] => [i32; 4], |a: [f32; 4]| a.map(|x| x as i32)),
+      Instr::I32X4TruncSatF32X4U => vconv!([f32; 4] => [u32; 4], |a: [f32; 4]| a.map(|x| x as u32)),
+      Instr::I32X4TruncSatF64X2SZero => vconv!([f64; 2] => [i32; 4], |a: [f64; 2]| [a[0] as i32, a[1] as i32, 0, 0]),
+      Instr::I32X4TruncSatF64X2UZero => vconv!([f64; 2] => [u32; 4], |a: [f64; 2]| [a[0] as u32, a[1] as u32, 0, 0]),
+      Instr::F32X4ConvertI32X4S => vconv!([i32; 4] => [f32; 4], |a: [i32; 4]| a.map(|x| x as f32)),
+      Instr::F32X4ConvertI32X4U => vconv!([u32; 4] => [f32; 4], |a: [u32; 4]| a.map(|x| x as f32)),
+      Instr::F64X2ConvertLowI32X4S => vconv!([i32; 4] => [f64; 2], |a: [i32; 4]| [a[0], a[1]].map(f64::from)),
+      Instr::F64X2ConvertLowI32X4U => vconv!([u32; 4] => [f64; 2], |a: [u32; 4]| [a[0], a[1]].map(f64::from)),
+      Instr::F32X4DemoteF64X2Zero => vconv!([f64; 2] => [f32; 4], |a: [f64; 2]| {
+        let [x, y] = a.map(|x| canonical!((x as f32).quiet()));
+        [x, y, 0.0, 0.0]
+      }),
+      Instr::F64X2PromoteLowF32X4 => vconv!([f32; 4] => [f64; 2], |a: [f32; 4]| {
+        [a[0], a[1]].map(|x| canonical!(f64::from(x).quiet()))
+      }),
+
+      Instr::I8X16RelaxedSwizzle => {
+        vbinconv!([u8; 16] => [u8; 16], |a, s| simd::relaxed_swizzle(a, s, x86_relaxed))
+      }
+      Instr::I32X4RelaxedTruncF32X4S => vconv!([f32; 4] => [i32; 4], |a: [f32; 4]| {
+        a.map(|x| simd::relaxed_trunc_s(x.into(), x86_relaxed))
+      }),
+      Instr::I32X4RelaxedTruncF32X4U => vconv!([f32; 4] => [u32; 4], |a: [f32; 4]| {
+        a.map(|x| simd::relaxed_trunc_u(x.into(), x86_relaxed))
+      }),
+      Instr::I32X4RelaxedTruncF64X2SZero => vconv!([f64; 2] => [i32; 4], |a: [f64; 2]| {
+        let [x, y] = a.map(|x| simd::relaxed_trunc_s(x, x86_relaxed));
+        [x, y, 0, 0]
+      }),
+      Instr::I32X4RelaxedTruncF64X2UZero => vconv!([f64; 2] => [u32; 4], |a: [f64; 2]| {
+        let [x, y] = a.map(|x| simd::relaxed_trunc_u(x, x86_relaxed));
+        [x, y, 0, 0]
+      }),
+      // the deterministic results are fused, x86 without FMA rounds the product first
+      Instr::F32X4RelaxedMadd => vfternop!([f32; 4], |a: f32, b: f32, c: f32| {
+        if x86_relaxed {
+          a * b + c
+        } else {
+          a.mul_add(b, c)
+        }
+      }),
+      Instr::F32X4RelaxedNmadd => vfternop!([f32; 4], |a: f32, b: f32, c: f32| {
+        if x86_relaxed {
+          -(a * b) + c
+        } else {
+          (-a).mul_add(b, c)
+        }
+      }),
+      Instr::F64X2RelaxedMadd => vfternop!([f64; 2], |a: f64, b: f64, c: f64| {
+        if x86_relaxed {
+          a * b + c
+        } else {
+          a.mul_add(b, c)
+        }
+      }),
+      Instr::F64X2RelaxedNmadd => vfternop!([f64; 2], |a: f64, b: f64, c: f64| {
+        if x86_relaxed {
+          -(a * b) + c
+        } else {
+          (-a).mul_add(b, c)
+        }
+      }),
+      Instr::I8X16RelaxedLaneselect => vlaneselect!(1),
+      Instr::I16X8RelaxedLaneselect => vlaneselect!(2),
+      Instr::I32X4RelaxedLaneselect => vlaneselect!(4),
+      Instr::I64X2RelaxedLaneselect => vlaneselect!(8),
+      // x86 (`minps`, `maxps`) returns the second operand if either is NaN or both are zeros
+      Instr::F32X4RelaxedMin => vfbinop!([f32; 4], |a: f32, b: f32| {
+        if !x86_relaxed {
+          a.fmin(b)
+        } else if a < b {
+          a
+        } else {
+          b
+        }
+      }),
+      Instr::F32X4RelaxedMax => vfbinop!([f32; 4], |a: f32, b: f32| {
+        if !x86_relaxed {
+          a.fmax(b)
+        } else if a > b {
+          a
+        } else {
+          b
+        }
+      }),
+      Instr::F64X2RelaxedMin => vfbinop!([f64; 2], |a: f64, b: f64| {
+        if !x86_relaxed {
+          a.fmin(b)
+        } else if a < b {
+          a
+        } else {
+          b
+        }
+      }),
+      Instr::F64X2RelaxedMax => vfbinop!([f64; 2], |a: f64, b: f64| {
+        if !x86_relaxed {
+          a.fmax(b)
+        } else if a > b {
+          a
+        } else {
+          b
+        }
+      }),
+      Instr::I16X8RelaxedQ15MulrS => {
+        vbinconv!([i16; 8] => [i16; 8], |a, b| simd::i16x8_relaxed_q15mulr_s(a, b, x86_relaxed))
+      }
+      Instr::I16X8RelaxedDotI8X16I7X16S => {
+        vbinconv!([i8; 16] => [i16; 8], |a, b| simd::i16x8_relaxed_dot_i8x16_i7x16_s(a, b, x86_relaxed))
+      }
+      Instr::I32X4RelaxedDotI8X16I7X16AddS => {
+        let c: [i32; 4] = pop!(V128Value).lanes();
+        let b: [i8; 16] = pop!(V128Value).lanes();
+        let a: [i8; 16] = pop!(V128Value).lanes();
+        push!(V128Value::from_lanes(simd::i32x4_relaxed_dot_i8x16_i7x16_add_s(
+          a,
+          b,
+          c,
+          x86_relaxed
+        )));
+      }
+    }
+
+    *pc += 1;
+  }
+}
+
+/// Invokes a function with the given arguments and collects its results.
+pub(crate) fn invoke(
+  instance: &mut ModuleInstance,
+  stack: &mut Stack,
+  func_idx: FuncIdx,
+  args: &[Value],
+) -> Result<Vec<Value>, Trap> {
+  let height = stack.operand.len();
+  for arg in args {
+    stack.operand.push(*arg);
+  }
+
+  let mut pc = 0;
+  let result = execute(instance, stack, func_idx, &mut pc)
+    .and_then(|_| stack.operand.pop_n(stack.operand.len().saturating_sub(height)));
+  result.map_err(|error| {
+    let trap = Trap::new(error, backtrace(instance, stack, func_idx, pc));
+    stack.clear();
+    trap
+  })
+}
+
+/// Describes the frames on the call stack from the innermost outwards, with the innermost one at `pc`.
+fn backtrace(instance: &ModuleInstance, stack: &Stack, func_idx: FuncIdx, pc: usize) -> Vec<FrameInfo> {
+  let n_imported = instance.host_funcs.len();
+  let func_name = |func_idx| instance.names().func_name(func_idx).map(String::from);
+
+  let mut frames = vec![];
+  let mut pc = pc;
+  for frame in stack.call.iter().rev() {
+    let body = &instance.module.functions[frame.func_idx as usize - n_imported].parsed_body;
+    frames.push(FrameInfo::new(
+      frame.func_idx,
+      func_name(frame.func_idx),
+      body.offsets.get(pc).copied(),
+    ));
+    // the caller is suspended at the call instruction just before the return position
+    pc = frame.return_pc.saturating_sub(1);
+  }
+
+  // a host function called directly, or a function that could not be entered, has no frame of its own
+  if frames.is_empty() {
+    frames.push(FrameInfo::new(func_idx, func_name(func_idx), None));
+  }
+
+  frames
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    config::Config,
+    parse::parse,
+  };
+
+  fn call(src: &[u8], func_idx: FuncIdx, args: &[Value]) -> Result<Vec<Value>, Error> {
+    call_with_stack(src, func_idx, args, &mut Stack::new())
+  }
+
+  fn call_with_stack(src: &[u8], func_idx: FuncIdx, args: &[Value], stack: &mut Stack) -> Result<Vec<Value>, Error> {
+    let module = parse(src).unwrap_or_else(|err| panic!("{err}"));
+    let mut instance = ModuleInstance::new(module, vec![], &Config::new()).unwrap_or_else(|err| panic!("{err}"));
+
+    invoke(&mut instance, stack, func_idx, args).map_err(Trap::into_error)
+  }
+
+  #[test]
+  fn test_execute_numeric() {
+    let src = include_bytes!("../tests/wasm/sample1.wasm");
+
+    assert!(matches!(
+      call(src, 0, &[Value::I32(1), Value::I32(2)]).as_deref(),
+      Ok([Value::I32(3)])
+    ));
+    assert!(matches!(
+      call(src, 3, &[Value::I64(5)]).as_deref(),
+      Ok([Value::I64(-60)])
+    ));
+    assert!(matches!(call(src, 4, &[Value::F32(1.5)]).as_deref(), Ok([Value::F32(v)]) if *v == 2.5));
+    assert!(matches!(call(src, 1, &[]).as_deref(), Ok([])));
+  }
+
+  #[test]
+  fn test_execute_traps() {
+    let src = include_bytes!("../tests/wasm/arith.wasm");
+
+    assert_eq!(
+      call(src, 0, &[Value::I32(1), Value::I32(0)]).err(),
+      Some(Error::IntegerDivideByZero)
+    );
+    assert_eq!(
+      call(src, 0, &[Value::I32(i32::MIN), Value::I32(-1)]).err(),
+      Some(Error::IntegerOverflow)
+    );
+    assert_eq!(
+      call(src, 1, &[Value::I64(1), Value::I64(0)]).err(),
+      Some(Error::IntegerDivideByZero)
+    );
+    assert_eq!(call(src, 5, &[Value::I32(0)]).err(), Some(Error::IntegerDivideByZero));
+    assert_eq!(call(src, 6, &[]).err(), Some(Error::Unreachable));
+    assert_eq!(
+      call(src, 0, &[Value::I64(1), Value::I32(1)]).err(),
+      Some(Error::TypeMismatch)
+    );
+  }
+
+  #[test]
+  fn test_execute_instrs() {
+    let src = include_bytes!("../tests/wasm/arith.wasm");
+
+    assert!(matches!(
+      call(src, 1, &[Value::I64(-1), Value::I64(10)]).as_deref(),
+      Ok([Value::I64(5)])
+    ));
+    assert!(matches!(
+      call(src, 2, &[Value::F32(0.0), Value::F32(-0.0)]).as_deref(),
+      Ok([Value::F32(v)]) if v.to_bits() == (-0.0f32).to_bits()
+    ));
+    assert!(
+      matches!(call(src, 2, &[Value::F32(f32::NAN), Value::F32(1.0)]).as_deref(), Ok([Value::F32(v)]) if v.is_nan())
+    );
+    assert!(matches!(call(src, 3, &[Value::F64(2.5)]).as_deref(), Ok([Value::F64(v)]) if *v == 2.0));
+    assert!(matches!(call(src, 3, &[Value::F64(-3.5)]).as_deref(), Ok([Value::F64(v)]) if *v == -4.0));
+    assert!(matches!(
+      call(src, 4, &[Value::I32(7), Value::I32(8), Value::I32(0)]).as_deref(),
+      Ok([Value::I32(8)])
+    ));
+    assert!(matches!(
+      call(src, 4, &[Value::I32(7), Value::I32(8), Value::I32(1)]).as_deref(),
+      Ok([Value::I32(7)])
+    ));
+    assert!(matches!(call(src, 5, &[Value::I32(9)]).as_deref(), Ok([Value::I32(1)])));
+  }
+
+  #[test]
+  fn test_execute_control() {
+    let src = include_bytes!("../tests/wasm/control.wasm");
+
+    assert!(matches!(
+      call(src, 0, &[Value::I32(10)]).as_deref(),
+      Ok([Value::I32(55)])
+    ));
+    assert!(matches!(
+      call(src, 1, &[Value::I32(0)]).as_deref(),
+      Ok([Value::I32(100)])
+    ));
+    assert!(matches!(
+      call(src, 1, &[Value::I32(1)]).as_deref(),
+      Ok([Value::I32(200)])
+    ));
+    assert!(matches!(
+      call(src, 1, &[Value::I32(42)]).as_deref(),
+      Ok([Value::I32(300)])
+    ));
+    assert!(matches!(
+      call(src, 2, &[Value::I32(-5)]).as_deref(),
+      Ok([Value::I32(-1)])
+    ));
+    assert!(matches!(call(src, 2, &[Value::I32(5)]).as_deref(), Ok([Value::I32(1)])));
+    assert!(matches!(call(src, 2, &[Value::I32(0)]).as_deref(), Ok([Value::I32(0)])));
+    assert!(matches!(
+      call(src, 3, &[Value::I32(21)]).as_deref(),
+      Ok([Value::I32(42)])
+    ));
+    assert!(matches!(call(src, 4, &[Value::I32(1)]).as_deref(), Ok([Value::I32(7)])));
+    assert!(matches!(call(src, 4, &[Value::I32(0)]).as_deref(), Ok([Value::I32(9)])));
+  }
+
+  #[test]
+  fn test_execute_recursion() {
+    let fac = include_bytes!("../tests/wasm/fac.wasm");
+    let fib = include_bytes!("../tests/wasm/fib.wasm");
+
+    assert!(matches!(call(fac, 0, &[Value::F64(5.0)]).as_deref(), Ok([Value::F64(v)]) if *v == 120.0));
+    assert!(matches!(
+      call(fib, 0, &[Value::I64(10)]).as_deref(),
+      Ok([Value::I64(89)])
+    ));
+  }
+
+  #[test]
+  fn test_execute_stack_overflow() {
+    let src = include_bytes!("../tests/wasm/control.wasm");
+
+    assert_eq!(call(src, 5, &[]).err(), Some(Error::StackOverflow));
+    assert_eq!(
+      call_with_stack(src, 5, &[], &mut Stack::with_limits(16, 8)).err(),
+      Some(Error::StackOverflow)
+    );
+    assert_eq!(
+      call_with_stack(src, 1, &[Value::I32(0)], &mut Stack::with_limits(2, 8)).err(),
+      Some(Error::StackOverflow)
+    );
+    assert!(matches!(
+      call_with_stack(src, 1, &[Value::I32(0)], &mut Stack::with_limits(3, 1)).as_deref(),
+      Ok([Value::I32(100)])
+    ));
+  }
+}
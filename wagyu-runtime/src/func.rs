@@ -0,0 +1,242 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{
+  executor::Error,
+  instance::ModuleInstance,
+  module::value::{
+    FuncIdx,
+    V128Value,
+    ValType,
+    Value,
+  },
+  trap::Trap,
+};
+
+/// A value type that can cross the boundary between the host and WebAssembly.
+pub trait WasmTy: Into<Value> + TryFrom<Value> {
+  const VALTYPE: ValType;
+}
+
+impl WasmTy for i32 {
+  const VALTYPE: ValType = ValType::I32;
+}
+
+impl WasmTy for i64 {
+  const VALTYPE: ValType = ValType::I64;
+}
+
+impl WasmTy for f32 {
+  const VALTYPE: ValType = ValType::F32;
+}
+
+impl WasmTy for f64 {
+  const VALTYPE: ValType = ValType::F64;
+}
+
+impl WasmTy for V128Value {
+  const VALTYPE: ValType = ValType::V128;
+}
+
+/// Parameters of a typed function: a single value type or a tuple of them.
+pub trait WasmParams: Sized {
+  fn valtypes() -> Vec<ValType>;
+
+  fn into_values(self) -> Vec<Value>;
+
+  fn from_values(values: &[Value]) -> Result<Self, Error>;
+}
+
+/// Results of a typed function: a single value type or a tuple of them.
+pub trait WasmResults: Sized {
+  fn valtypes() -> Vec<ValType>;
+
+  fn into_values(self) -> Vec<Value>;
+
+  fn from_values(values: Vec<Value>) -> Result<Self, Error>;
+}
+
+impl<T: WasmTy> WasmParams for T {
+  fn valtypes() -> Vec<ValType> {
+    vec![T::VALTYPE]
+  }
+
+  fn into_values(self) -> Vec<Value> {
+    vec![self.into()]
+  }
+
+  fn from_values(values: &[Value]) -> Result<Self, Error> {
+    match *values {
+      [value] => T::try_from(value).map_err(|_| Error::TypeMismatch),
+      _ => Err(Error::TypeMismatch),
+    }
+  }
+}
+
+impl<T: WasmTy> WasmResults for T {
+  fn valtypes() -> Vec<ValType> {
+    vec![T::VALTYPE]
+  }
+
+  fn into_values(self) -> Vec<Value> {
+    vec![self.into()]
+  }
+
+  fn from_values(values: Vec<Value>) -> Result<Self, Error> {
+    match values[..] {
+      [value] => T::try_from(value).map_err(|_| Error::TypeMismatch),
+      _ => Err(Error::TypeMismatch),
+    }
+  }
+}
+
+macro_rules! impl_wasm_tuple {
+  ($($ty:ident),*) => {
+    impl<$($ty: WasmTy),*> WasmParams for ($($ty,)*) {
+      fn valtypes() -> Vec<ValType> {
+        vec![$($ty::VALTYPE),*]
+      }
+
+      #[allow(non_snake_case)]
+      fn into_values(self) -> Vec<Value> {
+        let ($($ty,)*) = self;
+        vec![$($ty.into()),*]
+      }
+
+      #[allow(unused_mut, unused_variables)]
+      fn from_values(values: &[Value]) -> Result<Self, Error> {
+        let mut values = values.iter().copied();
+        let params = ($($ty::try_from(values.next().ok_or(Error::TypeMismatch)?).map_err(|_| Error::TypeMismatch)?,)*);
+
+        match values.next() {
+          Some(_) => Err(Error::TypeMismatch),
+          None => Ok(params),
+        }
+      }
+    }
+
+    impl<$($ty: WasmTy),*> WasmResults for ($($ty,)*) {
+      fn valtypes() -> Vec<ValType> {
+        vec![$($ty::VALTYPE),*]
+      }
+
+      #[allow(non_snake_case)]
+      fn into_values(self) -> Vec<Value> {
+        let ($($ty,)*) = self;
+        vec![$($ty.into()),*]
+      }
+
+      #[allow(unused_mut, unused_variables)]
+      fn from_values(values: Vec<Value>) -> Result<Self, Error> {
+        let mut values = values.into_iter();
+        let results = ($($ty::try_from(values.next().ok_or(Error::TypeMismatch)?).map_err(|_| Error::TypeMismatch)?,)*);
+
+        match values.next() {
+          Some(_) => Err(Error::TypeMismatch),
+          None => Ok(results),
+        }
+      }
+    }
+  };
+}
+
+impl_wasm_tuple!();
+impl_wasm_tuple!(A);
+impl_wasm_tuple!(A, B);
+impl_wasm_tuple!(A, B, C);
+impl_wasm_tuple!(A, B, C, D);
+impl_wasm_tuple!(A, B, C, D, E);
+impl_wasm_tuple!(A, B, C, D, E, F);
+impl_wasm_tuple!(A, B, C, D, E, F, G);
+impl_wasm_tuple!(A, B, C, D, E, F, G, H);
+
+/// A function exported by an instance, which can only be called on that instance.
+#[derive(Debug, Clone, Copy)]
+pub struct Func {
+  pub(crate) idx: FuncIdx,
+  pub(crate) instance_id: u64,
+}
+
+impl Func {
+  /// Returns the parameter and result types of the function.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`Error::ForeignFunc`] if the function was not obtained from `instance`.
+  pub fn ty<'a>(&self, instance: &'a ModuleInstance) -> Result<(&'a [ValType], &'a [ValType]), Error> {
+    let signature = instance.func_type(self)?;
+    Ok((&signature.params, &signature.results))
+  }
+
+  /// Calls the function with dynamically typed arguments.
+  ///
+  /// # Errors
+  ///
+  /// Returns a trap of [`Error::ForeignFunc`] if the function was not obtained from `instance`, of
+  /// [`Error::TypeMismatch`] if the arguments do not match the signature, or the trap that aborted execution.
+  pub fn call(&self, instance: &mut ModuleInstance, args: &[Value]) -> Result<Vec<Value>, Trap> {
+    let (params, _) = self.ty(instance)?;
+    let matches_signature =
+      params.len() == args.len() && params.iter().zip(args).all(|(param, arg)| *param == arg.valtype());
+    if !matches_signature {
+      return Err(Trap::from(Error::TypeMismatch));
+    }
+
+    instance.invoke(self.idx, args)
+  }
+
+  /// Checks the signature of the function once and returns a statically typed handle to it.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`Error::ForeignFunc`] if the function was not obtained from `instance`, or [`Error::TypeMismatch`] if
+  /// `Params` and `Results` do not match the signature of the function.
+  pub fn typed<Params: WasmParams, Results: WasmResults>(
+    &self,
+    instance: &ModuleInstance,
+  ) -> Result<TypedFunc<Params, Results>, Error> {
+    let (params, results) = self.ty(instance)?;
+    if params != Params::valtypes() || results != Results::valtypes() {
+      return Err(Error::TypeMismatch);
+    }
+
+    Ok(TypedFunc {
+      func: *self,
+      _signature: PhantomData,
+    })
+  }
+}
+
+/// A function whose signature has already been checked against `Params` and `Results`.
+pub struct TypedFunc<Params, Results> {
+  func: Func,
+  _signature: PhantomData<fn(Params) -> Results>,
+}
+
+impl<Params, Results> Clone for TypedFunc<Params, Results> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<Params, Results> Copy for TypedFunc<Params, Results> {}
+
+impl<Params: WasmParams, Results: WasmResults> TypedFunc<Params, Results> {
+  /// Calls the function.
+  ///
+  /// # Errors
+  ///
+  /// Returns a trap of [`Error::ForeignFunc`] if the function was not obtained from `instance`, or the trap that
+  /// aborted execution.
+  pub fn call(&self, instance: &mut ModuleInstance, params: Params) -> Result<Results, Trap> {
+    instance.func_type(&self.func)?;
+    let results = instance.invoke(self.func.idx, &params.into_values())?;
+
+    Ok(Results::from_values(results)?)
+  }
+
+  /// Returns the untyped function.
+  pub fn func(&self) -> Func {
+    self.func
+  }
+}
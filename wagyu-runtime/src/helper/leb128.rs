@@ -1,5 +1,10 @@
 use alloc::vec::Vec;
 
+/// Maximum number of bytes of a 64-bit integer in LEB128 encoding.
+pub const MAX_LEB128_LEN: usize = 10;
+/// Maximum number of bytes of a 32-bit integer in LEB128 encoding.
+pub const MAX_LEB128_LEN_32: usize = 5;
+
 /// Encodes an unsigned integer into a variable-length little-endian base 128 (LEB128) representation.
 ///
 /// # Arguments
@@ -19,7 +24,7 @@ use alloc::vec::Vec;
 /// let encoded = encode_uleb128(value);
 /// assert_eq!(encoded, vec![0x80, 0x01]);
 /// ```
-pub(crate) fn encode_uleb128<T: Into<u64>>(value: T) -> Vec<u8> {
+pub fn encode_uleb128<T: Into<u64>>(value: T) -> Vec<u8> {
   let mut bytes: Vec<u8> = Vec::new();
   let mut value: u64 = value.into();
 
@@ -46,7 +51,9 @@ pub(crate) fn encode_uleb128<T: Into<u64>>(value: T) -> Vec<u8> {
 ///
 /// # Returns
 ///
-/// A tuple containing the decoded value as `u64` and the count of read bytes as `usize`.
+/// A tuple containing the decoded value as `u64` and the count of read bytes as `usize`,
+/// or `None` if the bytes end before the value does, the value is longer than [`MAX_LEB128_LEN`] bytes,
+/// or the unused bits of its last byte are not zero.
 ///
 /// # Example
 ///
@@ -54,25 +61,35 @@ pub(crate) fn encode_uleb128<T: Into<u64>>(value: T) -> Vec<u8> {
 /// use wagyu_runtime::helper::leb128::decode_uleb128;
 ///
 /// let encoded: Vec<u8> = vec![0x80, 0x01];
-/// let (decoded, count) = decode_uleb128(&encoded);
+/// let (decoded, count) = decode_uleb128(&encoded).unwrap();
 /// assert_eq!(decoded, 128);
 /// assert_eq!(count, 2);
+///
+/// assert_eq!(decode_uleb128(&[0x80]), None);
 /// ```
-pub(crate) fn decode_uleb128(bytes: &[u8]) -> (u64, usize) {
-  let mut result: u64 = 0;
-  let mut shift = 0;
-  let mut count = 0;
-
-  for byte in bytes.iter() {
-    result |= ((*byte & 0x7F) as u64) << shift;
-    count += 1;
-    if *byte & 0x80 == 0 {
-      break;
-    }
-    shift += 7;
-  }
+pub fn decode_uleb128(bytes: &[u8]) -> Option<(u64, usize)> {
+  decode_unsigned(bytes, 64)
+}
 
-  (result, count)
+/// Decodes an unsigned LEB128 integer of at most 32 bits, as used for indices, counts and sizes.
+///
+/// # Returns
+///
+/// A tuple containing the decoded value as `u32` and the count of read bytes as `usize`,
+/// or `None` if the bytes end before the value does, the value is longer than [`MAX_LEB128_LEN_32`] bytes,
+/// or the unused bits of its last byte are not zero.
+///
+/// # Example
+///
+/// ```
+/// use wagyu_runtime::helper::leb128::decode_u32;
+///
+/// assert_eq!(decode_u32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]), Some((u32::MAX, 5)));
+/// assert_eq!(decode_u32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x1F]), None);
+/// assert_eq!(decode_u32(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]), None);
+/// ```
+pub fn decode_u32(bytes: &[u8]) -> Option<(u32, usize)> {
+  decode_unsigned(bytes, 32).map(|(value, count)| (value as u32, count))
 }
 
 /// Encodes a signed 64-bit integer into a byte vector using Signed Little-Endian Base 128 (LEB128) encoding.
@@ -94,7 +111,7 @@ pub(crate) fn decode_uleb128(bytes: &[u8]) -> (u64, usize) {
 /// let encoded = encode_sleb128(value);
 /// assert_eq!(encoded, vec![0x9B, 0xF1, 0x59]);
 /// ```
-pub(crate) fn encode_sleb128<T: Into<i64>>(value: T) -> Vec<u8> {
+pub fn encode_sleb128<T: Into<i64>>(value: T) -> Vec<u8> {
   let mut bytes = Vec::new();
   let mut value: i64 = value.into();
 
@@ -102,7 +119,7 @@ pub(crate) fn encode_sleb128<T: Into<i64>>(value: T) -> Vec<u8> {
     let mut byte = (value as u8) & 0x7F;
     value >>= 7;
 
-    let more = !((((value == 0) && ((byte & 0x40) == 0)) || ((value == -1) && ((byte & 0x40) != 0))) as bool) as u8;
+    let more = !(((value == 0) && ((byte & 0x40) == 0)) || ((value == -1) && ((byte & 0x40) != 0))) as u8;
 
     byte |= more << 7;
     bytes.push(byte);
@@ -124,7 +141,9 @@ pub(crate) fn encode_sleb128<T: Into<i64>>(value: T) -> Vec<u8> {
 ///
 /// # Returns
 ///
-/// A tuple containing the decoded `i64` value and the count of read bytes.
+/// A tuple containing the decoded `i64` value and the count of read bytes,
+/// or `None` if the bytes end before the value does, the value is longer than [`MAX_LEB128_LEN`] bytes,
+/// or the unused bits of its last byte do not repeat its sign bit.
 ///
 /// # Examples
 ///
@@ -132,31 +151,88 @@ pub(crate) fn encode_sleb128<T: Into<i64>>(value: T) -> Vec<u8> {
 /// use wagyu_runtime::helper::leb128::decode_sleb128;
 ///
 /// let encoded: Vec<u8> = vec![0x9B, 0xF1, 0x59]; // Example encoded bytes
-/// let (decoded, count) = decode_sleb128(&encoded);
+/// let (decoded, count) = decode_sleb128(&encoded).unwrap();
 /// assert_eq!(decoded, -624485);
 /// assert_eq!(count, 3);
+///
+/// assert_eq!(decode_sleb128(&[0xFF, 0xFF]), None);
 /// ```
-pub(crate) fn decode_sleb128(bytes: &[u8]) -> (i64, usize) {
-  let mut result: i64 = 0;
-  let mut shift: usize = 0;
-  let mut count: usize = 0;
-
-  for byte in bytes.iter() {
-    let value = (*byte & 0x7F) as i64;
-    result |= value << shift;
-    shift += 7;
-    count += 1;
-    if (*byte & 0x80) == 0 {
-      break;
+pub fn decode_sleb128(bytes: &[u8]) -> Option<(i64, usize)> {
+  decode_signed(bytes, 64)
+}
+
+/// Decodes a signed LEB128 integer of at most 32 bits, as used by `i32.const`.
+///
+/// # Returns
+///
+/// A tuple containing the decoded value as `i32` and the count of read bytes as `usize`,
+/// or `None` if the bytes end before the value does, the value is longer than [`MAX_LEB128_LEN_32`] bytes,
+/// or the unused bits of its last byte do not repeat its sign bit.
+///
+/// # Example
+///
+/// ```
+/// use wagyu_runtime::helper::leb128::decode_s32;
+///
+/// assert_eq!(decode_s32(&[0x80, 0x80, 0x80, 0x80, 0x78]), Some((i32::MIN, 5)));
+/// assert_eq!(decode_s32(&[0x80, 0x80, 0x80, 0x80, 0x08]), None);
+/// assert_eq!(decode_s32(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F]), None);
+/// ```
+pub fn decode_s32(bytes: &[u8]) -> Option<(i32, usize)> {
+  decode_signed(bytes, 32).map(|(value, count)| (value as i32, count))
+}
+
+/// Decodes an unsigned integer of `bits` bits, whose encoding is at most `ceil(bits / 7)` bytes long and whose last
+/// byte may only use the bits that fit in the value.
+fn decode_unsigned(bytes: &[u8], bits: usize) -> Option<(u64, usize)> {
+  let max_len = bits.div_ceil(7);
+  let mut result: u64 = 0;
+
+  for (count, byte) in bytes.iter().take(max_len).enumerate() {
+    let shift = count * 7;
+    let payload = (*byte & 0x7F) as u64;
+    if count + 1 == max_len && payload >> (bits - shift) != 0 {
+      return None;
+    }
+
+    result |= payload << shift;
+    if *byte & 0x80 == 0 {
+      return Some((result, count + 1));
     }
   }
 
-  // Sign extend the result if the last byte has its sign bit set
-  if (bytes[count - 1] & 0x40) != 0 {
-    result |= -(1 << shift);
+  None
+}
+
+/// Decodes a signed integer of `bits` bits, whose encoding is at most `ceil(bits / 7)` bytes long and whose last
+/// byte must repeat the sign bit of the value in its unused bits.
+fn decode_signed(bytes: &[u8], bits: usize) -> Option<(i64, usize)> {
+  let max_len = bits.div_ceil(7);
+  let mut result: i64 = 0;
+
+  for (count, byte) in bytes.iter().take(max_len).enumerate() {
+    let shift = count * 7;
+    let payload = (*byte & 0x7F) as i64;
+    if count + 1 == max_len {
+      // the sign bit and the unused bits above it are all zeros or all ones
+      let high_bits = payload >> (bits - shift - 1);
+      if high_bits != 0 && high_bits != 0x7F >> (bits - shift - 1) {
+        return None;
+      }
+    }
+
+    result |= payload << shift;
+    if (*byte & 0x80) == 0 {
+      // Sign extend the result if the last byte has its sign bit set
+      if (*byte & 0x40) != 0 && shift + 7 < 64 {
+        result |= -1 << (shift + 7);
+      }
+
+      return Some((result, count + 1));
+    }
   }
 
-  (result, count)
+  None
 }
 
 #[cfg(test)]
@@ -184,10 +260,35 @@ mod tests {
     ];
 
     for (input, (expected_value, expected_count)) in test_cases {
-      let (decoded_value, decoded_count) = decode_uleb128(&input);
+      let (decoded_value, decoded_count) = decode_uleb128(&input).unwrap();
       assert_eq!(decoded_value, expected_value);
       assert_eq!(decoded_count, expected_count);
     }
+
+    assert_eq!(decode_uleb128(&[]), None);
+    assert_eq!(decode_uleb128(&[0x80, 0x80]), None);
+    assert_eq!(decode_uleb128(&[0x80; 11]), None);
+    assert_eq!(
+      decode_uleb128(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]),
+      Some((u64::MAX, 10))
+    );
+    assert_eq!(
+      decode_uleb128(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x03]),
+      None
+    );
+  }
+
+  #[test]
+  fn test_decode_unsigned_leb128_u32() {
+    assert_eq!(decode_u32(&[0x80, 0x01]), Some((128, 2)));
+    assert_eq!(decode_u32(&[0x80, 0x80, 0x80, 0x80, 0x00]), Some((0, 5)));
+    assert_eq!(decode_u32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]), Some((u32::MAX, 5)));
+    // the last byte may only hold the 4 remaining bits
+    assert_eq!(decode_u32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x10]), None);
+    assert_eq!(decode_u32(&[0x80, 0x80, 0x80, 0x80, 0x70]), None);
+    // at most 5 bytes, even when the value fits
+    assert_eq!(decode_u32(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]), None);
+    assert_eq!(decode_u32(&[0x80, 0x80]), None);
   }
 
   #[test]
@@ -222,9 +323,31 @@ mod tests {
     ];
 
     for (input, (expected_value, expected_count)) in test_cases {
-      let (decoded_value, decoded_count) = decode_sleb128(&input);
+      let (decoded_value, decoded_count) = decode_sleb128(&input).unwrap();
       assert_eq!(decoded_value, expected_value);
       assert_eq!(decoded_count, expected_count);
     }
+
+    assert_eq!(
+      decode_sleb128(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7F]),
+      Some((i64::MIN, 10))
+    );
+    assert_eq!(
+      decode_sleb128(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01]),
+      None
+    );
+  }
+
+  #[test]
+  fn test_decode_signed_leb128_s32() {
+    assert_eq!(decode_s32(&[0xbf, 0x7f]), Some((-65, 2)));
+    assert_eq!(decode_s32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x07]), Some((i32::MAX, 5)));
+    assert_eq!(decode_s32(&[0x80, 0x80, 0x80, 0x80, 0x78]), Some((i32::MIN, 5)));
+    assert_eq!(decode_s32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x7F]), Some((-1, 5)));
+    // the unused bits of the last byte must repeat the sign bit
+    assert_eq!(decode_s32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]), None);
+    assert_eq!(decode_s32(&[0x80, 0x80, 0x80, 0x80, 0x70]), None);
+    // at most 5 bytes, even when the value fits
+    assert_eq!(decode_s32(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F]), None);
   }
 }
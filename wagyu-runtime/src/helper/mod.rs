@@ -1 +1 @@
-pub(crate) mod leb128;
+pub mod leb128;
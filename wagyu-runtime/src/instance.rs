@@ -1,19 +1,580 @@
-use crate::module::{
-  value::Value,
-  Module,
+use alloc::{
+  rc::Rc,
+  string::String,
+  vec::Vec,
 };
+use core::{
+  cell::RefCell,
+  fmt,
+  mem,
+  sync::atomic::{
+    AtomicU64,
+    Ordering,
+  },
+};
+
+use crate::{
+  config::{
+    Config,
+    RelaxedSimd,
+  },
+  executor,
+  func::Func,
+  module::{
+    global::Global,
+    memory::Memory,
+    names::Names,
+    table::Table,
+    types::{
+      MemoryType,
+      Type,
+    },
+    value::{
+      DataMode,
+      ElemMode,
+      ExportDesc,
+      FuncIdx,
+      GlobalIdx,
+      GlobalMut,
+      IndexType,
+      RefType,
+      ValType,
+      Value,
+    },
+    Module,
+  },
+  parse,
+  stack::Stack,
+  trap::Trap,
+  validator,
+};
+
+pub enum Error {
+  /// The binary could not be decoded.
+  Parse(parse::Error),
+  /// The module does not pass validation.
+  Validation(validator::Error),
+  /// No definition was registered under the module and field name of an import.
+  MissingImport { module: String, name: String },
+  /// The definition registered for an import does not match its declared type.
+  IncompatibleImport {
+    module: String,
+    name: String,
+    message: String,
+  },
+  /// The initial pages of a memory could not be allocated.
+  MemoryAllocation { pages: u64 },
+  /// The initial elements of a table exceed the size limit or could not be allocated.
+  TableAllocation { elements: u32 },
+  /// Instantiation trapped while initializing segments or running the start function.
+  Trap(Trap),
+}
+
+impl From<parse::Error> for Error {
+  fn from(value: parse::Error) -> Self {
+    Self::Parse(value)
+  }
+}
+
+impl From<validator::Error> for Error {
+  fn from(value: validator::Error) -> Self {
+    Self::Validation(value)
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Parse(err) => write!(f, "{err}"),
+      Self::Validation(err) => write!(f, "{err}"),
+      Self::MissingImport { module, name } => write!(f, "Link error: missing import \"{module}\" \"{name}\""),
+      Self::IncompatibleImport { module, name, message } => {
+        write!(f, "Link error: incompatible import \"{module}\" \"{name}\": {message}")
+      }
+      Self::MemoryAllocation { pages } => write!(f, "Allocation error: failed to allocate {pages} pages of memory"),
+      Self::TableAllocation { elements } => write!(f, "Allocation error: failed to allocate {elements} table elements"),
+      Self::Trap(trap) => write!(f, "{trap}"),
+    }
+  }
+}
+
+impl fmt::Debug for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(self, f)
+  }
+}
+
+/// Signature of a host function: it receives the calling instance and the arguments, and returns the results.
+pub type HostFn = dyn Fn(&mut Caller<'_>, &[Value]) -> Result<Vec<Value>, executor::Error>;
+
+/// A function implemented by the host.
+#[derive(Clone)]
+pub struct HostFunc {
+  pub(crate) ty: Type,
+  pub(crate) f: Rc<HostFn>,
+}
+
+impl HostFunc {
+  pub fn new(
+    params: &[ValType],
+    results: &[ValType],
+    f: impl Fn(&mut Caller<'_>, &[Value]) -> Result<Vec<Value>, executor::Error> + 'static,
+  ) -> Self {
+    Self {
+      ty: Type {
+        params: params.to_vec(),
+        results: results.to_vec(),
+      },
+      f: Rc::new(f),
+    }
+  }
+
+  /// Calls the function, checking its results against its signature.
+  pub(crate) fn call(&self, caller: &mut Caller<'_>, args: &[Value]) -> Result<Vec<Value>, executor::Error> {
+    let results = (self.f)(caller, args)?;
+
+    let matches_signature = results.len() == self.ty.results.len()
+      && self
+        .ty
+        .results
+        .iter()
+        .zip(&results)
+        .all(|(valtype, result)| *valtype == result.valtype());
+    if !matches_signature {
+      return Err(executor::Error::TypeMismatch);
+    }
+
+    Ok(results)
+  }
+}
+
+impl fmt::Debug for HostFunc {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("HostFunc").field("ty", &self.ty).finish_non_exhaustive()
+  }
+}
+
+/// A linear memory that can be shared between the host and instances.
+#[derive(Debug, Clone)]
+pub struct MemoryRef(pub(crate) Rc<RefCell<Memory>>);
+
+impl MemoryRef {
+  /// Creates a memory with 32-bit addresses of `initial` pages, which can grow up to `max` pages.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`Error::MemoryAllocation`] if the initial pages cannot be allocated.
+  pub fn new(initial: u32, max: Option<u32>) -> Result<Self, Error> {
+    Self::try_from(Memory::new(MemoryType {
+      min: initial.into(),
+      max: max.map(u64::from),
+      idx_type: IndexType::I32,
+    }))
+  }
+
+  /// Creates a memory with 64-bit addresses of `initial` pages, which can grow up to `max` pages.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`Error::MemoryAllocation`] if the initial pages cannot be allocated.
+  pub fn new64(initial: u64, max: Option<u64>) -> Result<Self, Error> {
+    Self::try_from(Memory::new(MemoryType {
+      min: initial,
+      max,
+      idx_type: IndexType::I64,
+    }))
+  }
+
+  /// Returns the declared limits and address type of the memory.
+  pub fn ty(&self) -> MemoryType {
+    self.0.borrow().ty
+  }
+
+  /// Returns the size of the memory in pages.
+  pub fn size(&self) -> u64 {
+    self.0.borrow().size()
+  }
+
+  /// Grows the memory by `delta` pages and returns the previous size, or `None` if it cannot grow.
+  pub fn grow(&self, delta: u64) -> Option<u64> {
+    self.0.borrow_mut().grow(delta)
+  }
+
+  /// Reads `buf.len()` bytes starting at `addr`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`executor::Error::OutOfBoundMemoryAccess`] if the range does not lie within the memory.
+  pub fn read(&self, addr: u64, buf: &mut [u8]) -> Result<(), executor::Error> {
+    self.0.borrow().read(addr, buf)
+  }
+
+  /// Writes `bytes` starting at `addr`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`executor::Error::OutOfBoundMemoryAccess`] if the range does not lie within the memory.
+  pub fn write(&self, addr: u64, bytes: &[u8]) -> Result<(), executor::Error> {
+    self.0.borrow_mut().write(addr, bytes)
+  }
+}
+
+impl TryFrom<Memory> for MemoryRef {
+  type Error = Error;
+
+  fn try_from(mut memory: Memory) -> Result<Self, Self::Error> {
+    memory.alloc()?;
+
+    Ok(Self(Rc::new(RefCell::new(memory))))
+  }
+}
+
+/// A global variable that can be shared between the host and instances.
+#[derive(Debug, Clone)]
+pub struct GlobalRef(pub(crate) Rc<RefCell<Global>>);
+
+impl GlobalRef {
+  pub fn new(value: Value, mutable: bool) -> Self {
+    Self::from(Global {
+      mutable: if mutable { GlobalMut::Var } else { GlobalMut::Const },
+      valtype: value.valtype(),
+      value: Some(value),
+      init: None,
+    })
+  }
+
+  pub fn get(&self) -> Value {
+    self.0.borrow().get()
+  }
+
+  /// Sets the value of a mutable global.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`executor::Error::TypeMismatch`] if the global is immutable or has a different type than `value`.
+  pub fn set(&self, value: Value) -> Result<(), executor::Error> {
+    let mut global = self.0.borrow_mut();
+    if matches!(global.mutable, GlobalMut::Const) || global.valtype != value.valtype() {
+      return Err(executor::Error::TypeMismatch);
+    }
+
+    global.set(value);
+
+    Ok(())
+  }
+}
+
+impl From<Global> for GlobalRef {
+  fn from(global: Global) -> Self {
+    Self(Rc::new(RefCell::new(global)))
+  }
+}
+
+/// A table that can be shared between the host and instances.
+#[derive(Debug, Clone)]
+pub struct TableRef(pub(crate) Rc<RefCell<Table>>);
+
+impl TableRef {
+  /// Creates a table of `initial` copies of the reference `init`, which can grow up to `max` elements.
+  ///
+  /// # Errors
+  ///
+  /// * [`executor::Error::TypeMismatch`] if `init` is not a reference.
+  /// * [`executor::Error::MemoryExhaustion`] if the elements exceed the size limit or cannot be allocated.
+  pub fn new(init: Value, initial: u32, max: Option<u32>) -> Result<Self, executor::Error> {
+    let reftype = RefType::try_from(init.valtype()).map_err(|_| executor::Error::TypeMismatch)?;
 
-pub(crate) type ImportObject<'a> = &'a [(&'a str, &'a [(&'a str, Value)])];
+    let mut table = Table::new(reftype, initial, max);
+    table.alloc(init).map_err(|_| executor::Error::MemoryExhaustion)?;
 
-pub(crate) struct ModuleInstance<'a> {
-  import_obj: ImportObject<'a>,
-  module: Module,
+    Ok(Self::from(table))
+  }
+
+  /// Returns the size of the table in elements.
+  pub fn size(&self) -> u32 {
+    self.0.borrow().size()
+  }
+
+  /// Returns the element at `idx`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`executor::Error::OutOfBoundTableAccess`] if `idx` does not lie within the table.
+  pub fn get(&self, idx: u32) -> Result<Value, executor::Error> {
+    self.0.borrow().get(idx)
+  }
+
+  /// Stores a reference at `idx`.
+  ///
+  /// # Errors
+  ///
+  /// * [`executor::Error::TypeMismatch`] if `val` is not a reference of the table's type.
+  /// * [`executor::Error::OutOfBoundTableAccess`] if `idx` does not lie within the table.
+  pub fn set(&self, idx: u32, val: Value) -> Result<(), executor::Error> {
+    let mut table = self.0.borrow_mut();
+    if val.valtype() != ValType::from(table.reftype) {
+      return Err(executor::Error::TypeMismatch);
+    }
+
+    table.set(idx, val)
+  }
+
+  /// Grows the table by `delta` copies of `init` and returns the previous size, or `None` if it cannot grow.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`executor::Error::TypeMismatch`] if `init` is not a reference of the table's type.
+  pub fn grow(&self, delta: u32, init: Value) -> Result<Option<u32>, executor::Error> {
+    let mut table = self.0.borrow_mut();
+    if init.valtype() != ValType::from(table.reftype) {
+      return Err(executor::Error::TypeMismatch);
+    }
+
+    match table.grow(delta, init) {
+      -1 => Ok(None),
+      old_size => Ok(Some(old_size as u32)),
+    }
+  }
+}
+
+impl From<Table> for TableRef {
+  fn from(table: Table) -> Self {
+    Self(Rc::new(RefCell::new(table)))
+  }
+}
+
+/// An entity that can satisfy an import.
+#[derive(Debug, Clone)]
+pub enum Extern {
+  Func(HostFunc),
+  Table(TableRef),
+  Memory(MemoryRef),
+  Global(GlobalRef),
+}
+
+impl Extern {
+  pub(crate) const fn kind(&self) -> &'static str {
+    match self {
+      Self::Func(_) => "function",
+      Self::Table(_) => "table",
+      Self::Memory(_) => "memory",
+      Self::Global(_) => "global",
+    }
+  }
+}
+
+impl From<HostFunc> for Extern {
+  fn from(value: HostFunc) -> Self {
+    Self::Func(value)
+  }
+}
+
+impl From<TableRef> for Extern {
+  fn from(value: TableRef) -> Self {
+    Self::Table(value)
+  }
+}
+
+impl From<MemoryRef> for Extern {
+  fn from(value: MemoryRef) -> Self {
+    Self::Memory(value)
+  }
+}
+
+impl From<GlobalRef> for Extern {
+  fn from(value: GlobalRef) -> Self {
+    Self::Global(value)
+  }
+}
+
+/// The instance a host function is called from.
+pub struct Caller<'a> {
+  pub(crate) memories: &'a [MemoryRef],
 }
 
-impl<'a> ModuleInstance<'a> {
-  pub(crate) const fn new(module: Module, import_obj: ImportObject<'a>) -> Self {
-    Self { import_obj, module }
+impl Caller<'_> {
+  /// Returns a memory of the calling instance by its index.
+  pub fn memory(&self, mem_idx: u32) -> Option<&MemoryRef> {
+    self.memories.get(mem_idx as usize)
   }
+}
+
+static NEXT_INSTANCE_ID: AtomicU64 = AtomicU64::new(0);
+
+pub struct ModuleInstance {
+  /// Identifies the instance to the function handles obtained from it.
+  id: u64,
+  pub(crate) module: Module,
+  /// Imported functions, which precede the functions of the module in the function index space.
+  pub(crate) host_funcs: Vec<HostFunc>,
+  pub(crate) tables: Vec<TableRef>,
+  pub(crate) memories: Vec<MemoryRef>,
+  pub(crate) globals: Vec<GlobalRef>,
+  stack: Stack,
+  /// Whether float operators replace the NaNs they produce with the canonical NaN.
+  pub(crate) canonicalize_nans: bool,
+  pub(crate) relaxed_simd: RelaxedSimd,
+}
+
+impl ModuleInstance {
+  /// Creates an instance from a module and the externs resolved for its imports, in import order,
+  /// and runs its start function.
+  pub(crate) fn new(mut module: Module, imports: Vec<Extern>, config: &Config) -> Result<Self, Error> {
+    let mut host_funcs = vec![];
+    let mut tables = vec![];
+    let mut memories = vec![];
+    let mut globals = vec![];
+
+    for import in imports {
+      match import {
+        Extern::Func(func) => host_funcs.push(func),
+        Extern::Table(table) => tables.push(table),
+        Extern::Memory(memory) => memories.push(memory),
+        Extern::Global(global) => globals.push(global),
+      }
+    }
+
+    for mut table in mem::take(&mut module.tables) {
+      table.alloc(table.reftype.null())?;
+      tables.push(TableRef::from(table));
+    }
+    for memory in mem::take(&mut module.memories) {
+      memories.push(MemoryRef::try_from(memory)?);
+    }
+    for mut global in mem::take(&mut module.globals) {
+      if let Some(init) = &global.init {
+        let value = init
+          .eval(|global_idx| globals[global_idx as usize].get())
+          .map_err(|error| Error::Trap(Trap::from(error)))?;
+        global.value = Some(value);
+      }
+      globals.push(GlobalRef::from(global));
+    }
+
+    let mut instance = Self {
+      id: NEXT_INSTANCE_ID.fetch_add(1, Ordering::Relaxed),
+      module,
+      host_funcs,
+      tables,
+      memories,
+      globals,
+      stack: Stack::with_limits(config.max_control_depth, config.max_call_depth),
+      canonicalize_nans: config.canonicalize_nans,
+      relaxed_simd: config.relaxed_simd,
+    };
+    instance.init_elems().map_err(|error| Error::Trap(Trap::from(error)))?;
+    instance.init_data().map_err(|error| Error::Trap(Trap::from(error)))?;
+    instance.run_start().map_err(Error::Trap)?;
+
+    Ok(instance)
+  }
+
+  /// Evaluates the references of element segments, copies active segments into their tables, and drops them along
+  /// with declarative ones since only passive segments stay available to `table.init`.
+  fn init_elems(&mut self) -> Result<(), executor::Error> {
+    let global = |global_idx: GlobalIdx| self.globals[global_idx as usize].get();
+
+    for elem in &mut self.module.elems {
+      elem.elements = elem
+        .init
+        .iter()
+        .map(|expr| expr.eval(global))
+        .collect::<Result<_, _>>()?;
+
+      match &elem.mode {
+        ElemMode::Active(table_idx, offset) => {
+          let Value::I32(offset) = offset.eval(global)? else {
+            return Err(executor::Error::TypeMismatch);
+          };
+          let n = elem.elements.len() as u32;
+          self.tables[*table_idx as usize]
+            .0
+            .borrow_mut()
+            .init(&elem.elements, offset as u32, 0, n)?;
+          elem.drop();
+        }
+        ElemMode::Declarative => elem.drop(),
+        ElemMode::Passive => {}
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Copies active data segments into their memories and drops them, since only passive segments stay available to
+  /// `memory.init`.
+  fn init_data(&mut self) -> Result<(), executor::Error> {
+    let global = |global_idx: GlobalIdx| self.globals[global_idx as usize].get();
+
+    for data in &mut self.module.data {
+      if let DataMode::Active(mem_idx, offset) = &data.mode {
+        let offset = match offset.eval(global)? {
+          Value::I32(offset) => offset as u32 as u64,
+          Value::I64(offset) => offset as u64,
+          _ => return Err(executor::Error::TypeMismatch),
+        };
+        let n = u32::try_from(data.data.len()).map_err(|_| executor::Error::OutOfBoundMemoryAccess)?;
+        self.memories[*mem_idx as usize]
+          .0
+          .borrow_mut()
+          .init(&data.data, offset, 0, n)?;
+        data.drop();
+      }
+    }
 
-  pub(crate) fn run_start(&mut self) {}
+    Ok(())
+  }
+
+  pub(crate) fn run_start(&mut self) -> Result<(), Trap> {
+    match self.module.start_func {
+      Some(func_idx) => self.invoke(func_idx, &[]).map(|_| ()),
+      None => Ok(()),
+    }
+  }
+
+  /// Returns the names of the module, which are empty if it has no name section.
+  pub fn names(&self) -> &Names {
+    self.module.names()
+  }
+
+  /// Describes a function by its index, followed by its name if the module has one.
+  pub fn describe_func(&self, func_idx: FuncIdx) -> String {
+    match self.names().func_name(func_idx) {
+      Some(name) => format!("{func_idx} (${name})"),
+      None => format!("{func_idx}"),
+    }
+  }
+
+  /// Looks up an exported function by its name.
+  pub fn get_func(&self, name: &str) -> Option<Func> {
+    let export = self
+      .module
+      .exports
+      .iter()
+      .find(|export| export.name == name && matches!(export.desc, ExportDesc::Func))?;
+    self.module.func_type(export.idx)?;
+
+    Some(Func {
+      idx: export.idx,
+      instance_id: self.id,
+    })
+  }
+
+  /// Returns the signature of a function handle, after checking that it was obtained from this instance.
+  pub(crate) fn func_type(&self, func: &Func) -> Result<&Type, executor::Error> {
+    if func.instance_id != self.id {
+      return Err(executor::Error::ForeignFunc);
+    }
+
+    self.module.func_type(func.idx).ok_or(executor::Error::ForeignFunc)
+  }
+
+  /// Invokes a function by its index in the function index space.
+  pub(crate) fn invoke(&mut self, func_idx: FuncIdx, args: &[Value]) -> Result<Vec<Value>, Trap> {
+    let mut stack = mem::take(&mut self.stack);
+    let results = executor::invoke(self, &mut stack, func_idx, args);
+    self.stack = stack;
+
+    results
+  }
 }
@@ -3,16 +3,21 @@ use alloc::vec::Vec;
 use crate::module::value::*;
 
 type LaneIdx = u8;
-type Offset = u32;
+type Offset = u64;
 type Align = u32;
+/// Position of an instruction within a parsed function body.
+pub(crate) type InstrPos = usize;
 
 #[derive(Debug)]
 pub(crate) enum Instr {
   // control instructions
-  Block,
-  Loop,
-  If,
-  Else,
+  /// Carries the position of the matching `end`.
+  Block(BlockType, InstrPos),
+  Loop(BlockType),
+  /// Carries the positions of the matching `else` (if any) and `end`.
+  If(BlockType, Option<InstrPos>, InstrPos),
+  /// Carries the position of the matching `end`.
+  Else(InstrPos),
   End,
 
   Unreachable,
@@ -46,9 +51,10 @@ pub(crate) enum Instr {
   TableSize(TableIdx),
   TableGrow(TableIdx),
   TableFill(TableIdx),
-  TableCopy(TableIdx),
-  TableInit(TableIdx),
-  ElemDrop(TableIdx),
+  /// Carries the destination and source tables.
+  TableCopy(TableIdx, TableIdx),
+  TableInit(TableIdx, ElemIdx),
+  ElemDrop(ElemIdx),
 
   // memory instructions
   I32Load(MemIdx, Offset, Align),
@@ -77,9 +83,10 @@ pub(crate) enum Instr {
   MemorySize(MemIdx),
   MemoryGrow(MemIdx),
   MemoryFill(MemIdx),
-  MemoryCopy(MemIdx),
+  /// Carries the destination and source memories.
+  MemoryCopy(MemIdx, MemIdx),
   MemoryInit(MemIdx, DataIdx),
-  DataDrop(MemIdx, DataIdx),
+  DataDrop(DataIdx),
 
   // numeric instruction
   I32Const(i32),
@@ -216,7 +223,7 @@ pub(crate) enum Instr {
   F32ConvertI32U,
   F32ConvertI64S,
   F32ConvertI64U,
-  F32DenoteF64,
+  F32DemoteF64,
   F64ConvertI32S,
   F64ConvertI32U,
   F64ConvertI64S,
@@ -356,8 +363,8 @@ pub(crate) enum Instr {
   I8X16ShrS,
   I8X16ShrU,
   I8X16Add,
-  I8X16AddSetS,
-  I8X16AddSetU,
+  I8X16AddSatS,
+  I8X16AddSatU,
   I8X16Sub,
   I8X16SubSatS,
   I8X16SubSatU,
@@ -382,8 +389,8 @@ pub(crate) enum Instr {
   I16X8ShrS,
   I16X8ShrU,
   I16X8Add,
-  I16X8AddSetS,
-  I16X8AddSetU,
+  I16X8AddSatS,
+  I16X8AddSatU,
   I16X8Sub,
   I16X8SubSatS,
   I16X8SubSatU,
@@ -406,6 +413,7 @@ pub(crate) enum Instr {
   I32X4AllTrue,
   I32X4Bitmask,
   I32X4ExtaddPairwiseI16X8S,
+  I32X4ExtaddPairwiseI16X8U,
   I32X4ExtendLowI16X8S,
   I32X4ExtendHighI16X8S,
   I32X4ExtendLowI16X8U,
@@ -479,12 +487,34 @@ pub(crate) enum Instr {
 
   I32X4TruncSatF32X4S,
   I32X4TruncSatF32X4U,
-  I32X4TruncSatF32X4SZero,
-  I32X4TruncSatF32X4UZero,
+  I32X4TruncSatF64X2SZero,
+  I32X4TruncSatF64X2UZero,
   F32X4ConvertI32X4S,
   F32X4ConvertI32X4U,
   F64X2ConvertLowI32X4S,
   F64X2ConvertLowI32X4U,
   F32X4DemoteF64X2Zero,
   F64X2PromoteLowF32X4,
+
+  // relaxed vector instructions
+  I8X16RelaxedSwizzle,
+  I32X4RelaxedTruncF32X4S,
+  I32X4RelaxedTruncF32X4U,
+  I32X4RelaxedTruncF64X2SZero,
+  I32X4RelaxedTruncF64X2UZero,
+  F32X4RelaxedMadd,
+  F32X4RelaxedNmadd,
+  F64X2RelaxedMadd,
+  F64X2RelaxedNmadd,
+  I8X16RelaxedLaneselect,
+  I16X8RelaxedLaneselect,
+  I32X4RelaxedLaneselect,
+  I64X2RelaxedLaneselect,
+  F32X4RelaxedMin,
+  F32X4RelaxedMax,
+  F64X2RelaxedMin,
+  F64X2RelaxedMax,
+  I16X8RelaxedQ15MulrS,
+  I16X8RelaxedDotI8X16I7X16S,
+  I32X4RelaxedDotI8X16I7X16AddS,
 }
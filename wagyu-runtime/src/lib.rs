@@ -1,34 +1,43 @@
 // #![no_std]
 
-use instance::{
-  ImportObject,
-  ModuleInstance,
-};
+use instance::ModuleInstance;
+use linker::Linker;
 use module::Module;
 
 #[macro_use]
 extern crate alloc;
 
+pub mod config;
 pub mod executor;
+pub mod func;
+pub mod helper;
 pub mod instance;
 pub mod instr;
+pub mod linker;
 pub mod module;
+pub mod numerics;
 pub mod parse;
+pub(crate) mod simd;
 pub mod stack;
+pub mod trap;
+pub mod validator;
 pub mod wasi;
 
-pub(crate) mod helper;
-
-pub fn instantiate<'a>(buf_src: &'a [u8], import_obj: ImportObject<'a>) -> Result<ModuleInstance<'a>, parse::Error> {
+pub fn instantiate(buf_src: &[u8], linker: &Linker) -> Result<ModuleInstance, instance::Error> {
   let module = parse::parse(buf_src)?;
 
-  Ok(ModuleInstance::new(module, import_obj))
+  linker.instantiate(module)
 }
 
 pub fn compile(buf_src: &[u8]) -> Result<Module, parse::Error> {
   parse::parse(buf_src)
 }
 
-pub fn validate(buf_src: &[u8]) -> bool {
-  parse::parse(buf_src).is_ok()
+/// Decodes and validates a module without instantiating it.
+pub fn validate(buf_src: &[u8]) -> Result<(), instance::Error> {
+  let module = parse::parse(buf_src)?;
+
+  validator::validate(&module)?;
+
+  Ok(())
 }
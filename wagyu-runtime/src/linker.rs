@@ -0,0 +1,207 @@
+use alloc::{
+  collections::BTreeMap,
+  string::{
+    String,
+    ToString,
+  },
+  vec::Vec,
+};
+
+use crate::{
+  config::Config,
+  executor,
+  func::{
+    WasmParams,
+    WasmResults,
+  },
+  instance::{
+    Caller,
+    Error,
+    Extern,
+    HostFunc,
+    ModuleInstance,
+  },
+  module::{
+    import::{
+      Import,
+      ImportKind,
+    },
+    value::{
+      Limit,
+      ValType,
+      Value,
+    },
+    Module,
+  },
+  validator,
+};
+
+/// Registers host definitions under module and field names and resolves the imports of modules against them.
+#[derive(Debug, Default)]
+pub struct Linker {
+  externs: BTreeMap<(String, String), Extern>,
+  config: Config,
+}
+
+impl Linker {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Creates a linker whose instances use the given configuration.
+  pub fn with_config(config: Config) -> Self {
+    Self {
+      externs: BTreeMap::new(),
+      config,
+    }
+  }
+
+  /// Defines an extern under a module and field name, replacing any previous definition.
+  pub fn define(&mut self, module: &str, name: &str, item: impl Into<Extern>) -> &mut Self {
+    self.externs.insert((module.to_string(), name.to_string()), item.into());
+    self
+  }
+
+  /// Defines a host function with a dynamic signature.
+  pub fn func_new(
+    &mut self,
+    module: &str,
+    name: &str,
+    params: &[ValType],
+    results: &[ValType],
+    f: impl Fn(&mut Caller<'_>, &[Value]) -> Result<Vec<Value>, executor::Error> + 'static,
+  ) -> &mut Self {
+    self.define(module, name, HostFunc::new(params, results, f))
+  }
+
+  /// Defines a host function whose signature is derived from `Params` and `Results`.
+  pub fn func_wrap<Params: WasmParams, Results: WasmResults>(
+    &mut self,
+    module: &str,
+    name: &str,
+    f: impl Fn(Params) -> Results + 'static,
+  ) -> &mut Self {
+    let host_func = HostFunc::new(&Params::valtypes(), &Results::valtypes(), move |_, args| {
+      Ok(f(Params::from_values(args)?).into_values())
+    });
+
+    self.define(module, name, host_func)
+  }
+
+  /// Returns the extern defined under a module and field name.
+  pub fn get(&self, module: &str, name: &str) -> Option<&Extern> {
+    self.externs.get(&(module.to_string(), name.to_string()))
+  }
+
+  /// Validates a module, resolves every import of it and instantiates it.
+  ///
+  /// # Errors
+  ///
+  /// * [`Error::Validation`] when the module is invalid.
+  /// * [`Error::MissingImport`] when an import has no definition.
+  /// * [`Error::IncompatibleImport`] when a definition does not match the type of its import.
+  /// * [`Error::Trap`] when the start function traps.
+  pub fn instantiate(&self, module: Module) -> Result<ModuleInstance, Error> {
+    validator::validate(&module)?;
+
+    let imports = module
+      .imports
+      .iter()
+      .map(|import| self.resolve(&module, import))
+      .collect::<Result<_, _>>()?;
+
+    ModuleInstance::new(module, imports, &self.config)
+  }
+
+  fn resolve(&self, module: &Module, import: &Import) -> Result<Extern, Error> {
+    let item = self
+      .get(&import.module_name, &import.field_name)
+      .ok_or_else(|| Error::MissingImport {
+        module: import.module_name.clone(),
+        name: import.field_name.clone(),
+      })?;
+    let incompatible = |message: String| Error::IncompatibleImport {
+      module: import.module_name.clone(),
+      name: import.field_name.clone(),
+      message,
+    };
+
+    match (&import.kind, item) {
+      (ImportKind::TypeIdx(type_idx), Extern::Func(func)) => {
+        let expected = &module.types[*type_idx as usize];
+        if func.ty != *expected {
+          return Err(incompatible(format!(
+            "expected function {:?} -> {:?}, found {:?} -> {:?}",
+            expected.params, expected.results, func.ty.params, func.ty.results
+          )));
+        }
+      }
+      (ImportKind::TableType(reftype, limit), Extern::Table(table)) => {
+        let table = table.0.borrow();
+        if table.reftype != *reftype {
+          return Err(incompatible(format!(
+            "expected table of {}, found table of {}",
+            ValType::from(*reftype),
+            ValType::from(table.reftype)
+          )));
+        }
+        let Limit(min, max) = *limit;
+        check_limit(
+          (min.into(), max.map(u64::from)),
+          table.size().into(),
+          table.max.map(u64::from),
+        )
+        .map_err(incompatible)?;
+      }
+      (ImportKind::MemType(memory_type), Extern::Memory(memory)) => {
+        let memory = memory.0.borrow();
+        if memory.ty.idx_type != memory_type.idx_type {
+          return Err(incompatible(format!(
+            "expected memory with {} addresses, found memory with {} addresses",
+            memory_type.idx_type.valtype(),
+            memory.ty.idx_type.valtype()
+          )));
+        }
+        check_limit((memory_type.min, memory_type.max), memory.size, memory.ty.max).map_err(incompatible)?;
+      }
+      (ImportKind::GlobalType(valtype, mutable), Extern::Global(global)) => {
+        let global = global.0.borrow();
+        if global.valtype != *valtype || global.mutable != *mutable {
+          return Err(incompatible(format!(
+            "expected global {:?} {:?}, found {:?} {:?}",
+            mutable, valtype, global.mutable, global.valtype
+          )));
+        }
+      }
+      (kind, item) => {
+        let expected = match kind {
+          ImportKind::TypeIdx(_) => "function",
+          ImportKind::TableType(..) => "table",
+          ImportKind::MemType(_) => "memory",
+          ImportKind::GlobalType(..) => "global",
+        };
+
+        return Err(incompatible(format!("expected {expected}, found {}", item.kind())));
+      }
+    }
+
+    Ok(item.clone())
+  }
+}
+
+/// Checks that an entity with the current `size` and `max` satisfies the limit of an import.
+fn check_limit((expected_min, expected_max): (u64, Option<u64>), size: u64, max: Option<u64>) -> Result<(), String> {
+  if size < expected_min {
+    return Err(format!("expected at least {expected_min} entries, found {size}"));
+  }
+
+  match (expected_max, max) {
+    (Some(expected_max), None) => Err(format!(
+      "expected a maximum of at most {expected_max}, found no maximum"
+    )),
+    (Some(expected_max), Some(max)) if max > expected_max => {
+      Err(format!("expected a maximum of at most {expected_max}, found {max}"))
+    }
+    _ => Ok(()),
+  }
+}
@@ -1,4 +1,11 @@
+use alloc::{
+  string::String,
+  vec::Vec,
+};
+
 #[derive(Debug)]
 pub struct Custom {
-  name: String,
+  pub(crate) name: String,
+  /// Contents of the section following its name, which the specification leaves uninterpreted.
+  pub(crate) data: Vec<u8>,
 }
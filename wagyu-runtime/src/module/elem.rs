@@ -1,4 +1,29 @@
+use alloc::vec::Vec;
+
+use super::{
+  expr::ConstExpr,
+  value::{
+    ElemMode,
+    RefType,
+    Value,
+  },
+};
+
 #[derive(Debug)]
 pub struct Element {
-  
-}
\ No newline at end of file
+  pub(crate) mode: ElemMode,
+  pub(crate) reftype: RefType,
+  /// Expressions giving the references of the segment.
+  pub(crate) init: Vec<ConstExpr>,
+  /// References the segment initializes a table with, evaluated from `init` on instantiation.
+  pub(crate) elements: Vec<Value>,
+}
+
+impl Element {
+  /// Prevents further use of an element segment. This instruction is intended to be used as an optimization hint.
+  /// After an element segment is dropped its elements can no longer be retrieved, so the memory used by this segment may
+  /// be freed.
+  pub(crate) fn drop(&mut self) {
+    self.elements.clear();
+  }
+}
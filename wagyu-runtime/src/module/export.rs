@@ -2,7 +2,17 @@ extern crate alloc;
 
 use alloc::string::String;
 
-use super::value::ExportDesc;
+use super::{
+  types::ExternType,
+  value::ExportDesc,
+};
+
+/// An export of a module, with the type of the exported entity.
+#[derive(Debug, Clone)]
+pub struct ExportType<'a> {
+  pub name: &'a str,
+  pub ty: ExternType,
+}
 
 #[derive(Debug)]
 pub(crate) struct Export {
@@ -0,0 +1,72 @@
+use alloc::vec::Vec;
+
+use super::value::{
+  GlobalIdx,
+  HeapType,
+  Value,
+};
+use crate::{
+  executor::Error,
+  instr::Instr,
+};
+
+/// A constant expression, which initializes a global or gives the offset or an element of a segment.
+#[derive(Debug)]
+pub(crate) struct ConstExpr {
+  pub(crate) instrs: Vec<Instr>,
+  /// Offset of the expression in the module binary.
+  pub(crate) offset: usize,
+}
+
+impl ConstExpr {
+  pub(crate) const fn new(instrs: Vec<Instr>, offset: usize) -> Self {
+    Self { instrs, offset }
+  }
+
+  /// Evaluates the expression, reading globals through `global`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`Error::TypeMismatch`] if the expression is not constant or does not produce exactly one value,
+  /// which validation rules out.
+  pub(crate) fn eval(&self, global: impl Fn(GlobalIdx) -> Value) -> Result<Value, Error> {
+    let mut stack = Vec::with_capacity(self.instrs.len());
+
+    for instr in &self.instrs {
+      let val = match instr {
+        Instr::I32Const(val) => Value::I32(*val),
+        Instr::I64Const(val) => Value::I64(*val),
+        Instr::F32Const(val) => Value::F32(*val),
+        Instr::F64Const(val) => Value::F64(*val),
+        Instr::V128Const(val) => Value::V128(*val),
+        Instr::GlobalGet(global_idx) => global(*global_idx),
+        Instr::RefNull(HeapType::Func) => Value::FuncRef(None),
+        Instr::RefNull(HeapType::Extern) => Value::ExternRef(None),
+        Instr::RefFunc(func_idx) => Value::FuncRef(Some(*func_idx)),
+        Instr::I32Add | Instr::I32Sub | Instr::I32Mul | Instr::I64Add | Instr::I64Sub | Instr::I64Mul => {
+          let (Some(rhs), Some(lhs)) = (stack.pop(), stack.pop()) else {
+            return Err(Error::TypeMismatch);
+          };
+
+          match (instr, lhs, rhs) {
+            (Instr::I32Add, Value::I32(lhs), Value::I32(rhs)) => Value::I32(lhs.wrapping_add(rhs)),
+            (Instr::I32Sub, Value::I32(lhs), Value::I32(rhs)) => Value::I32(lhs.wrapping_sub(rhs)),
+            (Instr::I32Mul, Value::I32(lhs), Value::I32(rhs)) => Value::I32(lhs.wrapping_mul(rhs)),
+            (Instr::I64Add, Value::I64(lhs), Value::I64(rhs)) => Value::I64(lhs.wrapping_add(rhs)),
+            (Instr::I64Sub, Value::I64(lhs), Value::I64(rhs)) => Value::I64(lhs.wrapping_sub(rhs)),
+            (Instr::I64Mul, Value::I64(lhs), Value::I64(rhs)) => Value::I64(lhs.wrapping_mul(rhs)),
+            _ => return Err(Error::TypeMismatch),
+          }
+        }
+        _ => return Err(Error::TypeMismatch),
+      };
+
+      stack.push(val);
+    }
+
+    match stack[..] {
+      [val] => Ok(val),
+      _ => Err(Error::TypeMismatch),
+    }
+  }
+}
@@ -15,11 +15,13 @@ pub(crate) struct Function {
 
 #[derive(Debug)]
 pub(crate) struct ParsedBody {
-  instrs: Vec<Instr>,
+  pub(crate) instrs: Vec<Instr>,
+  /// Offsets of the instructions in the module binary, followed by the offset of the final `end`.
+  pub(crate) offsets: Vec<usize>,
 }
 
 impl ParsedBody {
-  pub const fn new(instrs: Vec<Instr>) -> Self {
-    Self { instrs }
+  pub const fn new(instrs: Vec<Instr>, offsets: Vec<usize>) -> Self {
+    Self { instrs, offsets }
   }
 }
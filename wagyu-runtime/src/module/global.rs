@@ -1,7 +1,10 @@
-use super::value::{
-  GlobalMut,
-  ValType,
-  Value,
+use super::{
+  expr::ConstExpr,
+  value::{
+    GlobalMut,
+    ValType,
+    Value,
+  },
 };
 
 #[derive(Debug)]
@@ -9,12 +12,14 @@ pub(crate) struct Global {
   pub(crate) mutable: GlobalMut,
   pub(crate) valtype: ValType,
   pub(crate) value: Option<Value>,
+  /// Initializer of a global defined by a module, which sets its value on instantiation.
+  pub(crate) init: Option<ConstExpr>,
 }
 
 impl Global {
   pub(crate) fn get(&self) -> Value {
     match self.value.as_ref() {
-      Some(v) => v.clone(),
+      Some(v) => *v,
       None => panic!("cannot get uninitialized global"),
     }
   }
@@ -1,12 +1,26 @@
 use alloc::string::String;
 
-use super::value::{
-  GlobalMut,
-  Limit,
-  RefType,
-  ValType,
+use super::{
+  types::{
+    ExternType,
+    MemoryType,
+  },
+  value::{
+    GlobalMut,
+    Limit,
+    RefType,
+    ValType,
+  },
 };
 
+/// An import of a module, with the type its definition must match.
+#[derive(Debug, Clone)]
+pub struct ImportType<'a> {
+  pub module: &'a str,
+  pub name: &'a str,
+  pub ty: ExternType,
+}
+
 #[derive(Debug)]
 pub(crate) struct Import {
   pub(crate) module_name: String,
@@ -18,6 +32,6 @@ pub(crate) struct Import {
 pub(crate) enum ImportKind {
   TypeIdx(u32),
   TableType(RefType, Limit),
-  MemType(Limit),
+  MemType(MemoryType),
   GlobalType(ValType, GlobalMut),
 }
@@ -1,72 +1,193 @@
-use alloc::alloc::{
-  alloc,
-  dealloc,
-  handle_alloc_error,
-  realloc,
-  Layout,
-};
 use core::ptr;
 
+use super::types::MemoryType;
+#[cfg(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory"))]
+use super::value::IndexType;
+use crate::{
+  executor::Error,
+  instance,
+};
+
+#[cfg(not(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory")))]
+mod heap;
+#[cfg(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory"))]
+mod mmap;
+#[cfg(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory"))]
+mod signal;
+
 const PAGE_SIZE: usize = 65_536;
-const ALIGN: usize = 4;
 
 #[derive(Debug)]
-pub(crate) struct Memory32 {
+pub(crate) struct Memory {
   /// Pointer to the start of the block of the memory.
   pub(crate) ptr: *mut u8,
   /// Allocated page size of the memory.
-  pub(crate) size: u32,
-  /// Initial allocating size.
-  pub(crate) initial: u32,
-  /// Maximum allocating size.
-  pub(crate) max: Option<u32>,
+  pub(crate) size: u64,
+  /// Declared limits and address type of the memory.
+  pub(crate) ty: MemoryType,
 }
 
-impl Memory32 {
-  pub(crate) fn alloc(&mut self) {
+impl Memory {
+  pub(crate) const fn new(ty: MemoryType) -> Self {
+    Self {
+      ptr: ptr::null_mut(),
+      size: 0,
+      ty,
+    }
+  }
+
+  /// Allocates the initial pages of the memory, zero-filled.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`instance::Error::MemoryAllocation`] if the pages cannot be allocated.
+  pub(crate) fn alloc(&mut self) -> Result<(), instance::Error> {
     if !self.ptr.is_null() {
       panic!("memory is initially allocated");
     }
 
-    let layout = Layout::from_size_align((self.initial as usize) * PAGE_SIZE, ALIGN).unwrap();
-    let ptr = unsafe { alloc(layout) };
-    if self.ptr.is_null() {
-      handle_alloc_error(layout);
+    // reserved memories are mapped even when empty, so that their guard region covers every access
+    if self.ty.min == 0
+      && !cfg!(all(
+        target_os = "linux",
+        target_arch = "x86_64",
+        feature = "mmap-memory"
+      ))
+    {
+      return Ok(());
+    }
+
+    let ptr = unsafe { self.alloc_pages(self.ty.min) };
+    if ptr.is_null() {
+      return Err(instance::Error::MemoryAllocation { pages: self.ty.min });
     }
 
     self.ptr = ptr;
-    self.size = self.initial;
+    self.size = self.ty.min;
+
+    Ok(())
   }
 
   /// Returns the current size of a memory.
   /// The function operates in units of page size.
-  pub(crate) fn size(&self) -> (i32,) {
-    (self.size as i32,)
+  pub(crate) fn size(&self) -> u64 {
+    self.size
   }
 
   /// Grows memory by a given delta and returns the previous size,
-  /// or -1 if enough memory cannot be allocated.
-  /// The function operates in units of page size.
-  pub(crate) fn grow(&mut self, (delta,): (i32,)) -> (i32,) {
-    // FIXME: should handle a nagative number?
-
+  /// or `None` if the new size exceeds the maximum or enough memory cannot be allocated.
+  /// The function operates in units of page size, and the new pages are zero-filled.
+  pub(crate) fn grow(&mut self, delta: u64) -> Option<u64> {
     let old_size = self.size;
-    let new_size = self.size + (delta as u32);
-
-    if new_size > self.max.unwrap_or_else(|| PAGE_SIZE as u32) || new_size < self.initial {
-      return (-1,);
+    let max = self.ty.idx_type.max_pages();
+    let max = self.ty.max.map_or(max, |declared| declared.min(max));
+    let new_size = old_size.checked_add(delta).filter(|new_size| *new_size <= max)?;
+    if new_size == old_size {
+      return Some(old_size);
     }
 
-    let layout = Layout::from_size_align((old_size as usize) * PAGE_SIZE, ALIGN).unwrap();
-    let new_ptr = unsafe { realloc(self.ptr, layout, new_size as usize) };
+    let new_ptr = if self.ptr.is_null() {
+      unsafe { self.alloc_pages(new_size) }
+    } else {
+      unsafe { self.grow_pages(old_size, new_size) }
+    };
+    // the old block stays allocated when growing fails, so the memory is left as it was
     if new_ptr.is_null() {
-      handle_alloc_error(layout);
+      return None;
     }
 
     self.ptr = new_ptr;
     self.size = new_size;
 
-    (old_size as i32,)
+    Some(old_size)
+  }
+
+  /// Allocates a zero-filled block of `pages` pages from the backing of the memory.
+  ///
+  /// Memories are backed by reserved virtual memory when the `mmap-memory` feature is enabled on Linux, and by the
+  /// global allocator otherwise.
+  unsafe fn alloc_pages(&self, pages: u64) -> *mut u8 {
+    #[cfg(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory"))]
+    return mmap::alloc(&self.ty, pages);
+    #[cfg(not(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory")))]
+    return heap::alloc(pages);
+  }
+
+  unsafe fn grow_pages(&self, old_pages: u64, new_pages: u64) -> *mut u8 {
+    #[cfg(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory"))]
+    return mmap::grow(&self.ty, self.ptr, old_pages, new_pages);
+    #[cfg(not(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory")))]
+    return heap::grow(self.ptr, old_pages, new_pages);
+  }
+
+  unsafe fn dealloc_pages(&self) {
+    #[cfg(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory"))]
+    return mmap::dealloc_pages(&self.ty, self.ptr, self.size);
+    #[cfg(not(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory")))]
+    return heap::dealloc_pages(self.ptr, self.size);
+  }
+
+  /// Returns the length of the memory in bytes.
+  pub(crate) fn len(&self) -> u64 {
+    self.size * (PAGE_SIZE as u64)
+  }
+
+  /// Checks that `n` bytes starting at `addr` lie within the memory and returns the start as a host offset.
+  fn check_range(&self, addr: u64, n: u64) -> Result<usize, Error> {
+    match addr.checked_add(n) {
+      Some(end) if end <= self.len() => Ok(addr as usize),
+      _ => Err(Error::OutOfBoundMemoryAccess),
+    }
+  }
+
+  /// Reads `N` bytes at the effective address `addr + offset`.
+  pub(crate) fn load<const N: usize>(&self, addr: u64, offset: u64) -> Result<[u8; N], Error> {
+    // validation bounds the static offset of a 32-bit memory to 32 bits, so its guard region catches any overrun
+    #[cfg(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory"))]
+    if self.ty.idx_type == IndexType::I32 {
+      return unsafe { signal::load(self.ptr.add((addr + offset) as usize)) };
+    }
+
+    let ea = addr.checked_add(offset).ok_or(Error::OutOfBoundMemoryAccess)?;
+    let ea = self.check_range(ea, N as u64)?;
+    let mut bytes = [0; N];
+
+    unsafe { ptr::copy_nonoverlapping(self.ptr.add(ea), bytes.as_mut_ptr(), N) };
+
+    Ok(bytes)
+  }
+
+  /// Writes `N` bytes at the effective address `addr + offset`.
+  pub(crate) fn store<const N: usize>(&mut self, addr: u64, offset: u64, bytes: [u8; N]) -> Result<(), Error> {
+    #[cfg(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory"))]
+    if self.ty.idx_type == IndexType::I32 {
+      return unsafe { signal::store(self.ptr.add((addr + offset) as usize), bytes) };
+    }
+
+    let ea = addr.checked_add(offset).ok_or(Error::OutOfBoundMemoryAccess)?;
+    let ea = self.check_range(ea, N as u64)?;
+
+    unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), self.ptr.add(ea), N) };
+
+    Ok(())
+  }
+
+  /// Copies bytes starting at `addr` into `buf`.
+  pub(crate) fn read(&self, addr: u64, buf: &mut [u8]) -> Result<(), Error> {
+    let addr = self.check_range(addr, buf.len() as u64)?;
+
+    unsafe { ptr::copy_nonoverlapping(self.ptr.add(addr), buf.as_mut_ptr(), buf.len()) };
+
+    Ok(())
+  }
+
+  /// Copies `bytes` into the memory starting at `addr`.
+  pub(crate) fn write(&mut self, addr: u64, bytes: &[u8]) -> Result<(), Error> {
+    let addr = self.check_range(addr, bytes.len() as u64)?;
+
+    unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), self.ptr.add(addr), bytes.len()) };
+
+    Ok(())
   }
 
   /// Sets all values in a region to a given byte.
@@ -77,17 +198,15 @@ impl Memory32 {
   /// * `val` - Byte value to set.
   /// * `n` - Size of memory region in bytes.
   ///
-  /// # Panics
+  /// # Errors
   ///
-  /// Panics when the destination offset plus size is greater than the length of the target memory.
-  pub(crate) fn fill(&mut self, (dst, val, n): (i32, i32, i32)) {
-    unsafe {
-      if (dst + n) as usize > ((self.size as usize) * PAGE_SIZE) {
-        panic!("memory access out of bounds");
-      }
+  /// Traps when the destination offset plus size is greater than the length of the target memory.
+  pub(crate) fn fill(&mut self, dst: u64, val: u8, n: u64) -> Result<(), Error> {
+    let dst = self.check_range(dst, n)?;
 
-      ptr::write_bytes(self.ptr.add(dst as usize), val as u8, n as usize);
-    }
+    unsafe { ptr::write_bytes(self.ptr.add(dst), val, n as usize) };
+
+    Ok(())
   }
 
   /// Copies data from a source memory region to a possibly overlapping destination region.
@@ -98,21 +217,32 @@ impl Memory32 {
   /// * `src` - Source address.
   /// * `n` - Size of memory region in bytes.
   ///
-  /// # Panics
+  /// # Errors
   ///
   /// * When the source offset plus size is greater than the length of the source memory.
   /// * When the destination offset plus size is greater than the length of the target memory.
-  pub(crate) fn copy(&mut self, (dst, src, n): (i32, i32, i32)) {
-    unsafe {
-      if (dst + n) as usize > ((self.size as usize) * PAGE_SIZE) {
-        panic!("memory access out of bounds");
-      }
-      if (src + n) as usize > ((self.size as usize) * PAGE_SIZE) {
-        panic!("memory access out of bounds");
-      }
-
-      ptr::copy(self.ptr.add(src as usize), self.ptr.add(dst as usize), n as usize);
-    }
+  pub(crate) fn copy(&mut self, dst: u64, src: u64, n: u64) -> Result<(), Error> {
+    let dst = self.check_range(dst, n)?;
+    let src = self.check_range(src, n)?;
+
+    unsafe { ptr::copy(self.ptr.add(src), self.ptr.add(dst), n as usize) };
+
+    Ok(())
+  }
+
+  /// Copies data from a region of another memory.
+  ///
+  /// # Errors
+  ///
+  /// * When the source offset plus size is greater than the length of the source memory.
+  /// * When the destination offset plus size is greater than the length of the target memory.
+  pub(crate) fn copy_from(&mut self, src_memory: &Self, dst: u64, src: u64, n: u64) -> Result<(), Error> {
+    let dst = self.check_range(dst, n)?;
+    let src = src_memory.check_range(src, n)?;
+
+    unsafe { ptr::copy_nonoverlapping(src_memory.ptr.add(src), self.ptr.add(dst), n as usize) };
+
+    Ok(())
   }
 
   /// Copies data from a passive data segment into a memory.
@@ -121,32 +251,29 @@ impl Memory32 {
   /// * `src` - Offset into the source segment.
   /// * `n` - Size of memory region in bytes.
   ///
-  /// # Panics
+  /// # Errors
   ///
-  /// Panics when the destination offset plus size is greater than the length of the target memory.
-  pub(crate) fn init(&mut self, data: &str, (dst, src, n): (i32, i32, i32)) {
-    unsafe {
-      if (dst + n) as usize > ((self.size as usize) * PAGE_SIZE) {
-        panic!("memory access out of bounds");
-      }
-      if (src + n) as usize > data.len() {
-        panic!("memory access out of bounds");
-      }
+  /// * When the source offset plus size is greater than the length of the data segment.
+  /// * When the destination offset plus size is greater than the length of the target memory.
+  pub(crate) fn init(&mut self, data: &[u8], dst: u64, src: u32, n: u32) -> Result<(), Error> {
+    let dst = self.check_range(dst, n.into())?;
+    let (src, n) = (src as usize, n as usize);
+    let Some(bytes) = src.checked_add(n).and_then(|end| data.get(src..end)) else {
+      return Err(Error::OutOfBoundMemoryAccess);
+    };
 
-      let data_ptr = data[(src as usize)..].as_ptr();
-      ptr::copy(data_ptr, self.ptr.add(dst as usize), n as usize);
-    }
+    unsafe { ptr::copy(bytes.as_ptr(), self.ptr.add(dst), n) };
+
+    Ok(())
   }
 }
 
-impl Drop for Memory32 {
+impl Drop for Memory {
   fn drop(&mut self) {
     if self.ptr.is_null() {
       return;
     }
 
-    let layout = Layout::from_size_align((self.size as usize) * PAGE_SIZE, ALIGN).unwrap();
-
-    unsafe { dealloc(self.ptr, layout) }
+    unsafe { self.dealloc_pages() }
   }
 }
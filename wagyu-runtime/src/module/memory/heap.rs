@@ -0,0 +1,50 @@
+//! Backs memories with blocks of the global allocator, which are copied into a larger block when they grow.
+
+use alloc::alloc::{
+  alloc_zeroed,
+  dealloc,
+  realloc,
+  Layout,
+};
+use core::ptr;
+
+use super::PAGE_SIZE;
+
+const ALIGN: usize = 4;
+
+/// Allocates a zero-filled block of `pages` pages, or returns null if it cannot be allocated.
+pub(super) unsafe fn alloc(pages: u64) -> *mut u8 {
+  match layout(pages) {
+    Some(layout) => alloc_zeroed(layout),
+    None => ptr::null_mut(),
+  }
+}
+
+/// Resizes a block of `old_pages` pages to `new_pages` pages and zero-fills the new pages, or returns null and
+/// leaves the block as it was if it cannot be allocated.
+pub(super) unsafe fn grow(ptr: *mut u8, old_pages: u64, new_pages: u64) -> *mut u8 {
+  let (Some(old_layout), Some(new_layout)) = (layout(old_pages), layout(new_pages)) else {
+    return ptr::null_mut();
+  };
+
+  let new_ptr = realloc(ptr, old_layout, new_layout.size());
+  if !new_ptr.is_null() {
+    ptr::write_bytes(new_ptr.add(old_layout.size()), 0, new_layout.size() - old_layout.size());
+  }
+
+  new_ptr
+}
+
+/// Frees a block of `pages` pages.
+pub(super) unsafe fn dealloc_pages(ptr: *mut u8, pages: u64) {
+  let layout = layout(pages).expect("the block was allocated with this layout");
+
+  dealloc(ptr, layout);
+}
+
+/// Returns the layout of a block of `pages` pages, or `None` if it does not fit the address space of the host.
+fn layout(pages: u64) -> Option<Layout> {
+  let size = usize::try_from(pages).ok()?.checked_mul(PAGE_SIZE)?;
+
+  Layout::from_size_align(size, ALIGN).ok()
+}
@@ -0,0 +1,229 @@
+//! Backs memories with virtual memory on Linux.
+//!
+//! Each memory reserves inaccessible address space and makes pages accessible as the memory grows, and the kernel
+//! zero-fills new pages. A 32-bit memory reserves all a 32-bit address can reach, followed by a guard region covering
+//! the largest static offset, so any access it makes lands in its reservation. Its loads and stores therefore skip
+//! the bounds check, and an access past the end of the memory faults in an inaccessible page, which
+//! [`signal`](super::signal) turns into an out-of-bounds error. It always grows in place. A 64-bit memory cannot
+//! reserve all it can address, so its accesses stay bounds checked, and when it outgrows its reservation its pages
+//! are moved into one of the next power of two pages, which remaps them instead of copying.
+
+use core::{
+  ffi::{
+    c_int,
+    c_long,
+    c_void,
+  },
+  ptr,
+};
+
+use super::{
+  signal,
+  PAGE_SIZE,
+};
+use crate::module::{
+  types::MemoryType,
+  value::IndexType,
+};
+
+/// Bytes reserved for a 32-bit memory: the 4 GiB its addresses reach, as much again for the static offset of an
+/// access, and a page for an access that starts just before the end.
+pub(super) const RESERVED_LEN_32: usize = (2 << 32) + PAGE_SIZE;
+/// Pages reserved up front for a 64-bit memory, which can grow past them by moving.
+const RESERVED_PAGES_64: u64 = 1 << 17;
+
+const PROT_NONE: c_int = 0;
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_PRIVATE: c_int = 0x02;
+const MAP_ANONYMOUS: c_int = 0x20;
+const MAP_NORESERVE: c_int = 0x4000;
+const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+const MREMAP_MAYMOVE: c_int = 1;
+const MREMAP_FIXED: c_int = 2;
+
+extern "C" {
+  fn mmap(addr: *mut c_void, len: usize, prot: c_int, flags: c_int, fd: c_int, offset: c_long) -> *mut c_void;
+  fn mremap(old_addr: *mut c_void, old_len: usize, new_len: usize, flags: c_int, ...) -> *mut c_void;
+  fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int;
+  fn munmap(addr: *mut c_void, len: usize) -> c_int;
+}
+
+/// Reserves the address space of a memory and makes its first `pages` pages accessible, or returns null if either
+/// fails.
+pub(super) unsafe fn alloc(ty: &MemoryType, pages: u64) -> *mut u8 {
+  signal::install();
+
+  let Some(ptr) = reserved_len(ty, pages).and_then(|len| reserve(len)) else {
+    return ptr::null_mut();
+  };
+
+  if commit(ptr, 0, pages).is_err() {
+    dealloc_pages(ty, ptr, pages);
+    return ptr::null_mut();
+  }
+
+  ptr
+}
+
+/// Makes the pages between `old_pages` and `new_pages` accessible, moving them into a larger reservation if the
+/// current one cannot hold them, or returns null and leaves the memory as it was if the kernel refuses.
+pub(super) unsafe fn grow(ty: &MemoryType, ptr: *mut u8, old_pages: u64, new_pages: u64) -> *mut u8 {
+  let (Some(old_len), Some(new_len)) = (reserved_len(ty, old_pages), reserved_len(ty, new_pages)) else {
+    return ptr::null_mut();
+  };
+  if new_len == old_len {
+    return match commit(ptr, old_pages, new_pages) {
+      Ok(()) => ptr,
+      Err(()) => ptr::null_mut(),
+    };
+  }
+
+  let Some(new_ptr) = reserve(new_len) else {
+    return ptr::null_mut();
+  };
+  // the accessible pages are moved last, so that nothing can fail once they left the old reservation
+  let committed_len = (old_pages as usize) * PAGE_SIZE;
+  let moved = commit(new_ptr, old_pages, new_pages).is_ok()
+    && (committed_len == 0
+      || mremap(
+        ptr.cast(),
+        committed_len,
+        committed_len,
+        MREMAP_MAYMOVE | MREMAP_FIXED,
+        new_ptr.cast::<c_void>(),
+      ) != MAP_FAILED);
+  if !moved {
+    munmap(new_ptr.cast(), new_len);
+    return ptr::null_mut();
+  }
+  munmap(ptr.add(committed_len).cast(), old_len - committed_len);
+
+  new_ptr
+}
+
+/// Releases the address space of a memory of `pages` pages.
+pub(super) unsafe fn dealloc_pages(ty: &MemoryType, ptr: *mut u8, pages: u64) {
+  let len = reserved_len(ty, pages).expect("the memory was reserved with this length");
+
+  munmap(ptr.cast(), len);
+}
+
+/// Returns the bytes reserved for a memory of `pages` pages, or `None` if they do not fit the address space of the
+/// host.
+///
+/// The reservation only depends on the type and size of the memory, so that it can be found again to grow or
+/// release it.
+fn reserved_len(ty: &MemoryType, pages: u64) -> Option<usize> {
+  if ty.idx_type == IndexType::I32 {
+    return Some(RESERVED_LEN_32);
+  }
+
+  let limit = ty.idx_type.max_pages();
+  let limit = ty.max.map_or(limit, |max| max.min(limit));
+  let reserved = pages.next_power_of_two().max(RESERVED_PAGES_64).min(limit);
+
+  usize::try_from(reserved.max(pages)).ok()?.checked_mul(PAGE_SIZE)
+}
+
+unsafe fn reserve(len: usize) -> Option<*mut u8> {
+  let ptr = mmap(
+    ptr::null_mut(),
+    len,
+    PROT_NONE,
+    MAP_PRIVATE | MAP_ANONYMOUS | MAP_NORESERVE,
+    -1,
+    0,
+  );
+
+  (ptr != MAP_FAILED).then_some(ptr.cast())
+}
+
+unsafe fn commit(ptr: *mut u8, old_pages: u64, new_pages: u64) -> Result<(), ()> {
+  let start = (old_pages as usize) * PAGE_SIZE;
+  let end = (new_pages as usize) * PAGE_SIZE;
+  if end <= start {
+    return Ok(());
+  }
+
+  match mprotect(ptr.add(start).cast(), end - start, PROT_READ | PROT_WRITE) {
+    0 => Ok(()),
+    _ => Err(()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    executor::Error,
+    module::memory::Memory,
+  };
+
+  fn memory(idx_type: IndexType, min: u64) -> Memory {
+    let mut memory = Memory::new(MemoryType {
+      min,
+      max: None,
+      idx_type,
+    });
+    memory.alloc().expect("failed to allocate the memory");
+
+    memory
+  }
+
+  #[test]
+  fn test_guard_region_faults() {
+    let mut memory = memory(IndexType::I32, 1);
+    let end = PAGE_SIZE as u64;
+
+    assert_eq!(memory.store(end - 4, 0, *b"last"), Ok(()));
+    assert_eq!(memory.load::<4>(0, end - 4), Ok(*b"last"));
+    assert_eq!(
+      memory.store(end - 2, 0, *b"overflow"),
+      Err(Error::OutOfBoundMemoryAccess)
+    );
+    // the faulting store writes none of its bytes
+    assert_eq!(memory.load::<4>(end - 4, 0), Ok(*b"last"));
+    assert_eq!(memory.load::<16>(end, 0), Err(Error::OutOfBoundMemoryAccess));
+    assert_eq!(
+      memory.load::<1>(u32::MAX.into(), u32::MAX.into()),
+      Err(Error::OutOfBoundMemoryAccess)
+    );
+
+    // the grown pages become accessible in place
+    let ptr = memory.ptr;
+    assert_eq!(memory.grow(1), Some(1));
+    assert_eq!(memory.ptr, ptr);
+    assert_eq!(memory.load::<8>(end, 0), Ok([0; 8]));
+  }
+
+  #[test]
+  fn test_empty_memory_faults() {
+    let mut memory = memory(IndexType::I32, 0);
+
+    assert!(!memory.ptr.is_null());
+    assert_eq!(memory.load::<1>(0, 0), Err(Error::OutOfBoundMemoryAccess));
+    assert_eq!(memory.store(0, 0, [1]), Err(Error::OutOfBoundMemoryAccess));
+  }
+
+  #[test]
+  fn test_grow_memory64_past_reservation() {
+    let mut memory = memory(IndexType::I64, 1);
+    memory.write(0, b"low").expect("the memory must be writable");
+    let ptr = memory.ptr;
+
+    assert_eq!(memory.grow(RESERVED_PAGES_64 - 1), Some(1));
+    assert_eq!(memory.ptr, ptr);
+    assert_eq!(memory.grow(1), Some(RESERVED_PAGES_64));
+    assert_ne!(memory.ptr, ptr);
+
+    let mut bytes = [0xFF; 4];
+    memory.read(0, &mut bytes).expect("the memory must be readable");
+    assert_eq!(&bytes, b"low\0");
+    memory
+      .read(memory.len() - 4, &mut bytes)
+      .expect("the memory must be readable");
+    assert_eq!(bytes, [0; 4]);
+    assert_eq!(memory.load::<1>(memory.len(), 0), Err(Error::OutOfBoundMemoryAccess));
+  }
+}
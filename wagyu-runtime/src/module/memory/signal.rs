@@ -0,0 +1,241 @@
+//! Turns faults of accesses to the guard region of a memory into out-of-bounds errors.
+//!
+//! Loads and stores that skip the bounds check go through the small routines below, each of which accesses the
+//! memory with a single instruction. When one of them faults, the SIGSEGV handler moves the instruction pointer to a
+//! landing pad that returns an error code from the routine, so execution resumes normally in the caller instead of
+//! jumping across Rust frames. Any other fault goes to the handler installed before, and a faulting store writes
+//! nothing because a single instruction either completes or faults.
+
+use core::{
+  arch::global_asm,
+  cell::UnsafeCell,
+  ffi::{
+    c_int,
+    c_void,
+  },
+  mem,
+  ptr,
+};
+use std::sync::Once;
+
+use crate::executor::Error;
+
+const SIGSEGV: c_int = 11;
+const SA_SIGINFO: c_int = 4;
+const SA_ONSTACK: c_int = 0x0800_0000;
+const SIG_DFL: usize = 0;
+const SIG_IGN: usize = 1;
+/// Offset of the instruction pointer in the `ucontext_t` passed to a handler, `uc_mcontext.gregs[REG_RIP]`.
+const RIP_OFFSET: usize = 168;
+
+#[repr(C)]
+struct SigAction {
+  sa_sigaction: usize,
+  sa_mask: [u64; 16],
+  sa_flags: c_int,
+  sa_restorer: usize,
+}
+
+impl SigAction {
+  const fn new(sa_sigaction: usize, sa_flags: c_int) -> Self {
+    Self {
+      sa_sigaction,
+      sa_mask: [0; 16],
+      sa_flags,
+      sa_restorer: 0,
+    }
+  }
+}
+
+/// The action replaced by the handler, which only the handler reads once it is installed.
+struct PreviousAction(UnsafeCell<SigAction>);
+
+unsafe impl Sync for PreviousAction {}
+
+static PREVIOUS: PreviousAction = PreviousAction(UnsafeCell::new(SigAction::new(SIG_DFL, 0)));
+static INSTALL: Once = Once::new();
+
+extern "C" {
+  fn sigaction(signum: c_int, act: *const SigAction, oldact: *mut SigAction) -> c_int;
+
+  #[link_name = "wagyu_guarded_start"]
+  static GUARDED_START: u8;
+  #[link_name = "wagyu_guarded_end"]
+  static GUARDED_END: u8;
+  #[link_name = "wagyu_guarded_fault"]
+  static LANDING_PAD: u8;
+  fn wagyu_guarded_load1(src: *const u8, dst: *mut u8) -> u32;
+  fn wagyu_guarded_load2(src: *const u8, dst: *mut u8) -> u32;
+  fn wagyu_guarded_load4(src: *const u8, dst: *mut u8) -> u32;
+  fn wagyu_guarded_load8(src: *const u8, dst: *mut u8) -> u32;
+  fn wagyu_guarded_load16(src: *const u8, dst: *mut u8) -> u32;
+  fn wagyu_guarded_store1(dst: *mut u8, src: *const u8) -> u32;
+  fn wagyu_guarded_store2(dst: *mut u8, src: *const u8) -> u32;
+  fn wagyu_guarded_store4(dst: *mut u8, src: *const u8) -> u32;
+  fn wagyu_guarded_store8(dst: *mut u8, src: *const u8) -> u32;
+  fn wagyu_guarded_store16(dst: *mut u8, src: *const u8) -> u32;
+}
+
+// Each routine returns 0, or 1 through the landing pad when its access faults. They touch neither the stack nor any
+// callee-saved register, so the landing pad can return straight to the caller.
+global_asm!(
+  ".p2align 4",
+  ".globl wagyu_guarded_start",
+  ".hidden wagyu_guarded_start",
+  "wagyu_guarded_start:",
+  ".globl wagyu_guarded_load1",
+  ".hidden wagyu_guarded_load1",
+  "wagyu_guarded_load1:",
+  "movzx eax, byte ptr [rdi]",
+  "mov byte ptr [rsi], al",
+  "xor eax, eax",
+  "ret",
+  ".globl wagyu_guarded_load2",
+  ".hidden wagyu_guarded_load2",
+  "wagyu_guarded_load2:",
+  "movzx eax, word ptr [rdi]",
+  "mov word ptr [rsi], ax",
+  "xor eax, eax",
+  "ret",
+  ".globl wagyu_guarded_load4",
+  ".hidden wagyu_guarded_load4",
+  "wagyu_guarded_load4:",
+  "mov eax, dword ptr [rdi]",
+  "mov dword ptr [rsi], eax",
+  "xor eax, eax",
+  "ret",
+  ".globl wagyu_guarded_load8",
+  ".hidden wagyu_guarded_load8",
+  "wagyu_guarded_load8:",
+  "mov rax, qword ptr [rdi]",
+  "mov qword ptr [rsi], rax",
+  "xor eax, eax",
+  "ret",
+  ".globl wagyu_guarded_load16",
+  ".hidden wagyu_guarded_load16",
+  "wagyu_guarded_load16:",
+  "movdqu xmm0, xmmword ptr [rdi]",
+  "movdqu xmmword ptr [rsi], xmm0",
+  "xor eax, eax",
+  "ret",
+  ".globl wagyu_guarded_store1",
+  ".hidden wagyu_guarded_store1",
+  "wagyu_guarded_store1:",
+  "movzx eax, byte ptr [rsi]",
+  "mov byte ptr [rdi], al",
+  "xor eax, eax",
+  "ret",
+  ".globl wagyu_guarded_store2",
+  ".hidden wagyu_guarded_store2",
+  "wagyu_guarded_store2:",
+  "movzx eax, word ptr [rsi]",
+  "mov word ptr [rdi], ax",
+  "xor eax, eax",
+  "ret",
+  ".globl wagyu_guarded_store4",
+  ".hidden wagyu_guarded_store4",
+  "wagyu_guarded_store4:",
+  "mov eax, dword ptr [rsi]",
+  "mov dword ptr [rdi], eax",
+  "xor eax, eax",
+  "ret",
+  ".globl wagyu_guarded_store8",
+  ".hidden wagyu_guarded_store8",
+  "wagyu_guarded_store8:",
+  "mov rax, qword ptr [rsi]",
+  "mov qword ptr [rdi], rax",
+  "xor eax, eax",
+  "ret",
+  ".globl wagyu_guarded_store16",
+  ".hidden wagyu_guarded_store16",
+  "wagyu_guarded_store16:",
+  "movdqu xmm0, xmmword ptr [rsi]",
+  "movdqu xmmword ptr [rdi], xmm0",
+  "xor eax, eax",
+  "ret",
+  ".globl wagyu_guarded_end",
+  ".hidden wagyu_guarded_end",
+  "wagyu_guarded_end:",
+  ".globl wagyu_guarded_fault",
+  ".hidden wagyu_guarded_fault",
+  "wagyu_guarded_fault:",
+  "mov eax, 1",
+  "ret",
+);
+
+/// Installs the SIGSEGV handler once per process, before the first memory with a guard region is created.
+pub(super) fn install() {
+  INSTALL.call_once(|| unsafe {
+    let handler = handle_fault as unsafe extern "C" fn(c_int, *mut c_void, *mut c_void) as usize;
+    let action = SigAction::new(handler, SA_SIGINFO | SA_ONSTACK);
+    let status = sigaction(SIGSEGV, &action, PREVIOUS.0.get());
+    assert_eq!(status, 0, "failed to install the SIGSEGV handler");
+  });
+}
+
+unsafe extern "C" fn handle_fault(signum: c_int, info: *mut c_void, context: *mut c_void) {
+  let pc = context.cast::<u8>().add(RIP_OFFSET).cast::<usize>();
+  if (&raw const GUARDED_START as usize..&raw const GUARDED_END as usize).contains(&*pc) {
+    *pc = &raw const LANDING_PAD as usize;
+    return;
+  }
+
+  let previous = &*PREVIOUS.0.get();
+  match previous.sa_sigaction {
+    // returning runs the faulting instruction again, which now takes the default action
+    SIG_DFL | SIG_IGN => {
+      sigaction(signum, &SigAction::new(SIG_DFL, 0), ptr::null_mut());
+    }
+    handler if previous.sa_flags & SA_SIGINFO != 0 => {
+      let handler = mem::transmute::<usize, unsafe extern "C" fn(c_int, *mut c_void, *mut c_void)>(handler);
+      handler(signum, info, context);
+    }
+    handler => {
+      let handler = mem::transmute::<usize, unsafe extern "C" fn(c_int)>(handler);
+      handler(signum);
+    }
+  }
+}
+
+/// Reads `N` bytes at `src`, which lies within the reservation of a memory.
+///
+/// # Errors
+///
+/// Returns [`Error::OutOfBoundMemoryAccess`] if the bytes reach an inaccessible page.
+pub(super) unsafe fn load<const N: usize>(src: *const u8) -> Result<[u8; N], Error> {
+  let routine: unsafe extern "C" fn(*const u8, *mut u8) -> u32 = match N {
+    1 => wagyu_guarded_load1,
+    2 => wagyu_guarded_load2,
+    4 => wagyu_guarded_load4,
+    8 => wagyu_guarded_load8,
+    16 => wagyu_guarded_load16,
+    _ => unreachable!("no instruction loads {N} bytes"),
+  };
+  let mut bytes = [0; N];
+
+  match routine(src, bytes.as_mut_ptr()) {
+    0 => Ok(bytes),
+    _ => Err(Error::OutOfBoundMemoryAccess),
+  }
+}
+
+/// Writes `bytes` at `dst`, which lies within the reservation of a memory, or nothing if they do not all fit.
+///
+/// # Errors
+///
+/// Returns [`Error::OutOfBoundMemoryAccess`] if the bytes reach an inaccessible page.
+pub(super) unsafe fn store<const N: usize>(dst: *mut u8, bytes: [u8; N]) -> Result<(), Error> {
+  let routine: unsafe extern "C" fn(*mut u8, *const u8) -> u32 = match N {
+    1 => wagyu_guarded_store1,
+    2 => wagyu_guarded_store2,
+    4 => wagyu_guarded_store4,
+    8 => wagyu_guarded_store8,
+    16 => wagyu_guarded_store16,
+    _ => unreachable!("no instruction stores {N} bytes"),
+  };
+
+  match routine(dst, bytes.as_ptr()) {
+    0 => Ok(()),
+    _ => Err(Error::OutOfBoundMemoryAccess),
+  }
+}
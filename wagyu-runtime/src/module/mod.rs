@@ -3,25 +3,45 @@ use alloc::vec::Vec;
 use crate::module::{
   custom::Custom,
   data::Data,
-  export::Export,
+  elem::Element,
+  export::{
+    Export,
+    ExportType,
+  },
   function::Function,
   global::Global,
-  import::Import,
-  memory::Memory32,
+  import::{
+    Import,
+    ImportKind,
+    ImportType,
+  },
+  memory::Memory,
+  names::Names,
   table::Table,
-  types::Type,
-  value::FuncIdx,
-  elem::Element
+  types::{
+    ExternType,
+    Type,
+  },
+  value::{
+    ExportDesc,
+    FuncIdx,
+    GlobalIdx,
+    Limit,
+    MemIdx,
+    TableIdx,
+  },
 };
 
 pub mod custom;
 pub mod data;
 pub mod elem;
 pub mod export;
+pub mod expr;
 pub mod function;
 pub mod global;
 pub mod import;
 pub mod memory;
+pub mod names;
 pub mod table;
 pub mod types;
 pub mod value;
@@ -33,24 +53,140 @@ pub struct Module {
   pub(crate) imports: Vec<Import>,
   pub(crate) functions: Vec<Function>,
   pub(crate) tables: Vec<Table>,
-  pub(crate) memories: Vec<Memory32>,
+  pub(crate) memories: Vec<Memory>,
   pub(crate) globals: Vec<Global>,
   pub(crate) exports: Vec<Export>,
   pub(crate) start_func: Option<FuncIdx>,
   pub(crate) elems: Vec<Element>,
   pub(crate) data: Vec<Data>,
+  /// Number of data segments declared by the data count section, if present.
+  pub(crate) data_count: Option<u32>,
+  pub(crate) names: Names,
 }
 
 impl Module {
-  pub fn custom_sections(module: Self) {
-    todo!()
+  /// Returns the number of imported functions, which precede the defined ones in the function index space.
+  pub(crate) fn n_imported_funcs(&self) -> usize {
+    self
+      .imports
+      .iter()
+      .filter(|import| matches!(import.kind, ImportKind::TypeIdx(_)))
+      .count()
   }
 
-  pub fn exports(module: Self) -> Vec<Export> {
-    todo!()
+  /// Returns the signature of a function in the function index space.
+  pub(crate) fn func_type(&self, func_idx: FuncIdx) -> Option<&Type> {
+    let type_idx = match (func_idx as usize).checked_sub(self.n_imported_funcs()) {
+      Some(func_pos) => self.functions.get(func_pos)?.signature_idx,
+      None => self
+        .imports
+        .iter()
+        .filter_map(|import| match import.kind {
+          ImportKind::TypeIdx(type_idx) => Some(type_idx),
+          _ => None,
+        })
+        .nth(func_idx as usize)?,
+    };
+
+    self.types.get(type_idx as usize)
+  }
+
+  /// Returns the names decoded from the `name` custom section, which are empty if the module has none.
+  pub fn names(&self) -> &Names {
+    &self.names
+  }
+
+  /// Returns the contents of the custom sections with the given name, in the order they appear in the binary.
+  pub fn custom_sections<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a [u8]> + 'a {
+    self
+      .customs
+      .iter()
+      .filter(move |custom| custom.name == name)
+      .map(|custom| custom.data.as_slice())
+  }
+
+  /// Returns the imports of the module in order, with the types their definitions must match.
+  ///
+  /// Imports of undefined function types are skipped, which validation rules out.
+  pub fn imports(&self) -> impl Iterator<Item = ImportType<'_>> {
+    self.imports.iter().filter_map(|import| {
+      let ty = match &import.kind {
+        ImportKind::TypeIdx(type_idx) => ExternType::Func(self.types.get(*type_idx as usize)?.clone()),
+        ImportKind::TableType(reftype, limit) => ExternType::Table(*reftype, *limit),
+        ImportKind::MemType(memory_type) => ExternType::Memory(*memory_type),
+        ImportKind::GlobalType(valtype, mutable) => ExternType::Global(*valtype, *mutable),
+      };
+
+      Some(ImportType {
+        module: &import.module_name,
+        name: &import.field_name,
+        ty,
+      })
+    })
+  }
+
+  /// Returns the exports of the module in order, with the types of the exported entities.
+  ///
+  /// Exports of undefined entities are skipped, which validation rules out.
+  pub fn exports(&self) -> impl Iterator<Item = ExportType<'_>> {
+    self.exports.iter().filter_map(|export| {
+      let ty = match export.desc {
+        ExportDesc::Func => ExternType::Func(self.func_type(export.idx)?.clone()),
+        ExportDesc::Table => self.table_type(export.idx)?,
+        ExportDesc::Mem => self.memory_type(export.idx)?,
+        ExportDesc::Global => self.global_type(export.idx)?,
+      };
+
+      Some(ExportType { name: &export.name, ty })
+    })
+  }
+
+  /// Returns the type of a table in the table index space.
+  fn table_type(&self, table_idx: TableIdx) -> Option<ExternType> {
+    self
+      .imports
+      .iter()
+      .filter_map(|import| match import.kind {
+        ImportKind::TableType(reftype, limit) => Some(ExternType::Table(reftype, limit)),
+        _ => None,
+      })
+      .chain(
+        self
+          .tables
+          .iter()
+          .map(|table| ExternType::Table(table.reftype, Limit(table.min, table.max))),
+      )
+      .nth(table_idx as usize)
+  }
+
+  /// Returns the type of a memory in the memory index space.
+  fn memory_type(&self, mem_idx: MemIdx) -> Option<ExternType> {
+    self
+      .imports
+      .iter()
+      .filter_map(|import| match import.kind {
+        ImportKind::MemType(memory_type) => Some(ExternType::Memory(memory_type)),
+        _ => None,
+      })
+      .chain(self.memories.iter().map(|memory| ExternType::Memory(memory.ty)))
+      .nth(mem_idx as usize)
   }
 
-  pub fn imports(module: Self) -> Vec<Import> {
-    todo!()
+  /// Returns the type of a global in the global index space.
+  fn global_type(&self, global_idx: GlobalIdx) -> Option<ExternType> {
+    self
+      .imports
+      .iter()
+      .filter_map(|import| match import.kind {
+        ImportKind::GlobalType(valtype, mutable) => Some(ExternType::Global(valtype, mutable)),
+        _ => None,
+      })
+      .chain(
+        self
+          .globals
+          .iter()
+          .map(|global| ExternType::Global(global.valtype, global.mutable)),
+      )
+      .nth(global_idx as usize)
   }
 }
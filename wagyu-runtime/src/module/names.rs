@@ -0,0 +1,81 @@
+use alloc::{
+  collections::BTreeMap,
+  string::String,
+};
+
+/// Names of the entities in an index space, by index.
+pub(crate) type NameMap = BTreeMap<u32, String>;
+/// Names of the entities nested in another entity, such as the locals of a function, by the outer and inner index.
+pub(crate) type IndirectNameMap = BTreeMap<u32, NameMap>;
+
+/// Human-readable names from the `name` custom section, which only serve debugging.
+#[derive(Debug, Default)]
+pub struct Names {
+  pub(crate) module: Option<String>,
+  pub(crate) funcs: NameMap,
+  pub(crate) locals: IndirectNameMap,
+  pub(crate) labels: IndirectNameMap,
+  pub(crate) types: NameMap,
+  pub(crate) tables: NameMap,
+  pub(crate) memories: NameMap,
+  pub(crate) globals: NameMap,
+  pub(crate) elems: NameMap,
+  pub(crate) data: NameMap,
+  pub(crate) fields: IndirectNameMap,
+}
+
+impl Names {
+  pub fn module_name(&self) -> Option<&str> {
+    self.module.as_deref()
+  }
+
+  pub fn func_name(&self, func_idx: u32) -> Option<&str> {
+    get(&self.funcs, func_idx)
+  }
+
+  pub fn local_name(&self, func_idx: u32, local_idx: u32) -> Option<&str> {
+    get(self.locals.get(&func_idx)?, local_idx)
+  }
+
+  /// Returns the name of a label, indexed by the order of the structured instructions in the function body.
+  pub fn label_name(&self, func_idx: u32, label_idx: u32) -> Option<&str> {
+    get(self.labels.get(&func_idx)?, label_idx)
+  }
+
+  pub fn type_name(&self, type_idx: u32) -> Option<&str> {
+    get(&self.types, type_idx)
+  }
+
+  pub fn table_name(&self, table_idx: u32) -> Option<&str> {
+    get(&self.tables, table_idx)
+  }
+
+  pub fn memory_name(&self, mem_idx: u32) -> Option<&str> {
+    get(&self.memories, mem_idx)
+  }
+
+  pub fn global_name(&self, global_idx: u32) -> Option<&str> {
+    get(&self.globals, global_idx)
+  }
+
+  pub fn elem_name(&self, elem_idx: u32) -> Option<&str> {
+    get(&self.elems, elem_idx)
+  }
+
+  pub fn data_name(&self, data_idx: u32) -> Option<&str> {
+    get(&self.data, data_idx)
+  }
+
+  pub fn field_name(&self, type_idx: u32, field_idx: u32) -> Option<&str> {
+    get(self.fields.get(&type_idx)?, field_idx)
+  }
+
+  /// Returns the named functions in index order.
+  pub fn funcs(&self) -> impl Iterator<Item = (u32, &str)> {
+    self.funcs.iter().map(|(idx, name)| (*idx, name.as_str()))
+  }
+}
+
+fn get(map: &NameMap, idx: u32) -> Option<&str> {
+  map.get(&idx).map(String::as_str)
+}
@@ -1,31 +1,149 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::value::{
+  RefType,
+  Value,
+};
+use crate::{
+  executor::Error,
+  instance,
+};
+
+/// Most elements a table can hold, whatever its declared maximum, as in browsers.
+const MAX_SIZE: u32 = 10_000_000;
+
 #[derive(Debug)]
-pub(crate) struct Table {}
+pub(crate) struct Table {
+  pub(crate) reftype: RefType,
+  /// Declared initial size.
+  pub(crate) min: u32,
+  /// References stored in the table, whose length is the current size of the table.
+  pub(crate) elements: Vec<Value>,
+  /// Maximum size.
+  pub(crate) max: Option<u32>,
+}
 
 impl Table {
+  /// Creates a table of the given limits, whose elements are only allocated by [`Table::alloc`].
+  pub(crate) const fn new(reftype: RefType, min: u32, max: Option<u32>) -> Self {
+    Self {
+      reftype,
+      min,
+      elements: Vec::new(),
+      max,
+    }
+  }
+
+  /// Allocates the initial elements of the table, all set to `init`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`instance::Error::TableAllocation`] if the elements exceed the size limit or cannot be allocated.
+  pub(crate) fn alloc(&mut self, init: Value) -> Result<(), instance::Error> {
+    if !self.elements.is_empty() {
+      panic!("table is initially allocated");
+    }
+
+    if self.min > MAX_SIZE || self.elements.try_reserve_exact(self.min as usize).is_err() {
+      return Err(instance::Error::TableAllocation { elements: self.min });
+    }
+    self.elements.resize(self.min as usize, init);
+
+    Ok(())
+  }
+
+  /// Checks that `n` entries starting at `idx` lie within the table and returns them as a range.
+  fn check_range(&self, idx: u32, n: u32) -> Result<Range<usize>, Error> {
+    match idx.checked_add(n) {
+      Some(end) if end as usize <= self.elements.len() => Ok((idx as usize)..(end as usize)),
+      _ => Err(Error::OutOfBoundTableAccess),
+    }
+  }
+
   /// Loads an element in a table.
-  pub(crate) fn get(&self, table_idx: u32) {}
+  pub(crate) fn get(&self, idx: u32) -> Result<Value, Error> {
+    self
+      .elements
+      .get(idx as usize)
+      .copied()
+      .ok_or(Error::OutOfBoundTableAccess)
+  }
 
   /// Stores an element in a table.
-  pub(crate) fn set(&self, table_idx: u32) {}
+  pub(crate) fn set(&mut self, idx: u32, val: Value) -> Result<(), Error> {
+    let element = self
+      .elements
+      .get_mut(idx as usize)
+      .ok_or(Error::OutOfBoundTableAccess)?;
+    *element = val;
+
+    Ok(())
+  }
 
   /// Returns the current size of a table.
-  pub(crate) fn size(&self) {}
+  pub(crate) fn size(&self) -> u32 {
+    self.elements.len() as u32
+  }
 
   /// Grows table by a given delta and returns the previous size,
-  /// or -1 if enough space cannot be allocated.
+  /// or -1 if the new size exceeds the maximum or enough space cannot be allocated.
   /// It also takes an initialization value for the newly allocated entries.
-  pub(crate) fn grow(&self) {}
+  pub(crate) fn grow(&mut self, delta: u32, init: Value) -> i32 {
+    let old_size = self.size();
+    let max = self.max.map_or(MAX_SIZE, |declared| declared.min(MAX_SIZE));
+    match old_size.checked_add(delta) {
+      Some(new_size) if new_size <= max && self.elements.try_reserve_exact(delta as usize).is_ok() => {
+        self.elements.resize(new_size as usize, init);
+
+        old_size as i32
+      }
+      _ => -1,
+    }
+  }
 
   /// Sets all entries in a range to a given value.
-  pub(crate) fn fill(&self) {}
+  pub(crate) fn fill(&mut self, dst: u32, val: Value, n: u32) -> Result<(), Error> {
+    let range = self.check_range(dst, n)?;
+    self.elements[range].fill(val);
+
+    Ok(())
+  }
 
   /// Copies elements from a source table region to a possibly overlapping destination region; the first index denotes the destination.
-  pub(crate) fn copy(&self) {}
+  pub(crate) fn copy(&mut self, dst: u32, src: u32, n: u32) -> Result<(), Error> {
+    let dst = self.check_range(dst, n)?;
+    let src = self.check_range(src, n)?;
+    self.elements.copy_within(src, dst.start);
+
+    Ok(())
+  }
+
+  /// Copies elements from a region of another table.
+  pub(crate) fn copy_from(&mut self, src_table: &Self, dst: u32, src: u32, n: u32) -> Result<(), Error> {
+    let dst = self.check_range(dst, n)?;
+    let src = src_table.check_range(src, n)?;
+    self.elements[dst].copy_from_slice(&src_table.elements[src]);
+
+    Ok(())
+  }
 
   /// Copies elements from a passive element segment into a table.
-  pub(crate) fn init(&self) {}
+  ///
+  /// # Errors
+  ///
+  /// * When the source offset plus size is greater than the length of the element segment.
+  /// * When the destination offset plus size is greater than the size of the table.
+  pub(crate) fn init(&mut self, elements: &[Value], dst: u32, src: u32, n: u32) -> Result<(), Error> {
+    let dst = self.check_range(dst, n)?;
+    let Some(src) = (src as usize)
+      .checked_add(n as usize)
+      .and_then(|end| elements.get((src as usize)..end))
+    else {
+      return Err(Error::OutOfBoundTableAccess);
+    };
+    self.elements[dst].copy_from_slice(src);
 
-  /// Prevents further use of a passive element segment. This instruction is intended to be used as an optimization hint.
-  /// After an element segment is dropped its elements can no longer be retrieved, so the memory used by this segment may be freed.
-  pub(crate) fn drop(&self) {}
+    Ok(())
+  }
 }
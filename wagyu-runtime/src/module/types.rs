@@ -1,9 +1,57 @@
 use alloc::vec::Vec;
 
-use super::value::ValType;
+use super::value::{
+  GlobalMut,
+  IndexType,
+  Limit,
+  RefType,
+  ValType,
+};
 
-#[derive(Debug)]
-pub(crate) struct Type {
+/// Signature of a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Type {
   pub(crate) params: Vec<ValType>,
   pub(crate) results: Vec<ValType>,
 }
+
+impl Type {
+  pub fn params(&self) -> &[ValType] {
+    &self.params
+  }
+
+  pub fn results(&self) -> &[ValType] {
+    &self.results
+  }
+}
+
+/// Type of an entity that a module imports or exports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExternType {
+  Func(Type),
+  Table(RefType, Limit),
+  Memory(MemoryType),
+  Global(ValType, GlobalMut),
+}
+
+/// Type of a memory: its size range in pages and the type of its addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryType {
+  pub(crate) min: u64,
+  pub(crate) max: Option<u64>,
+  pub(crate) idx_type: IndexType,
+}
+
+impl MemoryType {
+  pub fn min(&self) -> u64 {
+    self.min
+  }
+
+  pub fn max(&self) -> Option<u64> {
+    self.max
+  }
+
+  pub fn idx_type(&self) -> IndexType {
+    self.idx_type
+  }
+}
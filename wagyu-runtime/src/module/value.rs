@@ -1,4 +1,7 @@
 use alloc::string::String;
+use core::fmt;
+
+use super::expr::ConstExpr;
 
 pub(crate) type TypeIdx = u32;
 pub(crate) type FuncIdx = u32;
@@ -10,8 +13,8 @@ pub(crate) type DataIdx = u32;
 pub(crate) type LocalIdx = u32;
 pub(crate) type LabelIdx = u32;
 
-#[derive(Debug, Clone)]
-pub(crate) enum ValType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValType {
   I32,
   I64,
   F32,
@@ -22,14 +25,16 @@ pub(crate) enum ValType {
 }
 
 #[derive(Debug, Clone, Copy)]
-pub(crate) enum Value {
+pub enum Value {
   I32(i32),
   I64(i64),
   F32(f32),
   F64(f64),
   V128(V128Value),
-  FuncRef(u32),
-  ExternRef(u32),
+  /// A reference to a function by its index in the function index space, or null.
+  FuncRef(Option<u32>),
+  /// An opaque reference owned by the host, or null.
+  ExternRef(Option<u32>),
 }
 
 impl TryFrom<u8> for ValType {
@@ -44,33 +49,248 @@ impl TryFrom<u8> for ValType {
       0x7B => Ok(Self::V128),
       0x70 => Ok(Self::FuncRef),
       0x6F => Ok(Self::ExternRef),
-      _ => Err(format!("invalid valtype")),
+      _ => Err(String::from("invalid valtype")),
     }
   }
 }
 
-#[derive(Debug)]
-pub(crate) enum RefType {
+impl fmt::Display for ValType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      Self::I32 => "i32",
+      Self::I64 => "i64",
+      Self::F32 => "f32",
+      Self::F64 => "f64",
+      Self::V128 => "v128",
+      Self::FuncRef => "funcref",
+      Self::ExternRef => "externref",
+    };
+
+    f.write_str(name)
+  }
+}
+
+impl Value {
+  /// Returns the type of the value.
+  pub const fn valtype(&self) -> ValType {
+    match self {
+      Self::I32(_) => ValType::I32,
+      Self::I64(_) => ValType::I64,
+      Self::F32(_) => ValType::F32,
+      Self::F64(_) => ValType::F64,
+      Self::V128(_) => ValType::V128,
+      Self::FuncRef(_) => ValType::FuncRef,
+      Self::ExternRef(_) => ValType::ExternRef,
+    }
+  }
+
+  /// Returns the default value of a type, used to initialize locals.
+  pub(crate) const fn default_of(valtype: &ValType) -> Self {
+    match valtype {
+      ValType::I32 => Self::I32(0),
+      ValType::I64 => Self::I64(0),
+      ValType::F32 => Self::F32(0.0),
+      ValType::F64 => Self::F64(0.0),
+      ValType::V128 => Self::V128(V128Value::from_bits(0)),
+      ValType::FuncRef => Self::FuncRef(None),
+      ValType::ExternRef => Self::ExternRef(None),
+    }
+  }
+}
+
+macro_rules! impl_value_conversion {
+  ($($ty:ty => $variant:ident),*) => {
+    $(
+      impl From<$ty> for Value {
+        fn from(value: $ty) -> Self {
+          Self::$variant(value)
+        }
+      }
+
+      impl TryFrom<Value> for $ty {
+        type Error = Value;
+
+        fn try_from(value: Value) -> Result<Self, Self::Error> {
+          match value {
+            Value::$variant(v) => Ok(v),
+            _ => Err(value),
+          }
+        }
+      }
+    )*
+  };
+}
+
+impl_value_conversion!(i32 => I32, i64 => I64, f32 => F32, f64 => F64, V128Value => V128);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefType {
   /// Denotes the infinite union of all references to functions, regardless of their function types.
   FuncRef,
   /// Denotes the infinite union of all references to objects owned by the embedder and that can be passed into WebAssembly under this type.
   ExternRef,
 }
 
-#[derive(Debug)]
-pub(crate) struct Limit(u32, Option<u32>);
+impl RefType {
+  /// Returns the null reference of the type.
+  pub(crate) const fn null(&self) -> Value {
+    match self {
+      Self::FuncRef => Value::FuncRef(None),
+      Self::ExternRef => Value::ExternRef(None),
+    }
+  }
+}
 
-#[derive(Debug, Clone, Copy)]
-pub(crate) enum V128Value {
-  I8X16([i8; 16]),
-  I16X8([i16; 8]),
-  I32X4([i32; 4]),
-  I64X2([i64; 2]),
-  F32X4([f32; 4]),
-  F64X2([f64; 2]),
+impl TryFrom<u8> for RefType {
+  type Error = String;
+
+  fn try_from(value: u8) -> Result<Self, Self::Error> {
+    match value {
+      0x70 => Ok(Self::FuncRef),
+      0x6F => Ok(Self::ExternRef),
+      _ => Err(String::from("invalid reftype")),
+    }
+  }
+}
+
+impl TryFrom<ValType> for RefType {
+  type Error = ValType;
+
+  fn try_from(value: ValType) -> Result<Self, Self::Error> {
+    match value {
+      ValType::FuncRef => Ok(Self::FuncRef),
+      ValType::ExternRef => Ok(Self::ExternRef),
+      _ => Err(value),
+    }
+  }
+}
+
+impl From<RefType> for ValType {
+  fn from(value: RefType) -> Self {
+    match value {
+      RefType::FuncRef => Self::FuncRef,
+      RefType::ExternRef => Self::ExternRef,
+    }
+  }
+}
+
+/// Size range of a table in elements or of a memory in pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limit(pub(crate) u32, pub(crate) Option<u32>);
+
+impl Limit {
+  pub fn min(&self) -> u32 {
+    self.0
+  }
+
+  pub fn max(&self) -> Option<u32> {
+    self.1
+  }
+}
+
+/// Type of the addresses of a memory, which is 64-bit for memories of the memory64 proposal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexType {
+  I32,
+  I64,
+}
+
+impl IndexType {
+  /// Returns the type of the operands that address a memory of this type.
+  pub const fn valtype(&self) -> ValType {
+    match self {
+      Self::I32 => ValType::I32,
+      Self::I64 => ValType::I64,
+    }
+  }
+
+  /// Returns the maximum number of pages of a memory of this type, which spans its whole address space.
+  pub(crate) const fn max_pages(&self) -> u64 {
+    match self {
+      Self::I32 => 1 << 16,
+      Self::I64 => 1 << 48,
+    }
+  }
+}
+
+/// A 128-bit vector, held as its bit pattern with lane 0 in the least significant bits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct V128Value(u128);
+
+impl V128Value {
+  pub const fn from_bits(bits: u128) -> Self {
+    Self(bits)
+  }
+
+  pub const fn to_bits(self) -> u128 {
+    self.0
+  }
+
+  pub const fn from_le_bytes(bytes: [u8; 16]) -> Self {
+    Self(u128::from_le_bytes(bytes))
+  }
+
+  pub const fn to_le_bytes(self) -> [u8; 16] {
+    self.0.to_le_bytes()
+  }
+
+  /// Builds a vector from its lanes, such as `[i32; 4]` for the `i32x4` shape.
+  pub fn from_lanes<L: Lanes>(lanes: L) -> Self {
+    lanes.into_v128()
+  }
+
+  /// Interprets the vector as lanes of a shape, such as `[f32; 4]` for the `f32x4` shape.
+  pub fn lanes<L: Lanes>(self) -> L {
+    L::from_v128(self)
+  }
+}
+
+/// An array of lanes that fills a vector exactly.
+pub trait Lanes: Copy {
+  fn from_v128(value: V128Value) -> Self;
+
+  fn into_v128(self) -> V128Value;
+}
+
+macro_rules! impl_lanes {
+  ($($ty:ty; $n:literal),*) => {
+    $(
+      impl Lanes for [$ty; $n] {
+        fn from_v128(value: V128Value) -> Self {
+          const WIDTH: usize = 16 / $n;
+          let bytes = value.to_le_bytes();
+          core::array::from_fn(|i| {
+            let mut lane = [0; WIDTH];
+            lane.copy_from_slice(&bytes[i * WIDTH..(i + 1) * WIDTH]);
+            <$ty>::from_le_bytes(lane)
+          })
+        }
+
+        fn into_v128(self) -> V128Value {
+          const WIDTH: usize = 16 / $n;
+          let mut bytes = [0; 16];
+          for (chunk, lane) in bytes.chunks_exact_mut(WIDTH).zip(self) {
+            chunk.copy_from_slice(&lane.to_le_bytes());
+          }
+          V128Value::from_le_bytes(bytes)
+        }
+      }
+    )*
+  };
 }
 
+impl_lanes!(
+  i8; 16, u8; 16, i16; 8, u16; 8, i32; 4, u32; 4, i64; 2, u64; 2, f32; 4, f64; 2
+);
+
 #[derive(Debug)]
+pub(crate) enum BlockType {
+  Empty,
+  Value(ValType),
+  TypeIdx(TypeIdx),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum HeapType {
   Func,
   Extern,
@@ -78,10 +298,10 @@ pub(crate) enum HeapType {
 
 #[derive(Debug)]
 pub(crate) enum ExportDesc {
-  FuncIdx,
-  TableIdx,
-  MemIdx,
-  GlobalIdx,
+  Func,
+  Table,
+  Mem,
+  Global,
 }
 
 impl TryFrom<u8> for ExportDesc {
@@ -89,17 +309,17 @@ impl TryFrom<u8> for ExportDesc {
 
   fn try_from(value: u8) -> Result<Self, Self::Error> {
     match value {
-      0x00 => Ok(Self::FuncIdx),
-      0x01 => Ok(Self::TableIdx),
-      0x02 => Ok(Self::MemIdx),
-      0x03 => Ok(Self::GlobalIdx),
-      _ => Err(format!("invalid export kind")),
+      0x00 => Ok(Self::Func),
+      0x01 => Ok(Self::Table),
+      0x02 => Ok(Self::Mem),
+      0x03 => Ok(Self::Global),
+      _ => Err(String::from("invalid export kind")),
     }
   }
 }
 
-#[derive(Debug)]
-pub(crate) enum GlobalMut {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlobalMut {
   Const,
   Var,
 }
@@ -111,13 +331,21 @@ impl TryFrom<u8> for GlobalMut {
     match value {
       0x00 => Ok(Self::Const),
       0x01 => Ok(Self::Var),
-      _ => Err(format!("invalid global mut")),
+      _ => Err(String::from("invalid global mut")),
     }
   }
 }
 
+#[derive(Debug)]
+pub(crate) enum ElemMode {
+  Passive,
+  Active(TableIdx, ConstExpr),
+  /// Forward-declares references to functions for `ref.func`, and is not available at runtime.
+  Declarative,
+}
+
 #[derive(Debug)]
 pub(crate) enum DataMode {
   Passive,
-  Active(MemIdx, u32),
+  Active(MemIdx, ConstExpr),
 }
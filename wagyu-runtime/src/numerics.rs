@@ -0,0 +1,233 @@
+//! Floating-point operators as defined by the specification.
+//!
+//! Arithmetic on the host already follows IEEE-754 for finite values and infinities, but the specification is
+//! stricter about NaN: every operator that produces a NaN from a NaN operand returns an arithmetic (quiet) NaN,
+//! and `min`/`max` must propagate NaN and order `-0.0` below `+0.0`, which `f32::min` and `f32::max` do not.
+//! The payload of a NaN result is otherwise left to the host, so [`Float::canonicalize_nan`] is provided for
+//! embedders that need bit-identical results on every host.
+
+use crate::executor::Error;
+
+/// Operators on the floating-point types of the value stack.
+pub trait Float: Copy {
+  /// Positive quiet NaN with an all-zero payload.
+  const CANONICAL_NAN: Self;
+
+  /// Returns the NaN with its quiet bit set, or the value itself if it is not a NaN.
+  fn quiet(self) -> Self;
+
+  /// Replaces any NaN with [`Float::CANONICAL_NAN`].
+  fn canonicalize_nan(self) -> Self;
+
+  /// `fmin`: propagates NaN and orders `-0.0` below `+0.0`.
+  fn fmin(self, other: Self) -> Self;
+
+  /// `fmax`: propagates NaN and orders `+0.0` above `-0.0`.
+  fn fmax(self, other: Self) -> Self;
+
+  /// `fceil`: rounds towards positive infinity, keeping the sign of zero.
+  fn fceil(self) -> Self;
+
+  /// `ffloor`: rounds towards negative infinity, keeping the sign of zero.
+  fn ffloor(self) -> Self;
+
+  /// `ftrunc`: rounds towards zero, keeping the sign of zero.
+  fn ftrunc(self) -> Self;
+
+  /// `fnearest`: rounds to the nearest integer, with ties to even.
+  fn fnearest(self) -> Self;
+
+  /// `fsqrt`: the correctly rounded square root.
+  fn fsqrt(self) -> Self;
+}
+
+macro_rules! impl_float {
+  ($ty:ty, $canonical:expr, $quiet_bit:expr) => {
+    impl Float for $ty {
+      const CANONICAL_NAN: Self = <$ty>::from_bits($canonical);
+
+      #[inline]
+      fn quiet(self) -> Self {
+        if self.is_nan() {
+          <$ty>::from_bits(self.to_bits() | $quiet_bit)
+        } else {
+          self
+        }
+      }
+
+      #[inline]
+      fn canonicalize_nan(self) -> Self {
+        if self.is_nan() {
+          Self::CANONICAL_NAN
+        } else {
+          self
+        }
+      }
+
+      #[inline]
+      fn fmin(self, other: Self) -> Self {
+        if self.is_nan() {
+          self.quiet()
+        } else if other.is_nan() {
+          other.quiet()
+        } else if self == other {
+          // only differs for zeros of opposite sign
+          <$ty>::from_bits(self.to_bits() | other.to_bits())
+        } else {
+          self.min(other)
+        }
+      }
+
+      #[inline]
+      fn fmax(self, other: Self) -> Self {
+        if self.is_nan() {
+          self.quiet()
+        } else if other.is_nan() {
+          other.quiet()
+        } else if self == other {
+          <$ty>::from_bits(self.to_bits() & other.to_bits())
+        } else {
+          self.max(other)
+        }
+      }
+
+      #[inline]
+      fn fceil(self) -> Self {
+        self.ceil().quiet()
+      }
+
+      #[inline]
+      fn ffloor(self) -> Self {
+        self.floor().quiet()
+      }
+
+      #[inline]
+      fn ftrunc(self) -> Self {
+        self.trunc().quiet()
+      }
+
+      #[inline]
+      fn fnearest(self) -> Self {
+        self.round_ties_even().quiet()
+      }
+
+      #[inline]
+      fn fsqrt(self) -> Self {
+        self.sqrt().quiet()
+      }
+    }
+  };
+}
+
+impl_float!(f32, 0x7FC0_0000, 1 << 22);
+impl_float!(f64, 0x7FF8_0000_0000_0000, 1 << 51);
+
+macro_rules! trunc {
+  ($(#[$attr:meta])* $name:ident, $float:ty, $int:ty, $lo:expr, $hi:expr) => {
+    $(#[$attr])*
+    pub fn $name(x: $float) -> Result<$int, Error> {
+      if x.is_nan() {
+        return Err(Error::InvalidConversionToInteger);
+      }
+
+      // both bounds are powers of two, so they are exact in either float type
+      let t = x.trunc();
+      if ($lo..$hi).contains(&t) {
+        Ok(t as $int)
+      } else {
+        Err(Error::IntegerOverflow)
+      }
+    }
+  };
+}
+
+trunc!(
+  /// `i32.trunc_f32_s`: converts towards zero, trapping on NaN and on results outside of `i32`.
+  i32_trunc_f32_s, f32, i32, -2_147_483_648.0, 2_147_483_648.0
+);
+trunc!(
+  /// `i32.trunc_f32_u`: converts towards zero, trapping on NaN and on results outside of `u32`.
+  i32_trunc_f32_u, f32, u32, 0.0, 4_294_967_296.0
+);
+trunc!(
+  /// `i32.trunc_f64_s`: converts towards zero, trapping on NaN and on results outside of `i32`.
+  i32_trunc_f64_s, f64, i32, -2_147_483_648.0, 2_147_483_648.0
+);
+trunc!(
+  /// `i32.trunc_f64_u`: converts towards zero, trapping on NaN and on results outside of `u32`.
+  i32_trunc_f64_u, f64, u32, 0.0, 4_294_967_296.0
+);
+trunc!(
+  /// `i64.trunc_f32_s`: converts towards zero, trapping on NaN and on results outside of `i64`.
+  i64_trunc_f32_s, f32, i64, -9_223_372_036_854_775_808.0, 9_223_372_036_854_775_808.0
+);
+trunc!(
+  /// `i64.trunc_f32_u`: converts towards zero, trapping on NaN and on results outside of `u64`.
+  i64_trunc_f32_u, f32, u64, 0.0, 18_446_744_073_709_551_616.0
+);
+trunc!(
+  /// `i64.trunc_f64_s`: converts towards zero, trapping on NaN and on results outside of `i64`.
+  i64_trunc_f64_s, f64, i64, -9_223_372_036_854_775_808.0, 9_223_372_036_854_775_808.0
+);
+trunc!(
+  /// `i64.trunc_f64_u`: converts towards zero, trapping on NaN and on results outside of `u64`.
+  i64_trunc_f64_u, f64, u64, 0.0, 18_446_744_073_709_551_616.0
+);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SIGNALING_NAN: f32 = f32::from_bits(0x7FA0_0001);
+
+  #[test]
+  fn min_max() {
+    assert_eq!(0.0f32.fmin(-0.0).to_bits(), (-0.0f32).to_bits());
+    assert_eq!((-0.0f32).fmin(0.0).to_bits(), (-0.0f32).to_bits());
+    assert_eq!(0.0f64.fmax(-0.0).to_bits(), 0.0f64.to_bits());
+    assert_eq!((-0.0f64).fmax(0.0).to_bits(), 0.0f64.to_bits());
+    assert_eq!(1.0f32.fmin(f32::NEG_INFINITY), f32::NEG_INFINITY);
+    assert_eq!(1.0f64.fmax(2.0), 2.0);
+
+    // NaN wins over every number and is returned quieted
+    assert_eq!(1.0f32.fmin(SIGNALING_NAN).to_bits(), 0x7FE0_0001);
+    assert_eq!(SIGNALING_NAN.fmax(f32::INFINITY).to_bits(), 0x7FE0_0001);
+  }
+
+  #[test]
+  fn rounding() {
+    assert_eq!(2.5f32.fnearest(), 2.0);
+    assert_eq!(3.5f32.fnearest(), 4.0);
+    assert_eq!((-0.5f64).fnearest().to_bits(), (-0.0f64).to_bits());
+    assert_eq!((-0.5f64).fceil().to_bits(), (-0.0f64).to_bits());
+    assert_eq!(0.5f32.ffloor(), 0.0);
+    assert_eq!((-1.5f32).ftrunc(), -1.0);
+    assert_eq!(4503599627370497.0f64.fnearest(), 4503599627370497.0);
+    assert_eq!(SIGNALING_NAN.fnearest().to_bits(), 0x7FE0_0001);
+    assert_eq!(SIGNALING_NAN.fsqrt().to_bits(), 0x7FE0_0001);
+  }
+
+  #[test]
+  fn canonicalize_nan() {
+    assert_eq!(SIGNALING_NAN.canonicalize_nan().to_bits(), 0x7FC0_0000);
+    assert_eq!((-f64::NAN).canonicalize_nan().to_bits(), 0x7FF8_0000_0000_0000);
+    assert_eq!((-0.0f32).canonicalize_nan().to_bits(), (-0.0f32).to_bits());
+  }
+
+  #[test]
+  fn trunc() {
+    assert_eq!(i32_trunc_f32_s(-2_147_483_648.0), Ok(i32::MIN));
+    assert_eq!(i32_trunc_f32_s(2_147_483_648.0), Err(Error::IntegerOverflow));
+    assert_eq!(i32_trunc_f64_s(-2_147_483_648.9), Ok(i32::MIN));
+    assert_eq!(i32_trunc_f64_s(-2_147_483_649.0), Err(Error::IntegerOverflow));
+    assert_eq!(i32_trunc_f64_u(-0.9), Ok(0));
+    assert_eq!(i32_trunc_f64_u(4_294_967_295.9), Ok(u32::MAX));
+    assert_eq!(i32_trunc_f32_u(-1.0), Err(Error::IntegerOverflow));
+    assert_eq!(i64_trunc_f64_s(f64::INFINITY), Err(Error::IntegerOverflow));
+    assert_eq!(
+      i64_trunc_f64_u(18_446_744_073_709_549_568.0),
+      Ok(18_446_744_073_709_549_568)
+    );
+    assert_eq!(i64_trunc_f32_s(f32::NAN), Err(Error::InvalidConversionToInteger));
+  }
+}
@@ -2,36 +2,68 @@ use alloc::{
   string::String,
   vec::Vec,
 };
-use core::{
-  fmt,
-  iter,
-  ops::Range,
-  ptr,
-};
+use core::fmt;
 
 use crate::{
   helper::leb128::{
+    decode_s32,
     decode_sleb128,
+    decode_u32,
     decode_uleb128,
+    MAX_LEB128_LEN,
+    MAX_LEB128_LEN_32,
+  },
+  instr::{
+    Instr,
+    InstrPos,
   },
-  instr::Instr,
   module::{
-    data::Data, export::Export, function::{
+    custom::Custom,
+    data::Data,
+    elem::Element,
+    export::Export,
+    expr::ConstExpr,
+    function::{
       Function,
       ParsedBody,
-    }, global::Global, import::{
+    },
+    global::Global,
+    import::{
       Import,
       ImportKind,
-    }, memory::Memory32, types::Type, value::{
+    },
+    memory::Memory,
+    names::{
+      IndirectNameMap,
+      NameMap,
+      Names,
+    },
+    table::Table,
+    types::{
+      MemoryType,
+      Type,
+    },
+    value::{
+      BlockType,
+      DataMode,
+      ElemMode,
       ExportDesc,
       GlobalMut,
+      HeapType,
+      IndexType,
+      Limit,
+      MemIdx,
+      RefType,
+      V128Value,
       ValType,
-    }, value::{
-      DataMode
-    }, Module
+    },
+    Module,
   },
 };
 
+/// Maximum number of locals a function may declare.
+const MAX_LOCALS: usize = 50_000;
+
 pub enum ErrorKind {
   InvalidBinaryMagic,
   InvalidBinaryVersion,
@@ -39,6 +71,12 @@ pub enum ErrorKind {
   InvalidInstruction,
   InvalidValue,
   MissingSection,
+  /// The input ends before the item being read does.
+  UnexpectedEof,
+  /// A section or function body is not exactly as long as its declared size.
+  SectionSizeMismatch,
+  /// The binary uses a feature that is not supported yet.
+  Unsupported,
 }
 
 pub struct Error {
@@ -76,24 +114,31 @@ impl fmt::Display for Error {
       ErrorKind::InvalidInstruction => write!(f, "Invalid instruction: {} at 0x{:07X}", self.message, self.offset),
       ErrorKind::InvalidValue => write!(f, "Invalid value: {} at 0x{:07X}", self.message, self.offset),
       ErrorKind::MissingSection => write!(f, "Missing section: {} at 0x{:07X}", self.message, self.offset),
+      ErrorKind::UnexpectedEof if self.message.is_empty() => {
+        write!(f, "Unexpected end of input at 0x{:07X}", self.offset)
+      }
+      ErrorKind::UnexpectedEof => write!(f, "Unexpected end of input: {} at 0x{:07X}", self.message, self.offset),
+      ErrorKind::SectionSizeMismatch => write!(f, "Section size mismatch: {} at 0x{:07X}", self.message, self.offset),
+      ErrorKind::Unsupported => write!(f, "Unsupported: {} at 0x{:07X}", self.message, self.offset),
     }
   }
 }
 
 pub(crate) fn parse(buf_src: &[u8]) -> Result<Module, Error> {
-  let binary_magic = &buf_src[0..4];
-  let binary_version = &buf_src[4..8];
-
-  if binary_magic != &[0x00, 0x61, 0x73, 0x6d] {
+  let binary_magic = read_bytes(buf_src, 0, 4)?;
+  if binary_magic != [0x00, 0x61, 0x73, 0x6d] {
     return Err(Error::from((0, ErrorKind::InvalidBinaryMagic)));
   }
-  if binary_version != &[0x01, 0x00, 0x00, 0x00] {
+
+  let binary_version = read_bytes(buf_src, 4, 4)?;
+  if binary_version != [0x01, 0x00, 0x00, 0x00] {
     return Err(Error::from((4, ErrorKind::InvalidBinaryVersion)));
   }
 
   let mut section_ofs = 8;
 
-  let mut tmp_data_count = 0;
+  let mut tmp_data_count = None;
+  let mut tmp_names = Names::default();
   let mut tmp_customs = Vec::new();
   let mut tmp_types = Vec::new();
   let mut tmp_imports = Vec::new();
@@ -107,63 +152,70 @@ pub(crate) fn parse(buf_src: &[u8]) -> Result<Module, Error> {
   let mut tmp_elems = Vec::new();
   let mut tmp_data = Vec::new();
 
-  // Calculates the fixup size of a section if body size is not provided.
-  let finalize_section = |section_ofs: usize, section_size: u64, section_size_b: usize| {
-    section_ofs + (section_size as usize) + section_size_b + 1
-  };
-
-  // Parses string in a given offset and len to the reading source binary.
-  let parse_utf8 = |ofs: usize, len: usize| {
-    String::from_utf8(Vec::from(&buf_src[ofs..(ofs + len)]))
-      .map_err(|err| Error::from((ofs, ErrorKind::InvalidValue, err.to_string())))
-  };
-
-  loop {
-    if section_ofs >= buf_src.len() {
-      break;
+  while section_ofs < buf_src.len() {
+    let section_id = read_byte(buf_src, section_ofs)?;
+    let (section_size, section_size_b) = read_u32(buf_src, section_ofs + 1)?;
+
+    let contents_ofs = section_ofs + 1 + section_size_b;
+    let section_end = contents_ofs + (section_size as usize);
+    if section_end > buf_src.len() {
+      return Err(Error::from((
+        buf_src.len(),
+        ErrorKind::UnexpectedEof,
+        format!("section {section_id} declares {section_size} bytes"),
+      )));
     }
 
-    section_ofs = match buf_src[section_ofs] {
+    // reads within a section cannot run past its end
+    let src_bin = &buf_src[..section_end];
+
+    let consumed_ofs = match section_id {
       // custom section
       0 => {
-        let (section_size, section_size_b) = decode_uleb128(&buf_src[(section_ofs + 1)..]);
-
-        finalize_section(section_ofs, section_size, section_size_b)
+        let (name, name_b) = parse_name(src_bin, contents_ofs)?;
+
+        let data_ofs = contents_ofs + name_b;
+        // a malformed name section must not invalidate the module, so its names are dropped instead
+        if name == "name" {
+          tmp_names = parse_names(src_bin, data_ofs).unwrap_or_default();
+        }
+        tmp_customs.push(Custom {
+          name,
+          data: read_bytes(src_bin, data_ofs, section_end - data_ofs)?.to_vec(),
+        });
+
+        section_end
       }
       // type section
       1 => {
-        let (section_size, section_size_b) = decode_uleb128(&buf_src[(section_ofs + 1)..]);
-        let (n_item, n_item_b) = decode_uleb128(&buf_src[(section_ofs + section_size_b + 1)..]);
+        let (n_item, n_item_b) = read_u32(src_bin, contents_ofs)?;
 
-        let parse_type = |range: Range<usize>| {
-          range
+        let parse_types = |ofs: usize| -> Result<(Vec<ValType>, usize), Error> {
+          let (n_type, n_type_b) = read_u32(src_bin, ofs)?;
+          let types_ofs = ofs + n_type_b;
+          let types = (types_ofs..(types_ofs + (n_type as usize)))
             .map(|ofs| {
-              ValType::try_from(buf_src[ofs])
-                .map_err(|err| Error::from((ofs, ErrorKind::InvalidValue, err.to_string())))
+              ValType::try_from(read_byte(src_bin, ofs)?)
+                .map_err(|err| Error::from((ofs, ErrorKind::InvalidValue, err)))
             })
-            .collect::<Result<Vec<_>, _>>()
+            .collect::<Result<Vec<_>, _>>()?;
+
+          Ok((types, types_ofs + (n_type as usize)))
         };
 
-        let mut item_ofs = section_ofs + 1 + section_size_b + n_item_b;
+        let mut item_ofs = contents_ofs + n_item_b;
         tmp_types = (0..n_item)
           .map(|_| {
-            if buf_src[item_ofs] != 0x60 {
+            if read_byte(src_bin, item_ofs)? != 0x60 {
               return Err(Error::from((
                 item_ofs,
                 ErrorKind::InvalidValue,
-                format!("not func type"),
+                String::from("not func type"),
               )));
             }
 
-            let (n_param, n_param_b) = decode_uleb128(&buf_src[(item_ofs + 1)..]);
-            let (n_result, n_result_b) = decode_uleb128(&buf_src[(item_ofs + n_param_b + (n_param as usize) + 1)..]);
-
-            let param_ofs = item_ofs + 1 + n_param_b;
-            let param_types = parse_type(param_ofs..(param_ofs + (n_param as usize)))?;
-
-            let result_ofs = item_ofs + 1 + n_param_b + (n_param as usize) + n_result_b;
-            let next_func_ofs = result_ofs + (n_result as usize);
-            let result_types = parse_type(result_ofs..next_func_ofs)?;
+            let (param_types, result_ofs) = parse_types(item_ofs + 1)?;
+            let (result_types, next_func_ofs) = parse_types(result_ofs)?;
 
             item_ofs = next_func_ofs;
 
@@ -174,39 +226,55 @@ pub(crate) fn parse(buf_src: &[u8]) -> Result<Module, Error> {
           })
           .collect::<Result<_, _>>()?;
 
-        finalize_section(section_ofs, section_size, section_size_b)
+        item_ofs
       }
       // import section
       2 => {
-        let (section_size, section_size_b) = decode_uleb128(&buf_src[(section_ofs + 1)..]);
-        let (n_item, n_item_b) = decode_uleb128(&buf_src[(section_ofs + section_size_b + 1)..]);
+        let (n_item, n_item_b) = read_u32(src_bin, contents_ofs)?;
 
-        let mut item_ofs = section_ofs + section_size_b + n_item_b + 1;
+        let mut item_ofs = contents_ofs + n_item_b;
         tmp_imports = (0..n_item)
           .map(|_| {
-            let (module_name_len, module_name_len_b) = decode_uleb128(&buf_src[item_ofs..]);
-            let module_name = parse_utf8(item_ofs + module_name_len_b, module_name_len as usize)?;
+            let (module_name_len, module_name_len_b) = read_u32(src_bin, item_ofs)?;
+            let module_name = parse_utf8(src_bin, item_ofs + module_name_len_b, module_name_len as usize)?;
 
-            let (field_name_len, field_name_len_b) =
-              decode_uleb128(&buf_src[(item_ofs + module_name_len_b + (module_name_len as usize))..]);
-            let field_name_ofs = item_ofs + module_name_len_b + (module_name_len as usize) + field_name_len_b;
-            let field_name = parse_utf8(field_name_ofs, field_name_len as usize)?;
+            let field_name_len_ofs = item_ofs + module_name_len_b + (module_name_len as usize);
+            let (field_name_len, field_name_len_b) = read_u32(src_bin, field_name_len_ofs)?;
+            let field_name_ofs = field_name_len_ofs + field_name_len_b;
+            let field_name = parse_utf8(src_bin, field_name_ofs, field_name_len as usize)?;
 
             let kind_ofs = field_name_ofs + (field_name_len as usize);
-            let (kind, kind_b) = match buf_src[kind_ofs] {
+            let (kind, kind_b) = match read_byte(src_bin, kind_ofs)? {
               0 => {
-                let (type_idx, type_idx_b) = decode_uleb128(&buf_src[(kind_ofs + 1)..]);
+                let (type_idx, type_idx_b) = read_u32(src_bin, kind_ofs + 1)?;
+
+                (ImportKind::TypeIdx(type_idx), type_idx_b)
+              }
+              1 => {
+                let reftype = RefType::try_from(read_byte(src_bin, kind_ofs + 1)?)
+                  .map_err(|err| Error::from((kind_ofs + 1, ErrorKind::InvalidValue, err)))?;
+                let (limit, limit_b) = parse_limit(src_bin, kind_ofs + 2)?;
+
+                (ImportKind::TableType(reftype, limit), 1 + limit_b)
+              }
+              2 => {
+                let (memory_type, memory_type_b) = parse_memory_type(src_bin, kind_ofs + 1)?;
+
+                (ImportKind::MemType(memory_type), memory_type_b)
+              }
+              3 => {
+                let valtype = ValType::try_from(read_byte(src_bin, kind_ofs + 1)?)
+                  .map_err(|err| Error::from((kind_ofs + 1, ErrorKind::InvalidValue, err)))?;
+                let global_mut = GlobalMut::try_from(read_byte(src_bin, kind_ofs + 2)?)
+                  .map_err(|err| Error::from((kind_ofs + 2, ErrorKind::InvalidValue, err)))?;
 
-                (ImportKind::TypeIdx(type_idx as u32), type_idx_b)
+                (ImportKind::GlobalType(valtype, global_mut), 2)
               }
-              1 => todo!(),
-              2 => todo!(),
-              3 => todo!(),
               _ => {
                 return Err(Error::from((
                   kind_ofs,
                   ErrorKind::InvalidValue,
-                  format!("invalid import kind"),
+                  String::from("invalid import kind"),
                 )))
               }
             };
@@ -221,235 +289,385 @@ pub(crate) fn parse(buf_src: &[u8]) -> Result<Module, Error> {
           })
           .collect::<Result<_, _>>()?;
 
-        finalize_section(section_ofs, section_size, section_size_b)
+        item_ofs
       }
       // function section
       3 => {
-        let (section_size, section_size_b) = decode_uleb128(&buf_src[(section_ofs + 1)..]);
-        let (n_item, n_item_b) = decode_uleb128(&buf_src[(section_ofs + section_size_b + 1)..]);
+        let (n_item, n_item_b) = read_u32(src_bin, contents_ofs)?;
 
-        let mut item_ofs = section_ofs + section_size_b + n_item_b + 1;
+        let mut item_ofs = contents_ofs + n_item_b;
         tmp_function_types = (0..n_item)
           .map(|_| {
-            let (type_pos, type_pos_b) = decode_uleb128(&buf_src[item_ofs..]);
+            let (type_idx, type_idx_b) = read_u32(src_bin, item_ofs)?;
 
-            item_ofs += type_pos_b;
+            item_ofs += type_idx_b;
 
-            Ok(type_pos)
+            Ok(type_idx)
           })
           .collect::<Result<_, Error>>()?;
 
-        finalize_section(section_ofs, section_size, section_size_b)
+        item_ofs
       }
       // table section
       4 => {
-        todo!()
+        let (n_item, n_item_b) = read_u32(src_bin, contents_ofs)?;
+
+        let mut item_ofs = contents_ofs + n_item_b;
+        tmp_tables = (0..n_item)
+          .map(|_| {
+            let reftype = RefType::try_from(read_byte(src_bin, item_ofs)?)
+              .map_err(|err| Error::from((item_ofs, ErrorKind::InvalidValue, err)))?;
+            let (Limit(initial, max), limit_b) = parse_limit(src_bin, item_ofs + 1)?;
+
+            item_ofs += 1 + limit_b;
+
+            Ok(Table::new(reftype, initial, max))
+          })
+          .collect::<Result<_, Error>>()?;
+
+        item_ofs
       }
       // memory section
       5 => {
-        let (section_size, section_size_b) = decode_uleb128(&buf_src[(section_ofs + 1)..]);
-        let (n_item, n_item_b) = decode_uleb128(&buf_src[(section_ofs + section_size_b + 1)..]);
+        let (n_item, n_item_b) = read_u32(src_bin, contents_ofs)?;
 
-        let mut item_ofs = section_ofs + section_size_b + n_item_b + 1;
+        let mut item_ofs = contents_ofs + n_item_b;
         tmp_memories = (0..n_item)
           .map(|_| {
-            let limit_flag = buf_src[item_ofs];
-            let (limit_initial, limit_initial_b) = decode_uleb128(&buf_src[(item_ofs + 1)..]);
+            let (memory_type, memory_type_b) = parse_memory_type(src_bin, item_ofs)?;
 
-            let (max_b, max) = match limit_flag {
-              0 => (0, None),
-              1 => {
-                let max_ofs = item_ofs + limit_initial_b + 1;
-                let (limit_max, limit_max_b) = decode_uleb128(&buf_src[max_ofs..]);
+            item_ofs += memory_type_b;
 
-                (limit_max_b, Some(limit_max))
-              }
-              _ => {
-                return Err(Error::from((
-                  item_ofs,
-                  ErrorKind::InvalidValue,
-                  format!("limit flag byte is invalid"),
-                )))
-              }
-            };
-
-            item_ofs += limit_initial_b + max_b + 1;
-
-            Ok(Memory32 {
-              ptr: ptr::null_mut(),
-              size: 0,
-              initial: limit_initial as u32,
-              max: max.map(|x| x as u32),
-            })
+            Ok(Memory::new(memory_type))
           })
-          .collect::<Result<_, _>>()?;
+          .collect::<Result<_, Error>>()?;
 
-        finalize_section(section_ofs, section_size, section_size_b)
+        item_ofs
       }
       // global section
       6 => {
-        let (section_size, section_size_b) = decode_uleb128(&buf_src[(section_ofs + 1)..]);
-        let (n_item, n_item_b) = decode_uleb128(&buf_src[(section_ofs + section_size_b + 1)..]);
+        let (n_item, n_item_b) = read_u32(src_bin, contents_ofs)?;
 
-        let mut item_ofs = section_ofs + section_size_b + n_item_b + 1;
+        let mut item_ofs = contents_ofs + n_item_b;
         tmp_globals = (0..n_item)
           .map(|_| {
-            let global_valtype = ValType::try_from(buf_src[item_ofs])
+            let global_valtype = ValType::try_from(read_byte(src_bin, item_ofs)?)
               .map_err(|err| Error::from((item_ofs, ErrorKind::InvalidValue, err.to_string())))?;
-            let global_mut = GlobalMut::try_from(buf_src[item_ofs + 1])
+            let global_mut = GlobalMut::try_from(read_byte(src_bin, item_ofs + 1)?)
               .map_err(|err| Error::from((item_ofs + 1, ErrorKind::InvalidValue, err.to_string())))?;
+            let (init, init_b) = parse_expr(src_bin, item_ofs + 2)?;
+
+            item_ofs += 2 + init_b;
 
             Ok(Global {
               mutable: global_mut,
               valtype: global_valtype,
-              value: todo!(),
+              value: None,
+              init: Some(init),
             })
           })
           .collect::<Result<_, Error>>()?;
 
-        finalize_section(section_ofs, section_size, section_size_b)
+        item_ofs
       }
       // export section
       7 => {
-        let (section_size, section_size_b) = decode_uleb128(&buf_src[(section_ofs + 1)..]);
-        let (n_item, n_item_b) = decode_uleb128(&buf_src[(section_ofs + section_size_b + 1)..]);
+        let (n_item, n_item_b) = read_u32(src_bin, contents_ofs)?;
 
-        let mut item_ofs = section_ofs + section_size_b + n_item_b + 1;
+        let mut item_ofs = contents_ofs + n_item_b;
         tmp_exports = (0..n_item)
           .map(|_| {
-            let (export_name_len, export_name_len_b) = decode_uleb128(&buf_src[item_ofs..]);
-            let export_name = parse_utf8(item_ofs + export_name_len_b, export_name_len as usize)?;
+            let (export_name_len, export_name_len_b) = read_u32(src_bin, item_ofs)?;
+            let export_name = parse_utf8(src_bin, item_ofs + export_name_len_b, export_name_len as usize)?;
 
-            let export_idx_ofs = item_ofs + export_name_len_b + (export_name_len as usize) + 1;
-            let export_desc = ExportDesc::try_from(buf_src[export_idx_ofs - 1])
-              .map_err(|err| Error::from((export_idx_ofs - 1, ErrorKind::InvalidValue, err.to_string())))?;
+            let export_desc_ofs = item_ofs + export_name_len_b + (export_name_len as usize);
+            let export_desc = ExportDesc::try_from(read_byte(src_bin, export_desc_ofs)?)
+              .map_err(|err| Error::from((export_desc_ofs, ErrorKind::InvalidValue, err.to_string())))?;
 
-            let (export_idx, export_idx_b) = decode_uleb128(&buf_src[export_idx_ofs..]);
+            let (export_idx, export_idx_b) = read_u32(src_bin, export_desc_ofs + 1)?;
 
-            item_ofs = export_idx_ofs + export_idx_b;
+            item_ofs = export_desc_ofs + 1 + export_idx_b;
 
             Ok(Export {
               name: export_name,
               desc: export_desc,
-              idx: export_idx as u32,
+              idx: export_idx,
             })
           })
           .collect::<Result<_, Error>>()?;
 
-        finalize_section(section_ofs, section_size, section_size_b)
+        item_ofs
       }
       // start section
       8 => {
-        let (section_size, section_size_b) = decode_uleb128(&buf_src[(section_ofs + 1)..]);
-        let (start_func_idx, _) = decode_uleb128(&buf_src[(section_ofs + section_size_b + 1)..]);
+        let (start_func_idx, start_func_idx_b) = read_u32(src_bin, contents_ofs)?;
 
-        tmp_start_func = Some(start_func_idx as u32);
+        tmp_start_func = Some(start_func_idx);
 
-        finalize_section(section_ofs, section_size, section_size_b)
+        contents_ofs + start_func_idx_b
       }
       // element section
       9 => {
-        todo!()
+        let (n_item, n_item_b) = read_u32(src_bin, contents_ofs)?;
+
+        let mut item_ofs = contents_ofs + n_item_b;
+        tmp_elems = (0..n_item)
+          .map(|_| {
+            let (segment_flag, segment_flag_b) = read_u32(src_bin, item_ofs)?;
+            if segment_flag > 7 {
+              return Err(Error::from((
+                item_ofs,
+                ErrorKind::InvalidValue,
+                String::from("invalid element segment flag"),
+              )));
+            }
+
+            // bit 0 marks a passive or declarative segment, bit 1 an explicit table index or a declarative segment,
+            // and bit 2 initializers given as expressions instead of function indices
+            let mut field_ofs = item_ofs + segment_flag_b;
+            let mode = match segment_flag & 0b011 {
+              0b000 => {
+                let (offset, offset_b) = parse_expr(src_bin, field_ofs)?;
+                field_ofs += offset_b;
+
+                ElemMode::Active(0, offset)
+              }
+              0b010 => {
+                let (table_idx, table_idx_b) = read_u32(src_bin, field_ofs)?;
+                let (offset, offset_b) = parse_expr(src_bin, field_ofs + table_idx_b)?;
+                field_ofs += table_idx_b + offset_b;
+
+                ElemMode::Active(table_idx, offset)
+              }
+              0b001 => ElemMode::Passive,
+              _ => ElemMode::Declarative,
+            };
+
+            let uses_exprs = segment_flag & 0b100 != 0;
+            // flags 0 and 4 imply funcref, the others spell out an element kind or a reference type
+            let reftype = if segment_flag & 0b011 == 0 {
+              RefType::FuncRef
+            } else if uses_exprs {
+              let reftype = RefType::try_from(read_byte(src_bin, field_ofs)?)
+                .map_err(|err| Error::from((field_ofs, ErrorKind::InvalidValue, err)))?;
+              field_ofs += 1;
+
+              reftype
+            } else {
+              if read_byte(src_bin, field_ofs)? != 0x00 {
+                return Err(Error::from((
+                  field_ofs,
+                  ErrorKind::InvalidValue,
+                  String::from("invalid element kind"),
+                )));
+              }
+              field_ofs += 1;
+
+              RefType::FuncRef
+            };
+
+            let (n_init, n_init_b) = read_u32(src_bin, field_ofs)?;
+            let mut init_ofs = field_ofs + n_init_b;
+            let init = (0..n_init)
+              .map(|_| {
+                let (expr, expr_b) = if uses_exprs {
+                  parse_expr(src_bin, init_ofs)?
+                } else {
+                  let (func_idx, func_idx_b) = read_u32(src_bin, init_ofs)?;
+
+                  (ConstExpr::new(vec![Instr::RefFunc(func_idx)], init_ofs), func_idx_b)
+                };
+
+                init_ofs += expr_b;
+
+                Ok(expr)
+              })
+              .collect::<Result<_, Error>>()?;
+
+            item_ofs = init_ofs;
+
+            Ok(Element {
+              mode,
+              reftype,
+              init,
+              elements: vec![],
+            })
+          })
+          .collect::<Result<_, Error>>()?;
+
+        item_ofs
       }
       // code section
       10 => {
-        let (section_size, section_size_b) = decode_uleb128(&buf_src[(section_ofs + 1)..]);
-        let (n_item, n_item_b) = decode_uleb128(&buf_src[(section_ofs + section_size_b + 1)..]);
+        let (n_item, n_item_b) = read_u32(src_bin, contents_ofs)?;
+        if n_item as usize != tmp_function_types.len() {
+          return Err(Error::from((
+            contents_ofs,
+            ErrorKind::InvalidSectionFormat,
+            format!(
+              "{n_item} function bodies for {} declared functions",
+              tmp_function_types.len()
+            ),
+          )));
+        }
+
+        let mut item_ofs = contents_ofs + n_item_b;
+        tmp_functions = tmp_function_types
+          .iter()
+          .map(|&func_type_idx| {
+            let (body_size, body_size_b) = read_u32(src_bin, item_ofs)?;
+            let body_ofs = item_ofs + body_size_b;
+            let body_end = body_ofs + (body_size as usize);
+            if body_end > src_bin.len() {
+              return Err(Error::from((
+                src_bin.len(),
+                ErrorKind::UnexpectedEof,
+                format!("function body declares {body_size} bytes"),
+              )));
+            }
+
+            // reads within a function body cannot run past its end
+            let body_bin = &src_bin[..body_end];
 
-        let mut item_ofs = section_ofs + section_size_b + n_item_b + 1;
-        tmp_functions = (0..n_item)
-          .map(|i| {
-            let func_type_idx = tmp_function_types[i as usize];
-            let (body_size, body_size_b) = decode_uleb128(&buf_src[item_ofs..]);
-            let (n_local, n_local_b) = decode_uleb128(&buf_src[(item_ofs + body_size_b)..]);
+            let (n_local, n_local_b) = read_u32(body_bin, body_ofs)?;
 
-            let mut local_ofs = item_ofs + body_size_b + n_local_b;
+            let mut local_ofs = body_ofs + n_local_b;
+            let mut n_total_local = 0;
             let locals = (0..n_local)
               .map(|_| -> Result<Vec<_>, _> {
-                let (n_type_count, n_type_count_b) = decode_uleb128(&buf_src[local_ofs..]);
+                let (n_type_count, n_type_count_b) = read_u32(body_bin, local_ofs)?;
+
+                n_total_local += n_type_count as usize;
+                if n_total_local > MAX_LOCALS {
+                  return Err(Error::from((
+                    local_ofs,
+                    ErrorKind::InvalidValue,
+                    String::from("too many locals"),
+                  )));
+                }
 
-                let valtype = ValType::try_from(buf_src[local_ofs + (n_type_count as usize)])
-                  .map_err(|err| Error::from((local_ofs + (n_type_count as usize), ErrorKind::InvalidValue, err)))?;
+                let valtype_ofs = local_ofs + n_type_count_b;
+                let valtype = ValType::try_from(read_byte(body_bin, valtype_ofs)?)
+                  .map_err(|err| Error::from((valtype_ofs, ErrorKind::InvalidValue, err)))?;
 
-                local_ofs += n_type_count_b + 1;
+                local_ofs = valtype_ofs + 1;
 
-                Ok(iter::repeat(valtype).take(n_type_count as usize).collect())
+                Ok(vec![valtype; n_type_count as usize])
               })
               .collect::<Result<Vec<_>, Error>>()?
               .into_iter()
               .flatten()
               .collect();
 
-            let instr_ofs = if local_ofs == 0 { local_ofs + 1 } else { local_ofs };
-            let parsed_body = parse_func_body(buf_src, instr_ofs)?;
+            let parsed_body = parse_func_body(body_bin, local_ofs)?;
 
-            item_ofs = finalize_section(item_ofs, body_size, body_size_b);
+            let consumed_ofs = parsed_body.offsets.last().map_or(local_ofs, |end_ofs| end_ofs + 1);
+            if consumed_ofs != body_end {
+              return Err(Error::from((
+                body_ofs,
+                ErrorKind::SectionSizeMismatch,
+                format!(
+                  "function body declares {body_size} bytes but {} were consumed",
+                  consumed_ofs - body_ofs
+                ),
+              )));
+            }
+
+            item_ofs = body_end;
 
             Ok(Function {
-              signature_idx: func_type_idx as u32,
+              signature_idx: func_type_idx,
               locals,
               parsed_body,
             })
           })
           .collect::<Result<_, Error>>()?;
 
-        finalize_section(section_ofs, section_size, section_size_b)
+        item_ofs
       }
       // data section
       11 => {
-        let (section_size, section_size_b) = decode_uleb128(&buf_src[(section_ofs + 1)..]);
-        let (n_item, n_item_b) = decode_uleb128(&buf_src[(section_ofs + section_size_b + 1)..]);
+        let (n_item, n_item_b) = read_u32(src_bin, contents_ofs)?;
 
-        let mut item_ofs = section_ofs + section_size_b + n_item_b + 1;
+        let mut item_ofs = contents_ofs + n_item_b;
         tmp_data = (0..n_item)
           .map(|_| {
-            let segment_flag = buf_src[item_ofs];
+            let (segment_flag, segment_flag_b) = read_u32(src_bin, item_ofs)?;
 
-            match segment_flag {
-              0 => todo!(),
-              1 => {
-                let (data_size, data_size_n) = decode_uleb128(&buf_src[(item_ofs + 1)..]);
-
-                let data = parse_utf8(item_ofs + 1 + data_size_n, data_size as usize)?;
+            let mut field_ofs = item_ofs + segment_flag_b;
+            let mode = match segment_flag {
+              0 => {
+                let (offset, offset_b) = parse_expr(src_bin, field_ofs)?;
+                field_ofs += offset_b;
 
-                item_ofs += 1 + data_size_n + (data_size as usize);
+                DataMode::Active(0, offset)
+              }
+              1 => DataMode::Passive,
+              2 => {
+                let (mem_idx, mem_idx_b) = read_u32(src_bin, field_ofs)?;
+                let (offset, offset_b) = parse_expr(src_bin, field_ofs + mem_idx_b)?;
+                field_ofs += mem_idx_b + offset_b;
 
-                Ok(Data {
-                  mode: DataMode::Passive,
-                  data
-                })
-              },
-              2 => todo!(),
+                DataMode::Active(mem_idx, offset)
+              }
               _ => {
-                Err(Error::from((
+                return Err(Error::from((
                   item_ofs,
                   ErrorKind::InvalidValue,
-                  format!("invalid data segment flag"),
+                  String::from("invalid data segment flag"),
                 )))
               }
-            }
+            };
+
+            let (data_size, data_size_b) = read_u32(src_bin, field_ofs)?;
+            let data_ofs = field_ofs + data_size_b;
+            let data = read_bytes(src_bin, data_ofs, data_size as usize)?.to_vec();
+
+            item_ofs = data_ofs + (data_size as usize);
+
+            Ok(Data { mode, data })
           })
           .collect::<Result<_, Error>>()?;
 
-        finalize_section(section_ofs, section_size, section_size_b)
+        item_ofs
       }
       // data count section
       12 => {
-        let (section_size, section_size_b) = decode_uleb128(&buf_src[(section_ofs + 1)..]);
-        let (n_data, _) = decode_uleb128(&buf_src[(section_ofs + section_size_b + 1)..]);
+        let (n_data, n_data_b) = read_u32(src_bin, contents_ofs)?;
 
-        tmp_data_count = n_data;
+        tmp_data_count = Some(n_data);
 
-        finalize_section(section_ofs, section_size, section_size_b)
+        contents_ofs + n_data_b
       }
       _ => {
         return Err(Error::from((
           section_ofs,
           ErrorKind::InvalidSectionFormat,
-          format!("invalid section id {}", buf_src[section_ofs]),
+          format!("invalid section id {section_id}"),
         )));
       }
+    };
+
+    if consumed_ofs != section_end {
+      return Err(Error::from((
+        contents_ofs,
+        ErrorKind::SectionSizeMismatch,
+        format!(
+          "section {section_id} declares {section_size} bytes but {} were consumed",
+          consumed_ofs - contents_ofs
+        ),
+      )));
     }
+
+    section_ofs = section_end;
+  }
+
+  if tmp_functions.len() != tmp_function_types.len() {
+    return Err(Error::from((
+      buf_src.len(),
+      ErrorKind::MissingSection,
+      format!("code section for {} declared functions", tmp_function_types.len()),
+    )));
   }
 
   Ok(Module {
@@ -463,41 +681,280 @@ pub(crate) fn parse(buf_src: &[u8]) -> Result<Module, Error> {
     exports: tmp_exports,
     start_func: tmp_start_func,
     elems: tmp_elems,
-    data: tmp_data
+    data: tmp_data,
+    data_count: tmp_data_count,
+    names: tmp_names,
   })
 }
 
+/// Reads the byte at `ofs`.
+fn read_byte(src_bin: &[u8], ofs: usize) -> Result<u8, Error> {
+  src_bin
+    .get(ofs)
+    .copied()
+    .ok_or_else(|| Error::from((ofs, ErrorKind::UnexpectedEof)))
+}
+
+/// Reads `len` bytes starting at `ofs`.
+fn read_bytes(src_bin: &[u8], ofs: usize, len: usize) -> Result<&[u8], Error> {
+  ofs
+    .checked_add(len)
+    .and_then(|end| src_bin.get(ofs..end))
+    .ok_or_else(|| Error::from((ofs, ErrorKind::UnexpectedEof)))
+}
+
+/// Reads an unsigned LEB128 integer at `ofs` and returns it with its length in bytes.
+fn read_uleb128(src_bin: &[u8], ofs: usize) -> Result<(u64, usize), Error> {
+  let bytes = src_bin.get(ofs..).unwrap_or_default();
+
+  decode_uleb128(bytes).ok_or_else(|| leb128_error(bytes, ofs, MAX_LEB128_LEN))
+}
+
+/// Reads a signed LEB128 integer at `ofs` and returns it with its length in bytes.
+fn read_sleb128(src_bin: &[u8], ofs: usize) -> Result<(i64, usize), Error> {
+  let bytes = src_bin.get(ofs..).unwrap_or_default();
+
+  decode_sleb128(bytes).ok_or_else(|| leb128_error(bytes, ofs, MAX_LEB128_LEN))
+}
+
+/// Reads an unsigned LEB128 integer that must fit in 32 bits, as used for indices, counts and sizes.
+fn read_u32(src_bin: &[u8], ofs: usize) -> Result<(u32, usize), Error> {
+  let bytes = src_bin.get(ofs..).unwrap_or_default();
+
+  decode_u32(bytes).ok_or_else(|| leb128_error(bytes, ofs, MAX_LEB128_LEN_32))
+}
+
+/// Reads a signed LEB128 integer that must fit in 32 bits, as used by `i32.const`.
+fn read_s32(src_bin: &[u8], ofs: usize) -> Result<(i32, usize), Error> {
+  let bytes = src_bin.get(ofs..).unwrap_or_default();
+
+  decode_s32(bytes).ok_or_else(|| leb128_error(bytes, ofs, MAX_LEB128_LEN_32))
+}
+
+/// Tells a value cut off by the end of the input from one encoded in more than `max_len` bytes, and from one whose
+/// last byte has unused bits set.
+fn leb128_error(bytes: &[u8], ofs: usize, max_len: usize) -> Error {
+  let bytes = &bytes[..bytes.len().min(max_len)];
+  if bytes.iter().any(|byte| byte & 0x80 == 0) {
+    Error::from((ofs, ErrorKind::InvalidValue, String::from("integer too large")))
+  } else if bytes.len() < max_len {
+    Error::from((ofs, ErrorKind::UnexpectedEof))
+  } else {
+    Error::from((
+      ofs,
+      ErrorKind::InvalidValue,
+      String::from("integer representation too long"),
+    ))
+  }
+}
+
+/// Parses a UTF-8 string of `len` bytes starting at `ofs`.
+fn parse_utf8(src_bin: &[u8], ofs: usize, len: usize) -> Result<String, Error> {
+  String::from_utf8(Vec::from(read_bytes(src_bin, ofs, len)?))
+    .map_err(|err| Error::from((ofs, ErrorKind::InvalidValue, err.to_string())))
+}
+
 fn parse_func_body(src_bin: &[u8], code_ofs: usize) -> Result<ParsedBody, Error> {
   let mut instr_ofs = code_ofs;
   let mut instrs = vec![];
+  let mut offsets = vec![];
+  // positions of the enclosing structured instructions and their `else`, patched once the matching `end` is reached
+  let mut open_blocks: Vec<(InstrPos, Option<InstrPos>)> = vec![];
 
   loop {
-    let (instr, instr_b) = match src_bin[instr_ofs] {
+    let opcode = read_byte(src_bin, instr_ofs)?;
+    let (instr, instr_b) = match opcode {
       0x00 => (Instr::Unreachable, 1),
       0x01 => (Instr::Nop, 1),
+      0x02 => {
+        let (block_type, block_type_b) = parse_block_type(src_bin, instr_ofs + 1)?;
+        open_blocks.push((instrs.len(), None));
+        (Instr::Block(block_type, 0), 1 + block_type_b)
+      }
+      0x03 => {
+        let (block_type, block_type_b) = parse_block_type(src_bin, instr_ofs + 1)?;
+        open_blocks.push((instrs.len(), None));
+        (Instr::Loop(block_type), 1 + block_type_b)
+      }
+      0x04 => {
+        let (block_type, block_type_b) = parse_block_type(src_bin, instr_ofs + 1)?;
+        open_blocks.push((instrs.len(), None));
+        (Instr::If(block_type, None, 0), 1 + block_type_b)
+      }
+      0x05 => {
+        let else_pos = instrs.len();
+        match open_blocks.last_mut() {
+          Some((if_pos, if_else_pos @ None)) if matches!(instrs[*if_pos], Instr::If(..)) => {
+            *if_else_pos = Some(else_pos);
+            if let Instr::If(_, if_else, _) = &mut instrs[*if_pos] {
+              *if_else = Some(else_pos);
+            }
+          }
+          _ => {
+            return Err(Error::from((
+              instr_ofs,
+              ErrorKind::InvalidInstruction,
+              String::from("else without matching if"),
+            )))
+          }
+        }
+
+        (Instr::Else(0), 1)
+      }
+      0x0B => {
+        let Some((block_pos, else_pos)) = open_blocks.pop() else {
+          offsets.push(instr_ofs);
+          break;
+        };
+
+        let end_pos = instrs.len();
+        if let Instr::Block(_, end) | Instr::If(_, _, end) = &mut instrs[block_pos] {
+          *end = end_pos;
+        }
+        if let Some(Instr::Else(end)) = else_pos.map(|pos| &mut instrs[pos]) {
+          *end = end_pos;
+        }
+
+        (Instr::End, 1)
+      }
+      0x0C => {
+        let (label_idx, label_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::Br(label_idx), 1 + label_idx_b)
+      }
+      0x0D => {
+        let (label_idx, label_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::BrIf(label_idx), 1 + label_idx_b)
+      }
+      0x0E => {
+        let (n_label, n_label_b) = read_u32(src_bin, instr_ofs + 1)?;
+
+        let mut label_ofs = instr_ofs + 1 + n_label_b;
+        let labels = (0..n_label)
+          .map(|_| {
+            let (label_idx, label_idx_b) = read_u32(src_bin, label_ofs)?;
+            label_ofs += label_idx_b;
+
+            Ok(label_idx)
+          })
+          .collect::<Result<_, Error>>()?;
+        let (default_idx, default_idx_b) = read_u32(src_bin, label_ofs)?;
+
+        (
+          Instr::BrTable(labels, default_idx),
+          label_ofs + default_idx_b - instr_ofs,
+        )
+      }
       0x0F => (Instr::Return, 1),
+      0x10 => {
+        let (func_idx, func_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::Call(func_idx), 1 + func_idx_b)
+      }
+      0x11 => {
+        let (type_idx, type_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        let (table_idx, table_idx_b) = read_u32(src_bin, instr_ofs + 1 + type_idx_b)?;
+        (Instr::CallIndirect(table_idx, type_idx), 1 + type_idx_b + table_idx_b)
+      }
 
       0x1A => (Instr::Drop, 1),
       0x1B => (Instr::Select(vec![]), 1),
-      0x1C => (Instr::Select(vec![todo!()]), 1 /* TODO: */),
+      0x1C => {
+        let (n_type, n_type_b) = read_u32(src_bin, instr_ofs + 1)?;
+        let types_ofs = instr_ofs + 1 + n_type_b;
+        let types = (types_ofs..(types_ofs + (n_type as usize)))
+          .map(|ofs| {
+            ValType::try_from(read_byte(src_bin, ofs)?).map_err(|err| Error::from((ofs, ErrorKind::InvalidValue, err)))
+          })
+          .collect::<Result<Vec<_>, _>>()?;
+
+        (Instr::Select(types), 1 + n_type_b + (n_type as usize))
+      }
+
+      0x20 => {
+        let (local_idx, local_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::LocalGet(local_idx), 1 + local_idx_b)
+      }
+      0x21 => {
+        let (local_idx, local_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::LocalSet(local_idx), 1 + local_idx_b)
+      }
+      0x22 => {
+        let (local_idx, local_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::LocalTee(local_idx), 1 + local_idx_b)
+      }
+      0x23 => {
+        let (global_idx, global_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::GlobalGet(global_idx), 1 + global_idx_b)
+      }
+      0x24 => {
+        let (global_idx, global_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::GlobalSet(global_idx), 1 + global_idx_b)
+      }
+      0x25 => {
+        let (table_idx, table_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::TableGet(table_idx), 1 + table_idx_b)
+      }
+      0x26 => {
+        let (table_idx, table_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::TableSet(table_idx), 1 + table_idx_b)
+      }
+
+      0x28..=0x3E => {
+        let ((mem_idx, offset, align), memarg_b) = parse_memarg(src_bin, instr_ofs + 1)?;
+        let instr = match opcode {
+          0x28 => Instr::I32Load(mem_idx, offset, align),
+          0x29 => Instr::I64Load(mem_idx, offset, align),
+          0x2A => Instr::F32Load(mem_idx, offset, align),
+          0x2B => Instr::F64Load(mem_idx, offset, align),
+          0x2C => Instr::I32Load8S(mem_idx, offset, align),
+          0x2D => Instr::I32Load8U(mem_idx, offset, align),
+          0x2E => Instr::I32Load16S(mem_idx, offset, align),
+          0x2F => Instr::I32Load16U(mem_idx, offset, align),
+          0x30 => Instr::I64Load8S(mem_idx, offset, align),
+          0x31 => Instr::I64Load8U(mem_idx, offset, align),
+          0x32 => Instr::I64Load16S(mem_idx, offset, align),
+          0x33 => Instr::I64Load16U(mem_idx, offset, align),
+          0x34 => Instr::I64Load32S(mem_idx, offset, align),
+          0x35 => Instr::I64Load32U(mem_idx, offset, align),
+          0x36 => Instr::I32Store(mem_idx, offset, align),
+          0x37 => Instr::I64Store(mem_idx, offset, align),
+          0x38 => Instr::F32Store(mem_idx, offset, align),
+          0x39 => Instr::F64Store(mem_idx, offset, align),
+          0x3A => Instr::I32Store8(mem_idx, offset, align),
+          0x3B => Instr::I32Store16(mem_idx, offset, align),
+          0x3C => Instr::I64Store8(mem_idx, offset, align),
+          0x3D => Instr::I64Store16(mem_idx, offset, align),
+          _ => Instr::I64Store32(mem_idx, offset, align),
+        };
+        (instr, 1 + memarg_b)
+      }
+      0x3F => {
+        let (mem_idx, mem_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::MemorySize(mem_idx), 1 + mem_idx_b)
+      }
+      0x40 => {
+        let (mem_idx, mem_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::MemoryGrow(mem_idx), 1 + mem_idx_b)
+      }
 
       0x41 => {
-        let (val, val_b) = decode_sleb128(&src_bin[(instr_ofs + 1)..]);
-        (Instr::I32Const(val as i32), 1 + val_b)
+        let (val, val_b) = read_s32(src_bin, instr_ofs + 1)?;
+        (Instr::I32Const(val), 1 + val_b)
       }
       0x42 => {
-        let (val, val_b) = decode_sleb128(&src_bin[(instr_ofs + 1)..]);
+        let (val, val_b) = read_sleb128(src_bin, instr_ofs + 1)?;
         (Instr::I64Const(val), 1 + val_b)
       }
       0x43 => {
-        let arr: [u8; 4] = src_bin[(instr_ofs + 1)..(instr_ofs + 1 + 4)].try_into().unwrap();
+        let mut arr = [0; 4];
+        arr.copy_from_slice(read_bytes(src_bin, instr_ofs + 1, 4)?);
         let val = f32::from_le_bytes(arr);
         (Instr::F32Const(val), 1 + 4)
       }
       0x44 => {
-        let arr: [u8; 8] = src_bin[(instr_ofs + 1)..(instr_ofs + 1 + 8)].try_into().unwrap();
+        let mut arr = [0; 8];
+        arr.copy_from_slice(read_bytes(src_bin, instr_ofs + 1, 8)?);
         let val = f64::from_le_bytes(arr);
-        (Instr::F64Const(val), 1 + 4)
+        (Instr::F64Const(val), 1 + 8)
       }
 
       0x45 => (Instr::I32Eqz, 1),
@@ -606,24 +1063,642 @@ fn parse_func_body(src_bin: &[u8], code_ofs: usize) -> Result<ParsedBody, Error>
       0xA5 => (Instr::F64Max, 1),
       0xA6 => (Instr::F64Copysign, 1),
 
-      0x0B => break,
+      0xA7 => (Instr::I32WrapI64, 1),
+      0xA8 => (Instr::I32TruncF32S, 1),
+      0xA9 => (Instr::I32TruncF32U, 1),
+      0xAA => (Instr::I32TruncF64S, 1),
+      0xAB => (Instr::I32TruncF64U, 1),
+      0xAC => (Instr::I64ExtendI32S, 1),
+      0xAD => (Instr::I64ExtendI32U, 1),
+      0xAE => (Instr::I64TruncF32S, 1),
+      0xAF => (Instr::I64TruncF32U, 1),
+      0xB0 => (Instr::I64TruncF64S, 1),
+      0xB1 => (Instr::I64TruncF64U, 1),
+      0xB2 => (Instr::F32ConvertI32S, 1),
+      0xB3 => (Instr::F32ConvertI32U, 1),
+      0xB4 => (Instr::F32ConvertI64S, 1),
+      0xB5 => (Instr::F32ConvertI64U, 1),
+      0xB6 => (Instr::F32DemoteF64, 1),
+      0xB7 => (Instr::F64ConvertI32S, 1),
+      0xB8 => (Instr::F64ConvertI32U, 1),
+      0xB9 => (Instr::F64ConvertI64S, 1),
+      0xBA => (Instr::F64ConvertI64U, 1),
+      0xBB => (Instr::F64PromoteF32, 1),
+      0xBC => (Instr::I32ReinterpretF32, 1),
+      0xBD => (Instr::I64ReinterpretF64, 1),
+      0xBE => (Instr::F32ReinterpretI32, 1),
+      0xBF => (Instr::F64ReinterpretI64, 1),
+
+      0xC0 => (Instr::I32Extend8S, 1),
+      0xC1 => (Instr::I32Extend16S, 1),
+      0xC2 => (Instr::I64Extend8S, 1),
+      0xC3 => (Instr::I64Extend16S, 1),
+      0xC4 => (Instr::I64Extend32S, 1),
+
+      0xD0 => {
+        let heap_type = match read_byte(src_bin, instr_ofs + 1)? {
+          0x70 => HeapType::Func,
+          0x6F => HeapType::Extern,
+          _ => {
+            return Err(Error::from((
+              instr_ofs + 1,
+              ErrorKind::InvalidValue,
+              String::from("invalid heap type"),
+            )))
+          }
+        };
+        (Instr::RefNull(heap_type), 2)
+      }
+      0xD1 => (Instr::RefIsNull, 1),
+      0xD2 => {
+        let (func_idx, func_idx_b) = read_u32(src_bin, instr_ofs + 1)?;
+        (Instr::RefFunc(func_idx), 1 + func_idx_b)
+      }
+
+      0xFC => {
+        let (subopcode, subopcode_b) = read_u32(src_bin, instr_ofs + 1)?;
+        let imm_ofs = instr_ofs + 1 + subopcode_b;
+        let (instr, imm_b) = match subopcode {
+          8 => {
+            let (data_idx, data_idx_b) = read_u32(src_bin, imm_ofs)?;
+            let (mem_idx, mem_idx_b) = read_u32(src_bin, imm_ofs + data_idx_b)?;
+            (Instr::MemoryInit(mem_idx, data_idx), data_idx_b + mem_idx_b)
+          }
+          9 => {
+            let (data_idx, data_idx_b) = read_u32(src_bin, imm_ofs)?;
+            (Instr::DataDrop(data_idx), data_idx_b)
+          }
+          10 => {
+            let (dst_mem_idx, dst_mem_idx_b) = read_u32(src_bin, imm_ofs)?;
+            let (src_mem_idx, src_mem_idx_b) = read_u32(src_bin, imm_ofs + dst_mem_idx_b)?;
+            (
+              Instr::MemoryCopy(dst_mem_idx, src_mem_idx),
+              dst_mem_idx_b + src_mem_idx_b,
+            )
+          }
+          11 => {
+            let (mem_idx, mem_idx_b) = read_u32(src_bin, imm_ofs)?;
+            (Instr::MemoryFill(mem_idx), mem_idx_b)
+          }
+          12 => {
+            let (elem_idx, elem_idx_b) = read_u32(src_bin, imm_ofs)?;
+            let (table_idx, table_idx_b) = read_u32(src_bin, imm_ofs + elem_idx_b)?;
+            (Instr::TableInit(table_idx, elem_idx), elem_idx_b + table_idx_b)
+          }
+          13 => {
+            let (elem_idx, elem_idx_b) = read_u32(src_bin, imm_ofs)?;
+            (Instr::ElemDrop(elem_idx), elem_idx_b)
+          }
+          14 => {
+            let (dst_table_idx, dst_table_idx_b) = read_u32(src_bin, imm_ofs)?;
+            let (src_table_idx, src_table_idx_b) = read_u32(src_bin, imm_ofs + dst_table_idx_b)?;
+            (
+              Instr::TableCopy(dst_table_idx, src_table_idx),
+              dst_table_idx_b + src_table_idx_b,
+            )
+          }
+          15..=17 => {
+            let (table_idx, table_idx_b) = read_u32(src_bin, imm_ofs)?;
+            let instr = match subopcode {
+              15 => Instr::TableGrow(table_idx),
+              16 => Instr::TableSize(table_idx),
+              _ => Instr::TableFill(table_idx),
+            };
+            (instr, table_idx_b)
+          }
+          0 => (Instr::I32TruncSatF32S, 0),
+          1 => (Instr::I32TruncSatF32U, 0),
+          2 => (Instr::I32TruncSatF64S, 0),
+          3 => (Instr::I32TruncSatF64U, 0),
+          4 => (Instr::I64TruncSatF32S, 0),
+          5 => (Instr::I64TruncSatF32U, 0),
+          6 => (Instr::I64TruncSatF64S, 0),
+          7 => (Instr::I64TruncSatF64U, 0),
+          _ => {
+            return Err(Error::from((
+              instr_ofs,
+              ErrorKind::InvalidInstruction,
+              format!("invalid instruction code 0xFC {subopcode}"),
+            )))
+          }
+        };
+
+        (instr, 1 + subopcode_b + imm_b)
+      }
+      0xFD => {
+        let (subopcode, subopcode_b) = read_u32(src_bin, instr_ofs + 1)?;
+        let imm_ofs = instr_ofs + 1 + subopcode_b;
+        let (instr, imm_b) = match subopcode {
+          0..=11 | 92 | 93 => {
+            let ((mem_idx, offset, align), memarg_b) = parse_memarg(src_bin, imm_ofs)?;
+            let instr = match subopcode {
+              0 => Instr::V128Load(mem_idx, offset, align),
+              1 => Instr::V128Load8X8S(mem_idx, offset, align),
+              2 => Instr::V128Load8X8U(mem_idx, offset, align),
+              3 => Instr::V128Load16X4S(mem_idx, offset, align),
+              4 => Instr::V128Load16X4U(mem_idx, offset, align),
+              5 => Instr::V128Load32X2S(mem_idx, offset, align),
+              6 => Instr::V128Load32X2U(mem_idx, offset, align),
+              7 => Instr::V128Load8Splat(mem_idx, offset, align),
+              8 => Instr::V128Load16Splat(mem_idx, offset, align),
+              9 => Instr::V128Load32Splat(mem_idx, offset, align),
+              10 => Instr::V128Load64Splat(mem_idx, offset, align),
+              11 => Instr::V128Store(mem_idx, offset, align),
+              92 => Instr::V128Load32Zero(mem_idx, offset, align),
+              _ => Instr::V128Load64Zero(mem_idx, offset, align),
+            };
+            (instr, memarg_b)
+          }
+          12 => {
+            let mut arr = [0; 16];
+            arr.copy_from_slice(read_bytes(src_bin, imm_ofs, 16)?);
+            (Instr::V128Const(V128Value::from_le_bytes(arr)), 16)
+          }
+          13 => {
+            let mut lanes = [0; 16];
+            lanes.copy_from_slice(read_bytes(src_bin, imm_ofs, 16)?);
+            (Instr::I8X16Shuffle(lanes), 16)
+          }
+          21..=34 => {
+            let lane = read_byte(src_bin, imm_ofs)?;
+            let instr = match subopcode {
+              21 => Instr::I8X16ExtractLaneS(lane),
+              22 => Instr::I8X16ExtractLaneU(lane),
+              23 => Instr::I8X16ReplaceLane(lane),
+              24 => Instr::I16X8ExtractLaneS(lane),
+              25 => Instr::I16X8ExtractLaneU(lane),
+              26 => Instr::I16X8ReplaceLane(lane),
+              27 => Instr::I32X4ExtractLane(lane),
+              28 => Instr::I32X4ReplaceLane(lane),
+              29 => Instr::I64X2ExtractLane(lane),
+              30 => Instr::I64X2ReplaceLane(lane),
+              31 => Instr::F32X4ExtractLane(lane),
+              32 => Instr::F32X4ReplaceLane(lane),
+              33 => Instr::F64X2ExtractLane(lane),
+              _ => Instr::F64X2ReplaceLane(lane),
+            };
+            (instr, 1)
+          }
+          84..=91 => {
+            let ((mem_idx, offset, align), memarg_b) = parse_memarg(src_bin, imm_ofs)?;
+            let lane = read_byte(src_bin, imm_ofs + memarg_b)?;
+            let instr = match subopcode {
+              84 => Instr::V128Load8Lane(mem_idx, offset, align, lane),
+              85 => Instr::V128Load16Lane(mem_idx, offset, align, lane),
+              86 => Instr::V128Load32Lane(mem_idx, offset, align, lane),
+              87 => Instr::V128Load64Lane(mem_idx, offset, align, lane),
+              88 => Instr::V128Store8Lane(mem_idx, offset, align, lane),
+              89 => Instr::V128Store16Lane(mem_idx, offset, align, lane),
+              90 => Instr::V128Store32Lane(mem_idx, offset, align, lane),
+              _ => Instr::V128Store64Lane(mem_idx, offset, align, lane),
+            };
+            (instr, memarg_b + 1)
+          }
+          _ => {
+            let instr = match subopcode {
+              14 => Instr::I8X16Swizzle,
+              15 => Instr::I8X16Splat,
+              16 => Instr::I16X8Splat,
+              17 => Instr::I32X4Splat,
+              18 => Instr::I64X2Splat,
+              19 => Instr::F32X4Splat,
+              20 => Instr::F64X2Splat,
+              35 => Instr::I8X16Eq,
+              36 => Instr::I8X16Ne,
+              37 => Instr::I8X16LtS,
+              38 => Instr::I8X16LtU,
+              39 => Instr::I8X16GtS,
+              40 => Instr::I8X16GtU,
+              41 => Instr::I8X16LeS,
+              42 => Instr::I8X16LeU,
+              43 => Instr::I8X16GeS,
+              44 => Instr::I8X16GeU,
+              45 => Instr::I16X8Eq,
+              46 => Instr::I16X8Ne,
+              47 => Instr::I16X8LtS,
+              48 => Instr::I16X8LtU,
+              49 => Instr::I16X8GtS,
+              50 => Instr::I16X8GtU,
+              51 => Instr::I16X8LeS,
+              52 => Instr::I16X8LeU,
+              53 => Instr::I16X8GeS,
+              54 => Instr::I16X8GeU,
+              55 => Instr::I32X4Eq,
+              56 => Instr::I32X4Ne,
+              57 => Instr::I32X4LtS,
+              58 => Instr::I32X4LtU,
+              59 => Instr::I32X4GtS,
+              60 => Instr::I32X4GtU,
+              61 => Instr::I32X4LeS,
+              62 => Instr::I32X4LeU,
+              63 => Instr::I32X4GeS,
+              64 => Instr::I32X4GeU,
+              65 => Instr::F32X4Eq,
+              66 => Instr::F32X4Ne,
+              67 => Instr::F32X4Lt,
+              68 => Instr::F32X4Gt,
+              69 => Instr::F32X4Le,
+              70 => Instr::F32X4Ge,
+              71 => Instr::F64X2Eq,
+              72 => Instr::F64X2Ne,
+              73 => Instr::F64X2Lt,
+              74 => Instr::F64X2Gt,
+              75 => Instr::F64X2Le,
+              76 => Instr::F64X2Ge,
+              77 => Instr::V128Not,
+              78 => Instr::V128And,
+              79 => Instr::V128Andnot,
+              80 => Instr::V128Or,
+              81 => Instr::V128Xor,
+              82 => Instr::V128Bitselect,
+              83 => Instr::V128AnyTrue,
+              94 => Instr::F32X4DemoteF64X2Zero,
+              95 => Instr::F64X2PromoteLowF32X4,
+              96 => Instr::I8X16Abs,
+              97 => Instr::I8X16Neg,
+              98 => Instr::I8X16Popcnt,
+              99 => Instr::I8X16AllTrue,
+              100 => Instr::I8X16Bitmask,
+              101 => Instr::I8X16NarrowI16X8S,
+              102 => Instr::I8X16NarrowI16X8U,
+              103 => Instr::F32X4Ceil,
+              104 => Instr::F32X4Floor,
+              105 => Instr::F32X4Trunc,
+              106 => Instr::F32X4Nearest,
+              107 => Instr::I8X16Shl,
+              108 => Instr::I8X16ShrS,
+              109 => Instr::I8X16ShrU,
+              110 => Instr::I8X16Add,
+              111 => Instr::I8X16AddSatS,
+              112 => Instr::I8X16AddSatU,
+              113 => Instr::I8X16Sub,
+              114 => Instr::I8X16SubSatS,
+              115 => Instr::I8X16SubSatU,
+              116 => Instr::F64X2Ceil,
+              117 => Instr::F64X2Floor,
+              118 => Instr::I8X16MinS,
+              119 => Instr::I8X16MinU,
+              120 => Instr::I8X16MaxS,
+              121 => Instr::I8X16MaxU,
+              122 => Instr::F64X2Trunc,
+              123 => Instr::I8X16AvgrU,
+              124 => Instr::I16X8ExtaddPairwiseI8X16S,
+              125 => Instr::I16X8ExtaddPairwiseI8X16U,
+              126 => Instr::I32X4ExtaddPairwiseI16X8S,
+              127 => Instr::I32X4ExtaddPairwiseI16X8U,
+              128 => Instr::I16X8Abs,
+              129 => Instr::I16X8Neg,
+              130 => Instr::I16X8Q15MulrSatS,
+              131 => Instr::I16X8AllTrue,
+              132 => Instr::I16X8Bitmask,
+              133 => Instr::I16X8NarrowI32X4S,
+              134 => Instr::I16X8NarrowI32X4U,
+              135 => Instr::I16X8ExtendLowI8X16S,
+              136 => Instr::I16X8ExtendHighI8X16S,
+              137 => Instr::I16X8ExtendLowI8X16U,
+              138 => Instr::I16X8ExtendHighI8X16U,
+              139 => Instr::I16X8Shl,
+              140 => Instr::I16X8ShrS,
+              141 => Instr::I16X8ShrU,
+              142 => Instr::I16X8Add,
+              143 => Instr::I16X8AddSatS,
+              144 => Instr::I16X8AddSatU,
+              145 => Instr::I16X8Sub,
+              146 => Instr::I16X8SubSatS,
+              147 => Instr::I16X8SubSatU,
+              148 => Instr::F64X2Nearest,
+              149 => Instr::I16X8Mul,
+              150 => Instr::I16X8MinS,
+              151 => Instr::I16X8MinU,
+              152 => Instr::I16X8MaxS,
+              153 => Instr::I16X8MaxU,
+              155 => Instr::I16X8AvgrU,
+              156 => Instr::I16X8ExtmulLowI8X16S,
+              157 => Instr::I16X8ExtmulHighI8X16S,
+              158 => Instr::I16X8ExtmulLowI8X16U,
+              159 => Instr::I16X8ExtmulHighI8X16U,
+              160 => Instr::I32X4Abs,
+              161 => Instr::I32X4Neg,
+              163 => Instr::I32X4AllTrue,
+              164 => Instr::I32X4Bitmask,
+              167 => Instr::I32X4ExtendLowI16X8S,
+              168 => Instr::I32X4ExtendHighI16X8S,
+              169 => Instr::I32X4ExtendLowI16X8U,
+              170 => Instr::I32X4ExtendHighI16X8U,
+              171 => Instr::I32X4Shl,
+              172 => Instr::I32X4ShrS,
+              173 => Instr::I32X4ShrU,
+              174 => Instr::I32X4Add,
+              177 => Instr::I32X4Sub,
+              181 => Instr::I32X4Mul,
+              182 => Instr::I32X4MinS,
+              183 => Instr::I32X4MinU,
+              184 => Instr::I32X4MaxS,
+              185 => Instr::I32X4MaxU,
+              186 => Instr::I32X4DotI16X8S,
+              188 => Instr::I32X4ExtmulLowI16X8S,
+              189 => Instr::I32X4ExtmulHighI16X8S,
+              190 => Instr::I32X4ExtmulLowI16X8U,
+              191 => Instr::I32X4ExtmulHighI16X8U,
+              192 => Instr::I64X2Abs,
+              193 => Instr::I64X2Neg,
+              195 => Instr::I64X2AllTrue,
+              196 => Instr::I64X2Bitmask,
+              199 => Instr::I64X2ExtendLowI32X4S,
+              200 => Instr::I64X2ExtendHighI32X4S,
+              201 => Instr::I64X2ExtendLowI32X4U,
+              202 => Instr::I64X2ExtendHighI32X4U,
+              203 => Instr::I64X2Shl,
+              204 => Instr::I64X2ShrS,
+              205 => Instr::I64X2ShrU,
+              206 => Instr::I64X2Add,
+              209 => Instr::I64X2Sub,
+              213 => Instr::I64X2Mul,
+              214 => Instr::I64X2Eq,
+              215 => Instr::I64X2Ne,
+              216 => Instr::I64X2LtS,
+              217 => Instr::I64X2GtS,
+              218 => Instr::I64X2LeS,
+              219 => Instr::I64X2GeS,
+              220 => Instr::I64X2ExtmulLowI32X4S,
+              221 => Instr::I64X2ExtmulHighI32X4S,
+              222 => Instr::I64X2ExtmulLowI32X4U,
+              223 => Instr::I64X2ExtmulHighI32X4U,
+              224 => Instr::F32X4Abs,
+              225 => Instr::F32X4Neg,
+              227 => Instr::F32X4Sqrt,
+              228 => Instr::F32X4Add,
+              229 => Instr::F32X4Sub,
+              230 => Instr::F32X4Mul,
+              231 => Instr::F32X4Div,
+              232 => Instr::F32X4Min,
+              233 => Instr::F32X4Max,
+              234 => Instr::F32X4Pmin,
+              235 => Instr::F32X4Pmax,
+              236 => Instr::F64X2Abs,
+              237 => Instr::F64X2Neg,
+              239 => Instr::F64X2Sqrt,
+              240 => Instr::F64X2Add,
+              241 => Instr::F64X2Sub,
+              242 => Instr::F64X2Mul,
+              243 => Instr::F64X2Div,
+              244 => Instr::F64X2Min,
+              245 => Instr::F64X2Max,
+              246 => Instr::F64X2Pmin,
+              247 => Instr::F64X2Pmax,
+              248 => Instr::I32X4TruncSatF32X4S,
+              249 => Instr::I32X4TruncSatF32X4U,
+              250 => Instr::F32X4ConvertI32X4S,
+              251 => Instr::F32X4ConvertI32X4U,
+              252 => Instr::I32X4TruncSatF64X2SZero,
+              253 => Instr::I32X4TruncSatF64X2UZero,
+              254 => Instr::F64X2ConvertLowI32X4S,
+              255 => Instr::F64X2ConvertLowI32X4U,
+              256 => Instr::I8X16RelaxedSwizzle,
+              257 => Instr::I32X4RelaxedTruncF32X4S,
+              258 => Instr::I32X4RelaxedTruncF32X4U,
+              259 => Instr::I32X4RelaxedTruncF64X2SZero,
+              260 => Instr::I32X4RelaxedTruncF64X2UZero,
+              261 => Instr::F32X4RelaxedMadd,
+              262 => Instr::F32X4RelaxedNmadd,
+              263 => Instr::F64X2RelaxedMadd,
+              264 => Instr::F64X2RelaxedNmadd,
+              265 => Instr::I8X16RelaxedLaneselect,
+              266 => Instr::I16X8RelaxedLaneselect,
+              267 => Instr::I32X4RelaxedLaneselect,
+              268 => Instr::I64X2RelaxedLaneselect,
+              269 => Instr::F32X4RelaxedMin,
+              270 => Instr::F32X4RelaxedMax,
+              271 => Instr::F64X2RelaxedMin,
+              272 => Instr::F64X2RelaxedMax,
+              273 => Instr::I16X8RelaxedQ15MulrS,
+              274 => Instr::I16X8RelaxedDotI8X16I7X16S,
+              275 => Instr::I32X4RelaxedDotI8X16I7X16AddS,
+              _ => {
+                return Err(Error::from((
+                  instr_ofs,
+                  ErrorKind::InvalidInstruction,
+                  format!("invalid instruction code 0xFD {subopcode}"),
+                )))
+              }
+            };
+            (instr, 0)
+          }
+        };
+
+        (instr, 1 + subopcode_b + imm_b)
+      }
+
       _ => {
         return Err(Error::from((
           instr_ofs,
           ErrorKind::InvalidInstruction,
-          format!("invalid instruction code {}", src_bin[instr_ofs]),
+          format!("invalid instruction code {opcode}"),
         )))
       }
     };
 
+    offsets.push(instr_ofs);
     instr_ofs += instr_b;
 
     instrs.push(instr);
   }
 
-  Ok(ParsedBody::new(instrs))
+  Ok(ParsedBody::new(instrs, offsets))
 }
 
-fn parse_expr(src_bin: &[u8], code_ofs: usize) -> Result<(), Error> {
-  todo!()
+/// Parses a length-prefixed UTF-8 name and returns it with its length in bytes.
+fn parse_name(src_bin: &[u8], ofs: usize) -> Result<(String, usize), Error> {
+  let (len, len_b) = read_u32(src_bin, ofs)?;
+  let name = parse_utf8(src_bin, ofs + len_b, len as usize)?;
+
+  Ok((name, len_b + (len as usize)))
+}
+
+/// Decodes the subsections of the `name` custom section, which runs from `ofs` to the end of `src_bin`.
+fn parse_names(src_bin: &[u8], ofs: usize) -> Result<Names, Error> {
+  let mut names = Names::default();
+
+  let mut subsection_ofs = ofs;
+  while subsection_ofs < src_bin.len() {
+    let subsection_id = read_byte(src_bin, subsection_ofs)?;
+    let (subsection_size, subsection_size_b) = read_u32(src_bin, subsection_ofs + 1)?;
+
+    let contents_ofs = subsection_ofs + 1 + subsection_size_b;
+    let subsection_end = contents_ofs + (subsection_size as usize);
+    if subsection_end > src_bin.len() {
+      return Err(Error::from((src_bin.len(), ErrorKind::UnexpectedEof)));
+    }
+
+    let sub_bin = &src_bin[..subsection_end];
+    match subsection_id {
+      0 => names.module = Some(parse_name(sub_bin, contents_ofs)?.0),
+      1 => names.funcs = parse_name_map(sub_bin, contents_ofs)?.0,
+      2 => names.locals = parse_indirect_name_map(sub_bin, contents_ofs)?,
+      3 => names.labels = parse_indirect_name_map(sub_bin, contents_ofs)?,
+      4 => names.types = parse_name_map(sub_bin, contents_ofs)?.0,
+      5 => names.tables = parse_name_map(sub_bin, contents_ofs)?.0,
+      6 => names.memories = parse_name_map(sub_bin, contents_ofs)?.0,
+      7 => names.globals = parse_name_map(sub_bin, contents_ofs)?.0,
+      8 => names.elems = parse_name_map(sub_bin, contents_ofs)?.0,
+      9 => names.data = parse_name_map(sub_bin, contents_ofs)?.0,
+      10 => names.fields = parse_indirect_name_map(sub_bin, contents_ofs)?,
+      // subsections from later proposals are skipped
+      _ => {}
+    }
+
+    subsection_ofs = subsection_end;
+  }
+
+  Ok(names)
+}
+
+/// Parses a vector of index and name pairs and returns it with the offset following it.
+fn parse_name_map(src_bin: &[u8], ofs: usize) -> Result<(NameMap, usize), Error> {
+  let (n_item, n_item_b) = read_u32(src_bin, ofs)?;
+
+  let mut item_ofs = ofs + n_item_b;
+  let name_map = (0..n_item)
+    .map(|_| {
+      let (idx, idx_b) = read_u32(src_bin, item_ofs)?;
+      let (name, name_b) = parse_name(src_bin, item_ofs + idx_b)?;
+
+      item_ofs += idx_b + name_b;
+
+      Ok((idx, name))
+    })
+    .collect::<Result<_, Error>>()?;
+
+  Ok((name_map, item_ofs))
+}
+
+/// Parses a vector of indices, each with the name map of the entities nested in it.
+fn parse_indirect_name_map(src_bin: &[u8], ofs: usize) -> Result<IndirectNameMap, Error> {
+  let (n_item, n_item_b) = read_u32(src_bin, ofs)?;
+
+  let mut item_ofs = ofs + n_item_b;
+  (0..n_item)
+    .map(|_| {
+      let (idx, idx_b) = read_u32(src_bin, item_ofs)?;
+      let (name_map, next_item_ofs) = parse_name_map(src_bin, item_ofs + idx_b)?;
+
+      item_ofs = next_item_ofs;
+
+      Ok((idx, name_map))
+    })
+    .collect()
+}
+
+/// Parses the limits of a table or memory type.
+fn parse_limit(src_bin: &[u8], ofs: usize) -> Result<(Limit, usize), Error> {
+  let limit_flag = read_byte(src_bin, ofs)?;
+  let (initial, initial_b) = read_u32(src_bin, ofs + 1)?;
+
+  match limit_flag {
+    0 => Ok((Limit(initial, None), 1 + initial_b)),
+    1 => {
+      let (max, max_b) = read_u32(src_bin, ofs + 1 + initial_b)?;
+
+      Ok((Limit(initial, Some(max)), 1 + initial_b + max_b))
+    }
+    _ => Err(Error::from((
+      ofs,
+      ErrorKind::InvalidValue,
+      String::from("limit flag byte is invalid"),
+    ))),
+  }
+}
+
+/// Parses the memory argument of a load or store into its memory index, offset and alignment exponent.
+///
+/// Bit 6 of the alignment flags an explicit memory index following it, as encoded by the multi-memory proposal;
+/// otherwise the instruction accesses memory 0. The offset is decoded as 64 bits for memory64, and validation bounds
+/// it for 32-bit memories.
+fn parse_memarg(src_bin: &[u8], ofs: usize) -> Result<((MemIdx, u64, u32), usize), Error> {
+  const MEM_IDX_FLAG: u32 = 1 << 6;
+
+  let (flags, flags_b) = read_u32(src_bin, ofs)?;
+  let (mem_idx, mem_idx_b) = if flags & MEM_IDX_FLAG != 0 {
+    read_u32(src_bin, ofs + flags_b)?
+  } else {
+    (0, 0)
+  };
+  let (offset, offset_b) = read_uleb128(src_bin, ofs + flags_b + mem_idx_b)?;
+
+  Ok(((mem_idx, offset, flags & !MEM_IDX_FLAG), flags_b + mem_idx_b + offset_b))
+}
+
+/// Parses the type of a memory, whose flags select a maximum and 64-bit addresses.
+fn parse_memory_type(src_bin: &[u8], ofs: usize) -> Result<(MemoryType, usize), Error> {
+  const MAX_FLAG: u8 = 0x01;
+  const SHARED_FLAG: u8 = 0x02;
+  const I64_FLAG: u8 = 0x04;
+
+  let flags = read_byte(src_bin, ofs)?;
+  if flags & !(MAX_FLAG | SHARED_FLAG | I64_FLAG) != 0 {
+    return Err(Error::from((
+      ofs,
+      ErrorKind::InvalidValue,
+      String::from("limit flag byte is invalid"),
+    )));
+  }
+  if flags & SHARED_FLAG != 0 {
+    return Err(Error::from((
+      ofs,
+      ErrorKind::Unsupported,
+      String::from("shared memory"),
+    )));
+  }
+
+  let idx_type = if flags & I64_FLAG != 0 {
+    IndexType::I64
+  } else {
+    IndexType::I32
+  };
+  // sizes of 32-bit memories are encoded as u32
+  let read_size = |ofs| match idx_type {
+    IndexType::I32 => read_u32(src_bin, ofs).map(|(size, size_b)| (u64::from(size), size_b)),
+    IndexType::I64 => read_uleb128(src_bin, ofs),
+  };
+  let (min, min_b) = read_size(ofs + 1)?;
+  let (max, max_b) = if flags & MAX_FLAG != 0 {
+    let (max, max_b) = read_size(ofs + 1 + min_b)?;
+    (Some(max), max_b)
+  } else {
+    (None, 0)
+  };
+
+  Ok((MemoryType { min, max, idx_type }, 1 + min_b + max_b))
+}
+
+/// Parses a block type, which is either empty, a single value type, or a type index encoded as a positive s33.
+fn parse_block_type(src_bin: &[u8], ofs: usize) -> Result<(BlockType, usize), Error> {
+  match read_byte(src_bin, ofs)? {
+    0x40 => Ok((BlockType::Empty, 1)),
+    byte @ (0x6F | 0x70 | 0x7B..=0x7F) => {
+      let valtype = ValType::try_from(byte).map_err(|err| Error::from((ofs, ErrorKind::InvalidValue, err)))?;
+      Ok((BlockType::Value(valtype), 1))
+    }
+    _ => {
+      let (type_idx, type_idx_b) = read_sleb128(src_bin, ofs)?;
+      if !(0..=(u32::MAX as i64)).contains(&type_idx) {
+        return Err(Error::from((
+          ofs,
+          ErrorKind::InvalidValue,
+          String::from("invalid block type"),
+        )));
+      }
+
+      Ok((BlockType::TypeIdx(type_idx as u32), type_idx_b))
+    }
+  }
+}
+
+/// Parses a constant expression and returns it with its length in bytes, including the final `end`.
+/// Whether its instructions are constant is left to validation.
+fn parse_expr(src_bin: &[u8], code_ofs: usize) -> Result<(ConstExpr, usize), Error> {
+  let ParsedBody { instrs, offsets } = parse_func_body(src_bin, code_ofs)?;
+  let end_ofs = offsets.last().copied().unwrap_or(code_ofs);
+
+  Ok((ConstExpr::new(instrs, code_ofs), end_ofs + 1 - code_ofs))
 }
@@ -0,0 +1,195 @@
+//! Lane operations of the vector instructions that do not map a single lane to a single lane.
+//!
+//! Everything is computed lane by lane on the host's scalar types, so results do not depend on the SIMD
+//! extensions of the host.
+
+use core::{
+  array,
+  ops::{
+    Add,
+    Mul,
+  },
+};
+
+use crate::module::value::V128Value;
+
+/// Applies `f` to the pairs of lanes at the same position.
+pub(crate) fn zip<T: Copy, U, const N: usize>(a: [T; N], b: [T; N], f: impl Fn(T, T) -> U) -> [U; N] {
+  array::from_fn(|i| f(a[i], b[i]))
+}
+
+/// Widens the low or high half of the lanes to lanes of twice the width.
+pub(crate) fn extend<T: Copy, U: From<T>, const N: usize, const M: usize>(lanes: [T; N], high: bool) -> [U; M] {
+  let start = if high { M } else { 0 };
+  array::from_fn(|i| U::from(lanes[start + i]))
+}
+
+/// Widens the low or high half of the lanes of both operands and multiplies them, which cannot overflow.
+pub(crate) fn extmul<T, U, const N: usize, const M: usize>(a: [T; N], b: [T; N], high: bool) -> [U; M]
+where
+  T: Copy,
+  U: Copy + From<T> + Mul<Output = U>,
+{
+  zip(extend(a, high), extend(b, high), |a: U, b: U| a * b)
+}
+
+/// Adds adjacent pairs of lanes into lanes of twice the width, which cannot overflow.
+pub(crate) fn extadd_pairwise<T, U, const N: usize, const M: usize>(lanes: [T; N]) -> [U; M]
+where
+  T: Copy,
+  U: From<T> + Add<Output = U>,
+{
+  array::from_fn(|i| U::from(lanes[2 * i]) + U::from(lanes[2 * i + 1]))
+}
+
+macro_rules! narrow {
+  ($name:ident, $from:ty; $n:literal, $to:ty; $m:literal) => {
+    /// Narrows the lanes of `a` followed by those of `b`, saturating values that do not fit.
+    pub(crate) fn $name(a: [$from; $n], b: [$from; $n]) -> [$to; $m] {
+      array::from_fn(|i| {
+        let lane = if i < $n { a[i] } else { b[i - $n] };
+        lane.clamp(<$from>::from(<$to>::MIN), <$from>::from(<$to>::MAX)) as $to
+      })
+    }
+  };
+}
+
+narrow!(i8x16_narrow_i16x8_s, i16; 8, i8; 16);
+narrow!(i8x16_narrow_i16x8_u, i16; 8, u8; 16);
+narrow!(i16x8_narrow_i32x4_s, i32; 4, i16; 8);
+narrow!(i16x8_narrow_i32x4_u, i32; 4, u16; 8);
+
+/// `i32x4.dot_i16x8_s`: multiplies the lanes and adds adjacent pairs of products.
+pub(crate) fn i32x4_dot_i16x8_s(a: [i16; 8], b: [i16; 8]) -> [i32; 4] {
+  let products: [i32; 8] = zip(a, b, |a, b| i32::from(a) * i32::from(b));
+  // only two products of -32768 * -32768 overflow the sum, and they wrap around
+  array::from_fn(|i| products[2 * i].wrapping_add(products[2 * i + 1]))
+}
+
+/// `i16x8.q15mulr_sat_s`: multiplies Q15 fixed-point numbers, rounding to nearest and saturating.
+pub(crate) fn i16x8_q15mulr_sat_s(a: [i16; 8], b: [i16; 8]) -> [i16; 8] {
+  zip(a, b, |a, b| {
+    let product = (i32::from(a) * i32::from(b) + 0x4000) >> 15;
+    product.clamp(i16::MIN.into(), i16::MAX.into()) as i16
+  })
+}
+
+/// Gathers the sign bits of the lanes into the low bits of an integer.
+pub(crate) fn bitmask<T: Copy + Default + PartialOrd, const N: usize>(lanes: [T; N]) -> i32 {
+  lanes
+    .iter()
+    .enumerate()
+    .fold(0, |mask, (i, lane)| mask | (i32::from(*lane < T::default()) << i))
+}
+
+/// `i8x16.swizzle`: selects bytes of `a` by the indices in `s`, with zero for out-of-range indices.
+pub(crate) fn swizzle(a: [u8; 16], s: [u8; 16]) -> [u8; 16] {
+  s.map(|idx| a.get(usize::from(idx)).copied().unwrap_or(0))
+}
+
+/// `i8x16.shuffle`: selects bytes of `a` followed by `b` by the validated indices in `lanes`.
+pub(crate) fn shuffle(a: [u8; 16], b: [u8; 16], lanes: &[u8; 16]) -> [u8; 16] {
+  lanes.map(|idx| {
+    let idx = usize::from(idx);
+    if idx < 16 {
+      a[idx]
+    } else {
+      b[idx - 16]
+    }
+  })
+}
+
+/// Repeats the bytes of a scalar loaded from memory over the whole vector.
+pub(crate) fn splat_bytes<const N: usize>(bytes: [u8; N]) -> V128Value {
+  V128Value::from_le_bytes(array::from_fn(|i| bytes[i % N]))
+}
+
+/// Places the bytes of a scalar loaded from memory in the low bits of an otherwise zero vector.
+pub(crate) fn zero_extend_bytes<const N: usize>(bytes: [u8; N]) -> V128Value {
+  let mut vector = [0; 16];
+  vector[..N].copy_from_slice(&bytes);
+  V128Value::from_le_bytes(vector)
+}
+
+// The relaxed instructions take an `x86` flag that selects the results of the x86 vector instructions instead of
+// the deterministic ones, which the vector instructions of other architectures already produce.
+
+/// `i8x16.relaxed_swizzle`: as `swizzle`, except that x86 (`pshufb`) only zeroes lanes whose index has its top bit
+/// set, and wraps the other indices.
+pub(crate) fn relaxed_swizzle(a: [u8; 16], s: [u8; 16], x86: bool) -> [u8; 16] {
+  if x86 {
+    s.map(|idx| if idx & 0x80 == 0 { a[usize::from(idx & 0x0F)] } else { 0 })
+  } else {
+    swizzle(a, s)
+  }
+}
+
+/// `relaxed_trunc_s`: as `trunc_sat_s`, except that x86 (`cvttps2dq`) returns `i32::MIN` for NaN and out-of-range
+/// lanes.
+pub(crate) fn relaxed_trunc_s(x: f64, x86: bool) -> i32 {
+  if x86 && !(-2_147_483_649.0 < x && x < 2_147_483_648.0) {
+    i32::MIN
+  } else {
+    x as i32
+  }
+}
+
+/// `relaxed_trunc_u`: as `trunc_sat_u`, except that x86 returns `u32::MAX` for NaN and out-of-range lanes.
+pub(crate) fn relaxed_trunc_u(x: f64, x86: bool) -> u32 {
+  if x86 && !(-1.0 < x && x < 4_294_967_296.0) {
+    u32::MAX
+  } else {
+    x as u32
+  }
+}
+
+/// `relaxed_laneselect`: as `bitselect`, except that x86 (`blendv`) selects whole lanes of `WIDTH` bytes by the
+/// top bit of the mask lane.
+pub(crate) fn relaxed_laneselect<const WIDTH: usize>(
+  a: V128Value,
+  b: V128Value,
+  mask: V128Value,
+  x86: bool,
+) -> V128Value {
+  if !x86 {
+    return V128Value::from_bits((a.to_bits() & mask.to_bits()) | (b.to_bits() & !mask.to_bits()));
+  }
+
+  let (a, b, mask) = (a.to_le_bytes(), b.to_le_bytes(), mask.to_le_bytes());
+  V128Value::from_le_bytes(array::from_fn(|i| {
+    // lanes are little-endian, so the top bit is in the last byte of the lane
+    if mask[i - i % WIDTH + WIDTH - 1] & 0x80 != 0 {
+      a[i]
+    } else {
+      b[i]
+    }
+  }))
+}
+
+/// `i16x8.relaxed_q15mulr_s`: as `q15mulr_sat_s`, except that x86 (`pmulhrsw`) wraps the only overflowing product,
+/// `-32768 * -32768`, around to `-32768`.
+pub(crate) fn i16x8_relaxed_q15mulr_s(a: [i16; 8], b: [i16; 8], x86: bool) -> [i16; 8] {
+  if x86 {
+    zip(a, b, |a, b| ((i32::from(a) * i32::from(b) + 0x4000) >> 15) as i16)
+  } else {
+    i16x8_q15mulr_sat_s(a, b)
+  }
+}
+
+/// `i16x8.relaxed_dot_i8x16_i7x16_s`: multiplies the lanes and adds adjacent pairs of products with saturation.
+///
+/// The lanes of `b` are expected to fit in 7 bits. Those that do not are signed in the deterministic result, and
+/// unsigned on x86 (`pmaddubsw`).
+pub(crate) fn i16x8_relaxed_dot_i8x16_i7x16_s(a: [i8; 16], b: [i8; 16], x86: bool) -> [i16; 8] {
+  let products: [i32; 16] = zip(a, b, |a, b| {
+    let b = if x86 { i32::from(b as u8) } else { i32::from(b) };
+    i32::from(a) * b
+  });
+  array::from_fn(|i| (products[2 * i] + products[2 * i + 1]).clamp(i16::MIN.into(), i16::MAX.into()) as i16)
+}
+
+/// `i32x4.relaxed_dot_i8x16_i7x16_add_s`: adds adjacent pairs of the 16-bit dot products to the lanes of `c`.
+pub(crate) fn i32x4_relaxed_dot_i8x16_i7x16_add_s(a: [i8; 16], b: [i8; 16], c: [i32; 4], x86: bool) -> [i32; 4] {
+  let sums: [i32; 4] = extadd_pairwise(i16x8_relaxed_dot_i8x16_i7x16_s(a, b, x86));
+  zip(sums, c, i32::wrapping_add)
+}
@@ -1,27 +1,238 @@
-use crate::module::value::Value;
+use alloc::vec::Vec;
+
+use crate::{
+  executor::Error,
+  module::value::{
+    FuncIdx,
+    Value,
+  },
+};
+
+/// Default maximum number of labels across all active frames.
+pub const DEFAULT_MAX_CONTROL_DEPTH: usize = 262_144;
+/// Default maximum number of nested function calls.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 16_384;
 
 pub struct Stack {
-  operand: OperandStack,
-  control: ControlStack,
-  call: CallStack,
+  pub(crate) operand: OperandStack,
+  pub(crate) control: ControlStack,
+  pub(crate) call: CallStack,
+}
+
+impl Stack {
+  pub fn new() -> Self {
+    Self::with_limits(DEFAULT_MAX_CONTROL_DEPTH, DEFAULT_MAX_CALL_DEPTH)
+  }
+
+  /// Creates a stack that traps with [`Error::StackOverflow`] once more than `max_control_depth` labels
+  /// or `max_call_depth` frames are active.
+  pub fn with_limits(max_control_depth: usize, max_call_depth: usize) -> Self {
+    Self {
+      operand: OperandStack::new(),
+      control: ControlStack::with_max_depth(max_control_depth),
+      call: CallStack::with_max_depth(max_call_depth),
+    }
+  }
+
+  /// Drops every value, label and frame, e.g. after a trap left the stack in an intermediate state.
+  pub(crate) fn clear(&mut self) {
+    self.operand.stack.clear();
+    self.control.labels.clear();
+    self.call.frames.clear();
+  }
+}
+
+impl Default for Stack {
+  fn default() -> Self {
+    Self::new()
+  }
 }
 
 pub struct OperandStack {
-  len: usize,
   stack: Vec<Value>,
 }
 
 impl OperandStack {
   pub fn new() -> Self {
-    Self {
-      len: 0,
-      stack: vec![]
+    Self { stack: vec![] }
+  }
+
+  pub(crate) fn len(&self) -> usize {
+    self.stack.len()
+  }
+
+  pub(crate) fn push(&mut self, val: Value) {
+    self.stack.push(val);
+  }
+
+  pub(crate) fn pop(&mut self) -> Result<Value, Error> {
+    self.stack.pop().ok_or(Error::StackUnderflow)
+  }
+
+  /// Pops a value and converts it into the expected operand type.
+  pub(crate) fn pop_as<T: TryFrom<Value>>(&mut self) -> Result<T, Error> {
+    T::try_from(self.pop()?).map_err(|_| Error::TypeMismatch)
+  }
+
+  pub(crate) fn peek(&self) -> Result<Value, Error> {
+    self.stack.last().copied().ok_or(Error::StackUnderflow)
+  }
+
+  /// Pops `n` values, preserving their order on the stack.
+  pub(crate) fn pop_n(&mut self, n: usize) -> Result<Vec<Value>, Error> {
+    if n > self.stack.len() {
+      return Err(Error::StackUnderflow);
+    }
+
+    Ok(self.stack.split_off(self.stack.len() - n))
+  }
+
+  /// Keeps the top `arity` values and discards everything between them and `height`.
+  pub(crate) fn unwind(&mut self, height: usize, arity: usize) -> Result<(), Error> {
+    let len = self.stack.len();
+    if height + arity > len {
+      return Err(Error::StackUnderflow);
     }
+
+    self.stack.drain(height..(len - arity));
+
+    Ok(())
   }
 }
 
+impl Default for OperandStack {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Label {
+  /// Number of values the label carries on a branch.
+  pub(crate) arity: usize,
+  /// Height of the operand stack when the label was entered.
+  pub(crate) height: usize,
+  /// Instruction position execution continues at after a branch.
+  pub(crate) continuation: usize,
+}
+
 pub struct ControlStack {
-  
+  labels: Vec<Label>,
+  max_depth: usize,
+}
+
+impl ControlStack {
+  pub fn new() -> Self {
+    Self::with_max_depth(DEFAULT_MAX_CONTROL_DEPTH)
+  }
+
+  pub fn with_max_depth(max_depth: usize) -> Self {
+    Self {
+      labels: vec![],
+      max_depth,
+    }
+  }
+
+  pub(crate) fn len(&self) -> usize {
+    self.labels.len()
+  }
+
+  pub(crate) fn push(&mut self, label: Label) -> Result<(), Error> {
+    if self.labels.len() >= self.max_depth {
+      return Err(Error::StackOverflow);
+    }
+
+    self.labels.push(label);
+
+    Ok(())
+  }
+
+  pub(crate) fn pop(&mut self) -> Option<Label> {
+    self.labels.pop()
+  }
+
+  /// Returns the label at a relative depth, where 0 is the innermost label.
+  pub(crate) fn get(&self, depth: usize) -> Option<Label> {
+    self.labels.iter().rev().nth(depth).copied()
+  }
+
+  pub(crate) fn truncate(&mut self, len: usize) {
+    self.labels.truncate(len);
+  }
+}
+
+impl Default for ControlStack {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[derive(Debug)]
+pub(crate) struct Frame {
+  /// Index of the executing function in the function index space.
+  pub(crate) func_idx: FuncIdx,
+  pub(crate) locals: Vec<Value>,
+  /// Instruction position in the caller to resume at once the function returns.
+  pub(crate) return_pc: usize,
+  /// Number of result values of the function.
+  pub(crate) arity: usize,
+  /// Height of the operand stack after the arguments were popped.
+  pub(crate) height: usize,
+  /// Height of the control stack when the function was entered.
+  pub(crate) label_height: usize,
+}
+
+pub struct CallStack {
+  frames: Vec<Frame>,
+  max_depth: usize,
+}
+
+impl CallStack {
+  pub fn new() -> Self {
+    Self::with_max_depth(DEFAULT_MAX_CALL_DEPTH)
+  }
+
+  pub fn with_max_depth(max_depth: usize) -> Self {
+    Self {
+      frames: vec![],
+      max_depth,
+    }
+  }
+
+  pub(crate) fn len(&self) -> usize {
+    self.frames.len()
+  }
+
+  pub(crate) fn push(&mut self, frame: Frame) -> Result<(), Error> {
+    if self.frames.len() >= self.max_depth {
+      return Err(Error::StackOverflow);
+    }
+
+    self.frames.push(frame);
+
+    Ok(())
+  }
+
+  pub(crate) fn pop(&mut self) -> Option<Frame> {
+    self.frames.pop()
+  }
+
+  pub(crate) fn current(&self) -> Option<&Frame> {
+    self.frames.last()
+  }
+
+  pub(crate) fn current_mut(&mut self) -> Option<&mut Frame> {
+    self.frames.last_mut()
+  }
+
+  /// Iterates over the frames from the outermost call.
+  pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = &Frame> {
+    self.frames.iter()
+  }
 }
 
-pub struct CallStack {}
+impl Default for CallStack {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,109 @@
+use alloc::{
+  string::String,
+  vec::Vec,
+};
+use core::fmt;
+
+use crate::{
+  executor::Error,
+  module::value::FuncIdx,
+};
+
+/// Function that was executing when a trap occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameInfo {
+  func_idx: FuncIdx,
+  func_name: Option<String>,
+  code_offset: Option<usize>,
+}
+
+impl FrameInfo {
+  pub(crate) const fn new(func_idx: FuncIdx, func_name: Option<String>, code_offset: Option<usize>) -> Self {
+    Self {
+      func_idx,
+      func_name,
+      code_offset,
+    }
+  }
+
+  /// Returns the index of the function in the function index space.
+  pub const fn func_idx(&self) -> FuncIdx {
+    self.func_idx
+  }
+
+  /// Returns the name of the function from the name section, if the module has one.
+  pub fn func_name(&self) -> Option<&str> {
+    self.func_name.as_deref()
+  }
+
+  /// Returns the offset in the module binary of the instruction that was executing,
+  /// or `None` for host functions.
+  pub const fn code_offset(&self) -> Option<usize> {
+    self.code_offset
+  }
+}
+
+impl fmt::Display for FrameInfo {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "function {}", self.func_idx)?;
+    if let Some(name) = &self.func_name {
+      write!(f, " (${name})")?;
+    }
+    if let Some(offset) = self.code_offset {
+      write!(f, " at 0x{offset:07X}")?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Abrupt termination of execution, with the wasm call stack at the point where it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trap {
+  error: Error,
+  backtrace: Vec<FrameInfo>,
+}
+
+impl Trap {
+  pub(crate) const fn new(error: Error, backtrace: Vec<FrameInfo>) -> Self {
+    Self { error, backtrace }
+  }
+
+  /// Returns the reason of the trap.
+  pub const fn error(&self) -> &Error {
+    &self.error
+  }
+
+  /// Consumes the trap and returns its reason.
+  pub fn into_error(self) -> Error {
+    self.error
+  }
+
+  /// Returns the frames of the call stack, starting with the innermost function.
+  ///
+  /// The backtrace is empty for traps that occurred outside of any function, such as
+  /// while initializing segments.
+  pub fn backtrace(&self) -> &[FrameInfo] {
+    &self.backtrace
+  }
+}
+
+impl From<Error> for Trap {
+  fn from(error: Error) -> Self {
+    Self::new(error, vec![])
+  }
+}
+
+impl fmt::Display for Trap {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.error)?;
+    if !self.backtrace.is_empty() {
+      write!(f, "\nwasm backtrace:")?;
+      for (depth, frame) in self.backtrace.iter().enumerate() {
+        write!(f, "\n  {depth}: {frame}")?;
+      }
+    }
+
+    Ok(())
+  }
+}
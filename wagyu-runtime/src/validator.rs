@@ -0,0 +1,1504 @@
+use alloc::{
+  collections::BTreeSet,
+  string::String,
+  vec::Vec,
+};
+use core::fmt;
+
+use crate::{
+  instr::Instr,
+  module::{
+    expr::ConstExpr,
+    function::Function,
+    import::ImportKind,
+    types::{
+      MemoryType,
+      Type,
+    },
+    value::{
+      BlockType,
+      DataMode,
+      ElemMode,
+      ExportDesc,
+      FuncIdx,
+      GlobalMut,
+      HeapType,
+      IndexType,
+      Limit,
+      RefType,
+      ValType,
+    },
+    Module,
+  },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+  TypeMismatch,
+  UnknownType,
+  UnknownFunction,
+  UnknownTable,
+  UnknownMemory,
+  UnknownGlobal,
+  UnknownLocal,
+  UnknownLabel,
+  UnknownData,
+  UnknownElem,
+  UndeclaredFunctionReference,
+  ConstantExpressionRequired,
+  ImmutableGlobal,
+  InvalidAlignment,
+  OffsetOutOfRange,
+  InvalidLaneIndex,
+  InvalidLimit,
+  InvalidStartFunction,
+  DuplicateExport,
+  DataCountRequired,
+  DataCountMismatch,
+  UnsupportedInstruction,
+}
+
+pub struct Error {
+  pub message: String,
+  pub kind: ErrorKind,
+  /// Index of the offending function in the function index space, if the error lies within a function body.
+  pub func_idx: Option<u32>,
+  /// Offset of the offending instruction in the module binary, if the error lies within a function body.
+  pub offset: Option<usize>,
+}
+
+impl Error {
+  fn module(kind: ErrorKind, message: String) -> Self {
+    Self {
+      message,
+      kind,
+      func_idx: None,
+      offset: None,
+    }
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let kind = match self.kind {
+      ErrorKind::TypeMismatch => "type mismatch",
+      ErrorKind::UnknownType => "unknown type",
+      ErrorKind::UnknownFunction => "unknown function",
+      ErrorKind::UnknownTable => "unknown table",
+      ErrorKind::UnknownMemory => "unknown memory",
+      ErrorKind::UnknownGlobal => "unknown global",
+      ErrorKind::UnknownLocal => "unknown local",
+      ErrorKind::UnknownLabel => "unknown label",
+      ErrorKind::UnknownData => "unknown data segment",
+      ErrorKind::UnknownElem => "unknown elem segment",
+      ErrorKind::UndeclaredFunctionReference => "undeclared function reference",
+      ErrorKind::ConstantExpressionRequired => "constant expression required",
+      ErrorKind::ImmutableGlobal => "global is immutable",
+      ErrorKind::InvalidAlignment => "alignment must not be larger than natural",
+      ErrorKind::OffsetOutOfRange => "offset out of range",
+      ErrorKind::InvalidLaneIndex => "invalid lane index",
+      ErrorKind::InvalidLimit => "invalid limit",
+      ErrorKind::InvalidStartFunction => "invalid start function",
+      ErrorKind::DuplicateExport => "duplicate export name",
+      ErrorKind::DataCountRequired => "data count section required",
+      ErrorKind::DataCountMismatch => "data count and data section have inconsistent lengths",
+      ErrorKind::UnsupportedInstruction => "unsupported instruction",
+    };
+
+    write!(f, "Validation error: {kind}: {}", self.message)?;
+    if let Some(func_idx) = self.func_idx {
+      write!(f, " in function {func_idx}")?;
+    }
+    if let Some(offset) = self.offset {
+      write!(f, " at 0x{offset:07X}")?;
+    }
+
+    Ok(())
+  }
+}
+
+impl fmt::Debug for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(self, f)
+  }
+}
+
+/// Index spaces of a module, which include its imports.
+struct Context<'a> {
+  types: &'a [Type],
+  funcs: Vec<&'a Type>,
+  tables: Vec<RefType>,
+  /// Address types of the memories.
+  memories: Vec<IndexType>,
+  globals: Vec<(ValType, GlobalMut)>,
+  /// Number of imported globals, the only ones constant expressions may read.
+  n_imported_globals: usize,
+  elems: Vec<RefType>,
+  data_count: Option<u32>,
+  /// Functions referenced outside of function bodies, which `ref.func` may refer to.
+  refs: BTreeSet<FuncIdx>,
+}
+
+/// Checks that a module is valid, as defined by the validation rules of the specification.
+///
+/// # Errors
+///
+/// Returns the first rule the module violates. Errors within a function body name the function and the offset
+/// of the offending instruction.
+pub fn validate(module: &Module) -> Result<(), Error> {
+  let type_of = |type_idx: u32| {
+    module
+      .types
+      .get(type_idx as usize)
+      .ok_or_else(|| Error::module(ErrorKind::UnknownType, format!("type {type_idx} is not defined")))
+  };
+
+  let mut funcs = vec![];
+  let mut tables = vec![];
+  let mut memories = vec![];
+  let mut globals = vec![];
+  for import in &module.imports {
+    match &import.kind {
+      ImportKind::TypeIdx(type_idx) => funcs.push(type_of(*type_idx)?),
+      ImportKind::TableType(reftype, Limit(min, max)) => {
+        check_limit(u64::from(*min), max.map(u64::from), u32::MAX.into())?;
+        tables.push(*reftype);
+      }
+      ImportKind::MemType(memory_type) => {
+        check_memory_type(memory_type)?;
+        memories.push(memory_type.idx_type);
+      }
+      ImportKind::GlobalType(valtype, mutable) => globals.push((*valtype, *mutable)),
+    }
+  }
+
+  for function in &module.functions {
+    funcs.push(type_of(function.signature_idx)?);
+  }
+  for table in &module.tables {
+    check_limit(table.min.into(), table.max.map(u64::from), u32::MAX.into())?;
+  }
+  for memory in &module.memories {
+    check_memory_type(&memory.ty)?;
+  }
+  tables.extend(module.tables.iter().map(|table| table.reftype));
+  memories.extend(module.memories.iter().map(|memory| memory.ty.idx_type));
+  let n_imported_globals = globals.len();
+  globals.extend(module.globals.iter().map(|global| (global.valtype, global.mutable)));
+
+  let refs = module
+    .globals
+    .iter()
+    .filter_map(|global| global.init.as_ref())
+    .chain(module.elems.iter().flat_map(|elem| &elem.init))
+    .flat_map(|expr| &expr.instrs)
+    .filter_map(|instr| match instr {
+      Instr::RefFunc(func_idx) => Some(*func_idx),
+      _ => None,
+    })
+    .chain(
+      module
+        .exports
+        .iter()
+        .filter(|export| matches!(export.desc, ExportDesc::Func))
+        .map(|export| export.idx),
+    )
+    .collect();
+
+  let ctx = Context {
+    types: &module.types,
+    funcs,
+    tables,
+    memories,
+    globals,
+    n_imported_globals,
+    elems: module.elems.iter().map(|elem| elem.reftype).collect(),
+    data_count: module.data_count,
+    refs,
+  };
+
+  let mut export_names = BTreeSet::new();
+  for export in &module.exports {
+    if !export_names.insert(export.name.as_str()) {
+      return Err(Error::module(
+        ErrorKind::DuplicateExport,
+        format!("\"{}\" is exported more than once", export.name),
+      ));
+    }
+
+    let (kind, len) = match export.desc {
+      ExportDesc::Func => (ErrorKind::UnknownFunction, ctx.funcs.len()),
+      ExportDesc::Table => (ErrorKind::UnknownTable, ctx.tables.len()),
+      ExportDesc::Mem => (ErrorKind::UnknownMemory, ctx.memories.len()),
+      ExportDesc::Global => (ErrorKind::UnknownGlobal, ctx.globals.len()),
+    };
+    if export.idx as usize >= len {
+      return Err(Error::module(
+        kind,
+        format!("export \"{}\" refers to index {}", export.name, export.idx),
+      ));
+    }
+  }
+
+  if let Some(func_idx) = module.start_func {
+    let ty = ctx.funcs.get(func_idx as usize).ok_or_else(|| {
+      Error::module(
+        ErrorKind::UnknownFunction,
+        format!("start function {func_idx} is not defined"),
+      )
+    })?;
+    if !ty.params.is_empty() || !ty.results.is_empty() {
+      return Err(Error::module(
+        ErrorKind::InvalidStartFunction,
+        format!("start function {func_idx} must not take parameters or return results"),
+      ));
+    }
+  }
+
+  for global in &module.globals {
+    if let Some(init) = &global.init {
+      check_const_expr(&ctx, init, global.valtype)?;
+    }
+  }
+
+  for elem in &module.elems {
+    for expr in &elem.init {
+      check_const_expr(&ctx, expr, ValType::from(elem.reftype))?;
+    }
+
+    if let ElemMode::Active(table_idx, offset) = &elem.mode {
+      check_const_expr(&ctx, offset, ValType::I32)?;
+      let reftype = ctx.tables.get(*table_idx as usize).ok_or_else(|| {
+        Error::module(
+          ErrorKind::UnknownTable,
+          format!("element segment refers to table {table_idx}"),
+        )
+      })?;
+      if *reftype != elem.reftype {
+        return Err(Error::module(
+          ErrorKind::TypeMismatch,
+          format!(
+            "element segment of {} cannot initialize a table of {}",
+            ValType::from(elem.reftype),
+            ValType::from(*reftype)
+          ),
+        ));
+      }
+    }
+  }
+
+  if let Some(data_count) = module.data_count {
+    if data_count as usize != module.data.len() {
+      return Err(Error::module(
+        ErrorKind::DataCountMismatch,
+        format!("{data_count} declared, {} defined", module.data.len()),
+      ));
+    }
+  }
+  for data in &module.data {
+    if let DataMode::Active(mem_idx, offset) = &data.mode {
+      let Some(idx_type) = ctx.memories.get(*mem_idx as usize) else {
+        return Err(Error::module(
+          ErrorKind::UnknownMemory,
+          format!("data segment refers to memory {mem_idx}"),
+        ));
+      };
+      check_const_expr(&ctx, offset, idx_type.valtype())?;
+    }
+  }
+
+  let n_imported = module.n_imported_funcs();
+  for (func_pos, function) in module.functions.iter().enumerate() {
+    FuncValidator::new(&ctx, (n_imported + func_pos) as FuncIdx, function).validate(function)?;
+  }
+
+  Ok(())
+}
+
+/// Checks that a constant expression only consists of constant instructions and produces a single value of `expected`.
+fn check_const_expr(ctx: &Context, expr: &ConstExpr, expected: ValType) -> Result<(), Error> {
+  let error = |kind, message| Error {
+    offset: Some(expr.offset),
+    ..Error::module(kind, message)
+  };
+
+  let mut vals = vec![];
+  for instr in &expr.instrs {
+    let valtype = match instr {
+      Instr::I32Const(_) => ValType::I32,
+      Instr::I64Const(_) => ValType::I64,
+      Instr::F32Const(_) => ValType::F32,
+      Instr::F64Const(_) => ValType::F64,
+      Instr::V128Const(_) => ValType::V128,
+      Instr::GlobalGet(global_idx) => {
+        let (valtype, mutable) = ctx
+          .globals
+          .get(*global_idx as usize)
+          .filter(|_| (*global_idx as usize) < ctx.n_imported_globals)
+          .copied()
+          .ok_or_else(|| {
+            error(
+              ErrorKind::UnknownGlobal,
+              format!("global {global_idx} is not an imported global"),
+            )
+          })?;
+        if mutable == GlobalMut::Var {
+          return Err(error(
+            ErrorKind::ConstantExpressionRequired,
+            format!("global {global_idx} is mutable"),
+          ));
+        }
+
+        valtype
+      }
+      Instr::RefNull(HeapType::Func) => ValType::FuncRef,
+      Instr::RefNull(HeapType::Extern) => ValType::ExternRef,
+      Instr::RefFunc(func_idx) => {
+        if *func_idx as usize >= ctx.funcs.len() {
+          return Err(error(
+            ErrorKind::UnknownFunction,
+            format!("function {func_idx} is not defined"),
+          ));
+        }
+
+        ValType::FuncRef
+      }
+      Instr::I32Add | Instr::I32Sub | Instr::I32Mul | Instr::I64Add | Instr::I64Sub | Instr::I64Mul => {
+        let valtype = match instr {
+          Instr::I32Add | Instr::I32Sub | Instr::I32Mul => ValType::I32,
+          _ => ValType::I64,
+        };
+        for _ in 0..2 {
+          match vals.pop() {
+            Some(actual) if actual == valtype => {}
+            Some(actual) => {
+              return Err(error(
+                ErrorKind::TypeMismatch,
+                format!("expected {valtype}, found {actual}"),
+              ))
+            }
+            None => {
+              return Err(error(
+                ErrorKind::TypeMismatch,
+                String::from("expected a value, found an empty stack"),
+              ))
+            }
+          }
+        }
+
+        valtype
+      }
+      _ => {
+        return Err(error(
+          ErrorKind::ConstantExpressionRequired,
+          format!("{instr:?} is not a constant instruction"),
+        ))
+      }
+    };
+
+    vals.push(valtype);
+  }
+
+  if vals[..] != [expected] {
+    return Err(error(
+      ErrorKind::TypeMismatch,
+      format!("expected [{expected}], found {vals:?}"),
+    ));
+  }
+
+  Ok(())
+}
+
+/// Checks that the limits of a memory are within the pages its address type can reach.
+fn check_memory_type(memory_type: &MemoryType) -> Result<(), Error> {
+  check_limit(memory_type.min, memory_type.max, memory_type.idx_type.max_pages())
+}
+
+/// Checks that the minimum of a limit does not exceed its maximum, and both are within `range`.
+fn check_limit(min: u64, max: Option<u64>, range: u64) -> Result<(), Error> {
+  if min > range || max.is_some_and(|max| max > range) {
+    return Err(Error::module(
+      ErrorKind::InvalidLimit,
+      format!("size must be at most {range}"),
+    ));
+  }
+  if max.is_some_and(|max| min > max) {
+    return Err(Error::module(
+      ErrorKind::InvalidLimit,
+      String::from("size minimum must not be greater than maximum"),
+    ));
+  }
+
+  Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameKind {
+  Block,
+  Loop,
+  If,
+  Else,
+  Func,
+}
+
+struct CtrlFrame {
+  kind: FrameKind,
+  params: Vec<ValType>,
+  results: Vec<ValType>,
+  /// Height of the operand stack when the frame was entered.
+  height: usize,
+  /// Whether the rest of the frame is unreachable, which makes the operand stack polymorphic.
+  unreachable: bool,
+}
+
+impl CtrlFrame {
+  /// Returns the types a branch to the frame's label carries.
+  fn label_types(&self) -> &[ValType] {
+    match self.kind {
+      FrameKind::Loop => &self.params,
+      _ => &self.results,
+    }
+  }
+}
+
+/// Type-checks a function body with the algorithm from the appendix of the specification.
+struct FuncValidator<'a> {
+  ctx: &'a Context<'a>,
+  func_idx: FuncIdx,
+  locals: Vec<ValType>,
+  /// Operand types, where `None` stands for an unknown type in unreachable code.
+  vals: Vec<Option<ValType>>,
+  ctrls: Vec<CtrlFrame>,
+  /// Offset of the instruction being validated.
+  offset: usize,
+}
+
+impl<'a> FuncValidator<'a> {
+  fn new(ctx: &'a Context<'a>, func_idx: FuncIdx, function: &Function) -> Self {
+    let ty = ctx.funcs[func_idx as usize];
+
+    Self {
+      ctx,
+      func_idx,
+      locals: ty.params.iter().chain(&function.locals).copied().collect(),
+      vals: vec![],
+      ctrls: vec![CtrlFrame {
+        kind: FrameKind::Func,
+        params: vec![],
+        results: ty.results.clone(),
+        height: 0,
+        unreachable: false,
+      }],
+      offset: 0,
+    }
+  }
+
+  fn error(&self, kind: ErrorKind, message: String) -> Error {
+    Error {
+      message,
+      kind,
+      func_idx: Some(self.func_idx),
+      offset: Some(self.offset),
+    }
+  }
+
+  fn validate(mut self, function: &Function) -> Result<(), Error> {
+    let body = &function.parsed_body;
+    for (instr, &offset) in body.instrs.iter().zip(&body.offsets) {
+      self.offset = offset;
+      self.validate_instr(instr)?;
+    }
+
+    // the final `end` closes the function body
+    self.offset = body.offsets.last().copied().unwrap_or_default();
+    let frame = self.pop_ctrl()?;
+    if frame.kind != FrameKind::Func {
+      return Err(self.error(ErrorKind::TypeMismatch, String::from("unclosed block")));
+    }
+
+    Ok(())
+  }
+
+  fn push_val(&mut self, valtype: ValType) {
+    self.vals.push(Some(valtype));
+  }
+
+  fn push_vals(&mut self, valtypes: &[ValType]) {
+    self.vals.extend(valtypes.iter().copied().map(Some));
+  }
+
+  fn pop_val(&mut self) -> Result<Option<ValType>, Error> {
+    let frame = self
+      .ctrls
+      .last()
+      .expect("the function frame stays until the final `end`");
+    if self.vals.len() == frame.height {
+      if frame.unreachable {
+        return Ok(None);
+      }
+
+      return Err(self.error(
+        ErrorKind::TypeMismatch,
+        String::from("expected a value, found an empty stack"),
+      ));
+    }
+
+    Ok(self.vals.pop().flatten())
+  }
+
+  fn pop_expect(&mut self, expected: ValType) -> Result<Option<ValType>, Error> {
+    match self.pop_val()? {
+      Some(actual) if actual != expected => {
+        Err(self.error(ErrorKind::TypeMismatch, format!("expected {expected}, found {actual}")))
+      }
+      actual => Ok(actual),
+    }
+  }
+
+  /// Pops values of the `expected` types and returns the actual types in stack order.
+  fn pop_vals(&mut self, expected: &[ValType]) -> Result<Vec<Option<ValType>>, Error> {
+    let mut popped = expected
+      .iter()
+      .rev()
+      .map(|valtype| self.pop_expect(*valtype))
+      .collect::<Result<Vec<_>, _>>()?;
+    popped.reverse();
+
+    Ok(popped)
+  }
+
+  fn pop_ref(&mut self) -> Result<(), Error> {
+    match self.pop_val()? {
+      Some(actual @ (ValType::I32 | ValType::I64 | ValType::F32 | ValType::F64 | ValType::V128)) => {
+        Err(self.error(ErrorKind::TypeMismatch, format!("expected a reference, found {actual}")))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  fn push_ctrl(&mut self, kind: FrameKind, params: Vec<ValType>, results: Vec<ValType>) {
+    let height = self.vals.len();
+    self.push_vals(&params);
+    self.ctrls.push(CtrlFrame {
+      kind,
+      params,
+      results,
+      height,
+      unreachable: false,
+    });
+  }
+
+  fn pop_ctrl(&mut self) -> Result<CtrlFrame, Error> {
+    let frame = self
+      .ctrls
+      .last()
+      .expect("the function frame stays until the final `end`");
+    let (results, height) = (frame.results.clone(), frame.height);
+
+    self.pop_vals(&results)?;
+    if self.vals.len() != height {
+      return Err(self.error(
+        ErrorKind::TypeMismatch,
+        format!(
+          "{} values remain on the stack at the end of a block",
+          self.vals.len() - height
+        ),
+      ));
+    }
+
+    Ok(self.ctrls.pop().expect("the frame was just inspected"))
+  }
+
+  fn set_unreachable(&mut self) {
+    let frame = self
+      .ctrls
+      .last_mut()
+      .expect("the function frame stays until the final `end`");
+    self.vals.truncate(frame.height);
+    frame.unreachable = true;
+  }
+
+  fn label_types(&self, depth: u32) -> Result<Vec<ValType>, Error> {
+    self
+      .ctrls
+      .iter()
+      .rev()
+      .nth(depth as usize)
+      .map(|frame| frame.label_types().to_vec())
+      .ok_or_else(|| self.error(ErrorKind::UnknownLabel, format!("label {depth} is not defined")))
+  }
+
+  fn block_type(&self, block_type: &BlockType) -> Result<(Vec<ValType>, Vec<ValType>), Error> {
+    match block_type {
+      BlockType::Empty => Ok((vec![], vec![])),
+      BlockType::Value(valtype) => Ok((vec![], vec![*valtype])),
+      BlockType::TypeIdx(type_idx) => self
+        .ctx
+        .types
+        .get(*type_idx as usize)
+        .map(|ty| (ty.params.clone(), ty.results.clone()))
+        .ok_or_else(|| self.error(ErrorKind::UnknownType, format!("type {type_idx} is not defined"))),
+    }
+  }
+
+  fn func_type(&self, func_idx: FuncIdx) -> Result<&'a Type, Error> {
+    self.ctx.funcs.get(func_idx as usize).copied().ok_or_else(|| {
+      self.error(
+        ErrorKind::UnknownFunction,
+        format!("function {func_idx} is not defined"),
+      )
+    })
+  }
+
+  fn local(&self, local_idx: u32) -> Result<ValType, Error> {
+    self
+      .locals
+      .get(local_idx as usize)
+      .copied()
+      .ok_or_else(|| self.error(ErrorKind::UnknownLocal, format!("local {local_idx} is not defined")))
+  }
+
+  fn global(&self, global_idx: u32) -> Result<(ValType, GlobalMut), Error> {
+    self
+      .ctx
+      .globals
+      .get(global_idx as usize)
+      .copied()
+      .ok_or_else(|| self.error(ErrorKind::UnknownGlobal, format!("global {global_idx} is not defined")))
+  }
+
+  fn table(&self, table_idx: u32) -> Result<ValType, Error> {
+    self
+      .ctx
+      .tables
+      .get(table_idx as usize)
+      .map(|reftype| ValType::from(*reftype))
+      .ok_or_else(|| self.error(ErrorKind::UnknownTable, format!("table {table_idx} is not defined")))
+  }
+
+  fn elem(&self, elem_idx: u32) -> Result<ValType, Error> {
+    self
+      .ctx
+      .elems
+      .get(elem_idx as usize)
+      .map(|reftype| ValType::from(*reftype))
+      .ok_or_else(|| {
+        self.error(
+          ErrorKind::UnknownElem,
+          format!("element segment {elem_idx} is not defined"),
+        )
+      })
+  }
+
+  /// Checks that a memory is defined and returns the type of its addresses.
+  fn check_memory(&self, mem_idx: u32) -> Result<ValType, Error> {
+    self
+      .ctx
+      .memories
+      .get(mem_idx as usize)
+      .map(IndexType::valtype)
+      .ok_or_else(|| self.error(ErrorKind::UnknownMemory, format!("memory {mem_idx} is not defined")))
+  }
+
+  fn check_data(&self, data_idx: u32) -> Result<(), Error> {
+    match self.ctx.data_count {
+      None => Err(self.error(
+        ErrorKind::DataCountRequired,
+        format!("data segment {data_idx} is referenced"),
+      )),
+      Some(data_count) if data_idx >= data_count => Err(self.error(
+        ErrorKind::UnknownData,
+        format!("data segment {data_idx} is not defined"),
+      )),
+      Some(_) => Ok(()),
+    }
+  }
+
+  /// Checks a memory argument, whose alignment exponent must not exceed the natural alignment of `n_bytes` and whose
+  /// offset must be addressable by the memory, and returns the type of the addresses of the memory.
+  fn check_memarg(&self, mem_idx: u32, offset: u64, align: u32, n_bytes: u32) -> Result<ValType, Error> {
+    let addr_type = self.check_memory(mem_idx)?;
+    if align >= u32::BITS || 1 << align > n_bytes {
+      return Err(self.error(
+        ErrorKind::InvalidAlignment,
+        format!("alignment 2^{align} exceeds {n_bytes} bytes"),
+      ));
+    }
+    if addr_type == ValType::I32 && u32::try_from(offset).is_err() {
+      return Err(self.error(
+        ErrorKind::OffsetOutOfRange,
+        format!("offset {offset} exceeds a 32-bit memory"),
+      ));
+    }
+
+    Ok(addr_type)
+  }
+
+  fn load(&mut self, mem_idx: u32, offset: u64, align: u32, n_bytes: u32, valtype: ValType) -> Result<(), Error> {
+    let addr_type = self.check_memarg(mem_idx, offset, align, n_bytes)?;
+    self.pop_expect(addr_type)?;
+    self.push_val(valtype);
+
+    Ok(())
+  }
+
+  fn store(&mut self, mem_idx: u32, offset: u64, align: u32, n_bytes: u32, valtype: ValType) -> Result<(), Error> {
+    let addr_type = self.check_memarg(mem_idx, offset, align, n_bytes)?;
+    self.pop_expect(valtype)?;
+    self.pop_expect(addr_type)?;
+
+    Ok(())
+  }
+
+  /// Checks that a lane index selects one of `n_lanes` lanes.
+  fn check_lane(&self, lane: u8, n_lanes: u8) -> Result<(), Error> {
+    if lane >= n_lanes {
+      return Err(self.error(
+        ErrorKind::InvalidLaneIndex,
+        format!("lane {lane} exceeds {n_lanes} lanes"),
+      ));
+    }
+
+    Ok(())
+  }
+
+  /// Pops `params` and pushes `results`, the signature of every plain numeric instruction.
+  fn op(&mut self, params: &[ValType], results: &[ValType]) -> Result<(), Error> {
+    self.pop_vals(params)?;
+    self.push_vals(results);
+
+    Ok(())
+  }
+
+  fn validate_instr(&mut self, instr: &Instr) -> Result<(), Error> {
+    use ValType::{
+      F32,
+      F64,
+      I32,
+      I64,
+      V128,
+    };
+
+    match instr {
+      Instr::Unreachable => self.set_unreachable(),
+      Instr::Nop => {}
+      Instr::Block(block_type, _) | Instr::Loop(block_type) => {
+        let (params, results) = self.block_type(block_type)?;
+        self.pop_vals(&params)?;
+
+        let kind = match instr {
+          Instr::Loop(_) => FrameKind::Loop,
+          _ => FrameKind::Block,
+        };
+        self.push_ctrl(kind, params, results);
+      }
+      Instr::If(block_type, ..) => {
+        let (params, results) = self.block_type(block_type)?;
+        self.pop_expect(I32)?;
+        self.pop_vals(&params)?;
+        self.push_ctrl(FrameKind::If, params, results);
+      }
+      Instr::Else(_) => {
+        let frame = self.pop_ctrl()?;
+        if frame.kind != FrameKind::If {
+          return Err(self.error(ErrorKind::TypeMismatch, String::from("else without matching if")));
+        }
+
+        self.push_ctrl(FrameKind::Else, frame.params, frame.results);
+      }
+      Instr::End => {
+        let frame = self.pop_ctrl()?;
+        if frame.kind == FrameKind::If && frame.params != frame.results {
+          return Err(self.error(
+            ErrorKind::TypeMismatch,
+            String::from("if without else must leave its parameters unchanged"),
+          ));
+        }
+
+        self.push_vals(&frame.results);
+      }
+      Instr::Br(depth) => {
+        let label_types = self.label_types(*depth)?;
+        self.pop_vals(&label_types)?;
+        self.set_unreachable();
+      }
+      Instr::BrIf(depth) => {
+        let label_types = self.label_types(*depth)?;
+        self.pop_expect(I32)?;
+        self.pop_vals(&label_types)?;
+        self.push_vals(&label_types);
+      }
+      Instr::BrTable(depths, default_depth) => {
+        self.pop_expect(I32)?;
+
+        let default_types = self.label_types(*default_depth)?;
+        for depth in depths {
+          let label_types = self.label_types(*depth)?;
+          if label_types.len() != default_types.len() {
+            return Err(self.error(
+              ErrorKind::TypeMismatch,
+              String::from("br_table targets labels of different arities"),
+            ));
+          }
+
+          // each target is checked against the values without consuming them
+          let popped = self.pop_vals(&label_types)?;
+          self.vals.extend(popped);
+        }
+
+        self.pop_vals(&default_types)?;
+        self.set_unreachable();
+      }
+      Instr::Return => {
+        let results = self.ctrls[0].results.clone();
+        self.pop_vals(&results)?;
+        self.set_unreachable();
+      }
+      Instr::Call(func_idx) => {
+        let ty = self.func_type(*func_idx)?;
+        self.op(&ty.params, &ty.results)?;
+      }
+      Instr::CallIndirect(table_idx, type_idx) => {
+        let element_type = self.table(*table_idx)?;
+        if element_type != ValType::FuncRef {
+          return Err(self.error(
+            ErrorKind::TypeMismatch,
+            format!("call_indirect requires a funcref table, found {element_type}"),
+          ));
+        }
+        let (params, results) = self.block_type(&BlockType::TypeIdx(*type_idx))?;
+        self.pop_expect(I32)?;
+        self.op(&params, &results)?;
+      }
+
+      Instr::RefNull(heap_type) => self.push_val(match heap_type {
+        HeapType::Func => ValType::FuncRef,
+        HeapType::Extern => ValType::ExternRef,
+      }),
+      Instr::RefIsNull => {
+        self.pop_ref()?;
+        self.push_val(I32);
+      }
+      Instr::RefFunc(func_idx) => {
+        self.func_type(*func_idx)?;
+        if !self.ctx.refs.contains(func_idx) {
+          return Err(self.error(
+            ErrorKind::UndeclaredFunctionReference,
+            format!("function {func_idx} must be declared by an element segment or export"),
+          ));
+        }
+        self.push_val(ValType::FuncRef);
+      }
+
+      Instr::Drop => {
+        self.pop_val()?;
+      }
+      Instr::Select(valtypes) => match valtypes[..] {
+        [] => {
+          self.pop_expect(I32)?;
+          let lhs = self.pop_val()?;
+          let rhs = self.pop_val()?;
+          let valtype = match (lhs, rhs) {
+            (Some(ValType::FuncRef | ValType::ExternRef), _) | (_, Some(ValType::FuncRef | ValType::ExternRef)) => {
+              return Err(self.error(
+                ErrorKind::TypeMismatch,
+                String::from("select without types requires numeric or vector operands"),
+              ));
+            }
+            (Some(lhs), Some(rhs)) if lhs != rhs => {
+              return Err(self.error(
+                ErrorKind::TypeMismatch,
+                format!("select operands {lhs} and {rhs} differ"),
+              ));
+            }
+            (lhs, rhs) => lhs.or(rhs),
+          };
+          self.vals.push(valtype);
+        }
+        [valtype] => {
+          self.pop_expect(I32)?;
+          self.pop_expect(valtype)?;
+          self.pop_expect(valtype)?;
+          self.push_val(valtype);
+        }
+        _ => {
+          return Err(self.error(
+            ErrorKind::TypeMismatch,
+            String::from("select must have at most one result type"),
+          ))
+        }
+      },
+
+      Instr::LocalGet(local_idx) => {
+        let valtype = self.local(*local_idx)?;
+        self.push_val(valtype);
+      }
+      Instr::LocalSet(local_idx) => {
+        let valtype = self.local(*local_idx)?;
+        self.pop_expect(valtype)?;
+      }
+      Instr::LocalTee(local_idx) => {
+        let valtype = self.local(*local_idx)?;
+        self.op(&[valtype], &[valtype])?;
+      }
+      Instr::GlobalGet(global_idx) => {
+        let (valtype, _) = self.global(*global_idx)?;
+        self.push_val(valtype);
+      }
+      Instr::GlobalSet(global_idx) => {
+        let (valtype, mutable) = self.global(*global_idx)?;
+        if mutable != GlobalMut::Var {
+          return Err(self.error(ErrorKind::ImmutableGlobal, format!("global {global_idx} cannot be set")));
+        }
+        self.pop_expect(valtype)?;
+      }
+
+      Instr::TableGet(table_idx) => {
+        let element_type = self.table(*table_idx)?;
+        self.op(&[I32], &[element_type])?;
+      }
+      Instr::TableSet(table_idx) => {
+        let element_type = self.table(*table_idx)?;
+        self.op(&[I32, element_type], &[])?;
+      }
+      Instr::TableSize(table_idx) => {
+        self.table(*table_idx)?;
+        self.push_val(I32);
+      }
+      Instr::TableGrow(table_idx) => {
+        let element_type = self.table(*table_idx)?;
+        self.op(&[element_type, I32], &[I32])?;
+      }
+      Instr::TableFill(table_idx) => {
+        let element_type = self.table(*table_idx)?;
+        self.op(&[I32, element_type, I32], &[])?;
+      }
+      Instr::TableCopy(dst_table_idx, src_table_idx) => {
+        let dst_element_type = self.table(*dst_table_idx)?;
+        let src_element_type = self.table(*src_table_idx)?;
+        if dst_element_type != src_element_type {
+          return Err(self.error(
+            ErrorKind::TypeMismatch,
+            format!("cannot copy {src_element_type} elements into a table of {dst_element_type}"),
+          ));
+        }
+        self.op(&[I32, I32, I32], &[])?;
+      }
+      Instr::TableInit(table_idx, elem_idx) => {
+        let element_type = self.table(*table_idx)?;
+        let segment_type = self.elem(*elem_idx)?;
+        if element_type != segment_type {
+          return Err(self.error(
+            ErrorKind::TypeMismatch,
+            format!("cannot initialize a table of {element_type} with {segment_type} elements"),
+          ));
+        }
+        self.op(&[I32, I32, I32], &[])?;
+      }
+      Instr::ElemDrop(elem_idx) => {
+        self.elem(*elem_idx)?;
+      }
+
+      Instr::I32Load(mem_idx, offset, align) => self.load(*mem_idx, *offset, *align, 4, I32)?,
+      Instr::I64Load(mem_idx, offset, align) => self.load(*mem_idx, *offset, *align, 8, I64)?,
+      Instr::F32Load(mem_idx, offset, align) => self.load(*mem_idx, *offset, *align, 4, F32)?,
+      Instr::F64Load(mem_idx, offset, align) => self.load(*mem_idx, *offset, *align, 8, F64)?,
+      Instr::I32Load8S(mem_idx, offset, align) | Instr::I32Load8U(mem_idx, offset, align) => {
+        self.load(*mem_idx, *offset, *align, 1, I32)?
+      }
+      Instr::I32Load16S(mem_idx, offset, align) | Instr::I32Load16U(mem_idx, offset, align) => {
+        self.load(*mem_idx, *offset, *align, 2, I32)?
+      }
+      Instr::I64Load8S(mem_idx, offset, align) | Instr::I64Load8U(mem_idx, offset, align) => {
+        self.load(*mem_idx, *offset, *align, 1, I64)?
+      }
+      Instr::I64Load16S(mem_idx, offset, align) | Instr::I64Load16U(mem_idx, offset, align) => {
+        self.load(*mem_idx, *offset, *align, 2, I64)?
+      }
+      Instr::I64Load32S(mem_idx, offset, align) | Instr::I64Load32U(mem_idx, offset, align) => {
+        self.load(*mem_idx, *offset, *align, 4, I64)?
+      }
+      Instr::I32Store(mem_idx, offset, align) => self.store(*mem_idx, *offset, *align, 4, I32)?,
+      Instr::I64Store(mem_idx, offset, align) => self.store(*mem_idx, *offset, *align, 8, I64)?,
+      Instr::F32Store(mem_idx, offset, align) => self.store(*mem_idx, *offset, *align, 4, F32)?,
+      Instr::F64Store(mem_idx, offset, align) => self.store(*mem_idx, *offset, *align, 8, F64)?,
+      Instr::I32Store8(mem_idx, offset, align) => self.store(*mem_idx, *offset, *align, 1, I32)?,
+      Instr::I32Store16(mem_idx, offset, align) => self.store(*mem_idx, *offset, *align, 2, I32)?,
+      Instr::I64Store8(mem_idx, offset, align) => self.store(*mem_idx, *offset, *align, 1, I64)?,
+      Instr::I64Store16(mem_idx, offset, align) => self.store(*mem_idx, *offset, *align, 2, I64)?,
+      Instr::I64Store32(mem_idx, offset, align) => self.store(*mem_idx, *offset, *align, 4, I64)?,
+      Instr::MemorySize(mem_idx) => {
+        let addr_type = self.check_memory(*mem_idx)?;
+        self.push_val(addr_type);
+      }
+      Instr::MemoryGrow(mem_idx) => {
+        let addr_type = self.check_memory(*mem_idx)?;
+        self.op(&[addr_type], &[addr_type])?;
+      }
+      Instr::MemoryFill(mem_idx) => {
+        let addr_type = self.check_memory(*mem_idx)?;
+        self.op(&[addr_type, I32, addr_type], &[])?;
+      }
+      Instr::MemoryCopy(dst_mem_idx, src_mem_idx) => {
+        let dst_addr_type = self.check_memory(*dst_mem_idx)?;
+        let src_addr_type = self.check_memory(*src_mem_idx)?;
+        // the size is 64-bit only when both memories are
+        let size_type = if dst_addr_type == I64 && src_addr_type == I64 {
+          I64
+        } else {
+          I32
+        };
+        self.op(&[dst_addr_type, src_addr_type, size_type], &[])?;
+      }
+      Instr::MemoryInit(mem_idx, data_idx) => {
+        let addr_type = self.check_memory(*mem_idx)?;
+        self.check_data(*data_idx)?;
+        self.op(&[addr_type, I32, I32], &[])?;
+      }
+      Instr::DataDrop(data_idx) => self.check_data(*data_idx)?,
+
+      Instr::I32Const(_) => self.push_val(I32),
+      Instr::I64Const(_) => self.push_val(I64),
+      Instr::F32Const(_) => self.push_val(F32),
+      Instr::F64Const(_) => self.push_val(F64),
+
+      Instr::I32Eqz => self.op(&[I32], &[I32])?,
+      Instr::I32Eq
+      | Instr::I32Ne
+      | Instr::I32LtS
+      | Instr::I32LtU
+      | Instr::I32GtS
+      | Instr::I32GtU
+      | Instr::I32LeS
+      | Instr::I32LeU
+      | Instr::I32GeS
+      | Instr::I32GeU => self.op(&[I32, I32], &[I32])?,
+      Instr::I64Eqz => self.op(&[I64], &[I32])?,
+      Instr::I64Eq
+      | Instr::I64Ne
+      | Instr::I64LtS
+      | Instr::I64LtU
+      | Instr::I64GtS
+      | Instr::I64GtU
+      | Instr::I64LeS
+      | Instr::I64LeU
+      | Instr::I64GeS
+      | Instr::I64GeU => self.op(&[I64, I64], &[I32])?,
+      Instr::F32Eq | Instr::F32Ne | Instr::F32Lt | Instr::F32Gt | Instr::F32Le | Instr::F32Ge => {
+        self.op(&[F32, F32], &[I32])?
+      }
+      Instr::F64Eq | Instr::F64Ne | Instr::F64Lt | Instr::F64Gt | Instr::F64Le | Instr::F64Ge => {
+        self.op(&[F64, F64], &[I32])?
+      }
+
+      Instr::I32Clz | Instr::I32Ctz | Instr::I32Popcnt | Instr::I32Extend8S | Instr::I32Extend16S => {
+        self.op(&[I32], &[I32])?
+      }
+      Instr::I32Add
+      | Instr::I32Sub
+      | Instr::I32Mul
+      | Instr::I32DivS
+      | Instr::I32DivU
+      | Instr::I32RemS
+      | Instr::I32RemU
+      | Instr::I32And
+      | Instr::I32Or
+      | Instr::I32Xor
+      | Instr::I32Shl
+      | Instr::I32ShrS
+      | Instr::I32ShrU
+      | Instr::I32Rotl
+      | Instr::I32Rotr => self.op(&[I32, I32], &[I32])?,
+      Instr::I64Clz
+      | Instr::I64Ctz
+      | Instr::I64Popcnt
+      | Instr::I64Extend8S
+      | Instr::I64Extend16S
+      | Instr::I64Extend32S => self.op(&[I64], &[I64])?,
+      Instr::I64Add
+      | Instr::I64Sub
+      | Instr::I64Mul
+      | Instr::I64DivS
+      | Instr::I64DivU
+      | Instr::I64RemS
+      | Instr::I64RemU
+      | Instr::I64And
+      | Instr::I64Or
+      | Instr::I64Xor
+      | Instr::I64Shl
+      | Instr::I64ShrS
+      | Instr::I64ShrU
+      | Instr::I64Rotl
+      | Instr::I64Rotr => self.op(&[I64, I64], &[I64])?,
+      Instr::F32Abs
+      | Instr::F32Neg
+      | Instr::F32Ceil
+      | Instr::F32Floor
+      | Instr::F32Trunc
+      | Instr::F32Nearest
+      | Instr::F32Sqrt => self.op(&[F32], &[F32])?,
+      Instr::F32Add
+      | Instr::F32Sub
+      | Instr::F32Mul
+      | Instr::F32Div
+      | Instr::F32Min
+      | Instr::F32Max
+      | Instr::F32Copysign => self.op(&[F32, F32], &[F32])?,
+      Instr::F64Abs
+      | Instr::F64Neg
+      | Instr::F64Ceil
+      | Instr::F64Floor
+      | Instr::F64Trunc
+      | Instr::F64Nearest
+      | Instr::F64Sqrt => self.op(&[F64], &[F64])?,
+      Instr::F64Add
+      | Instr::F64Sub
+      | Instr::F64Mul
+      | Instr::F64Div
+      | Instr::F64Min
+      | Instr::F64Max
+      | Instr::F64Copysign => self.op(&[F64, F64], &[F64])?,
+
+      Instr::I32WrapI64 => self.op(&[I64], &[I32])?,
+      Instr::I32TruncF32S | Instr::I32TruncF32U | Instr::I32TruncSatF32S | Instr::I32TruncSatF32U => {
+        self.op(&[F32], &[I32])?
+      }
+      Instr::I32TruncF64S | Instr::I32TruncF64U | Instr::I32TruncSatF64S | Instr::I32TruncSatF64U => {
+        self.op(&[F64], &[I32])?
+      }
+      Instr::I64ExtendI32S | Instr::I64ExtendI32U => self.op(&[I32], &[I64])?,
+      Instr::I64TruncF32S | Instr::I64TruncF32U | Instr::I64TruncSatF32S | Instr::I64TruncSatF32U => {
+        self.op(&[F32], &[I64])?
+      }
+      Instr::I64TruncF64S | Instr::I64TruncF64U | Instr::I64TruncSatF64S | Instr::I64TruncSatF64U => {
+        self.op(&[F64], &[I64])?
+      }
+      Instr::F32ConvertI32S | Instr::F32ConvertI32U => self.op(&[I32], &[F32])?,
+      Instr::F32ConvertI64S | Instr::F32ConvertI64U => self.op(&[I64], &[F32])?,
+      Instr::F32DemoteF64 => self.op(&[F64], &[F32])?,
+      Instr::F64ConvertI32S | Instr::F64ConvertI32U => self.op(&[I32], &[F64])?,
+      Instr::F64ConvertI64S | Instr::F64ConvertI64U => self.op(&[I64], &[F64])?,
+      Instr::F64PromoteF32 => self.op(&[F32], &[F64])?,
+      Instr::I32ReinterpretF32 => self.op(&[F32], &[I32])?,
+      Instr::I64ReinterpretF64 => self.op(&[F64], &[I64])?,
+      Instr::F32ReinterpretI32 => self.op(&[I32], &[F32])?,
+      Instr::F64ReinterpretI64 => self.op(&[I64], &[F64])?,
+
+      Instr::V128Load(mem_idx, offset, align) => self.load(*mem_idx, *offset, *align, 16, V128)?,
+      Instr::V128Load8X8S(mem_idx, offset, align)
+      | Instr::V128Load8X8U(mem_idx, offset, align)
+      | Instr::V128Load16X4S(mem_idx, offset, align)
+      | Instr::V128Load16X4U(mem_idx, offset, align)
+      | Instr::V128Load32X2S(mem_idx, offset, align)
+      | Instr::V128Load32X2U(mem_idx, offset, align)
+      | Instr::V128Load64Splat(mem_idx, offset, align)
+      | Instr::V128Load64Zero(mem_idx, offset, align) => self.load(*mem_idx, *offset, *align, 8, V128)?,
+      Instr::V128Load8Splat(mem_idx, offset, align) => self.load(*mem_idx, *offset, *align, 1, V128)?,
+      Instr::V128Load16Splat(mem_idx, offset, align) => self.load(*mem_idx, *offset, *align, 2, V128)?,
+      Instr::V128Load32Splat(mem_idx, offset, align) | Instr::V128Load32Zero(mem_idx, offset, align) => {
+        self.load(*mem_idx, *offset, *align, 4, V128)?
+      }
+      Instr::V128Store(mem_idx, offset, align) => self.store(*mem_idx, *offset, *align, 16, V128)?,
+      Instr::V128Load8Lane(mem_idx, offset, align, lane)
+      | Instr::V128Load16Lane(mem_idx, offset, align, lane)
+      | Instr::V128Load32Lane(mem_idx, offset, align, lane)
+      | Instr::V128Load64Lane(mem_idx, offset, align, lane)
+      | Instr::V128Store8Lane(mem_idx, offset, align, lane)
+      | Instr::V128Store16Lane(mem_idx, offset, align, lane)
+      | Instr::V128Store32Lane(mem_idx, offset, align, lane)
+      | Instr::V128Store64Lane(mem_idx, offset, align, lane) => {
+        let n_bytes = match instr {
+          Instr::V128Load8Lane(..) | Instr::V128Store8Lane(..) => 1,
+          Instr::V128Load16Lane(..) | Instr::V128Store16Lane(..) => 2,
+          Instr::V128Load32Lane(..) | Instr::V128Store32Lane(..) => 4,
+          _ => 8,
+        };
+        let addr_type = self.check_memarg(*mem_idx, *offset, *align, n_bytes)?;
+        self.check_lane(*lane, 16 / n_bytes as u8)?;
+        self.pop_expect(V128)?;
+        self.pop_expect(addr_type)?;
+        if matches!(
+          instr,
+          Instr::V128Load8Lane(..) | Instr::V128Load16Lane(..) | Instr::V128Load32Lane(..) | Instr::V128Load64Lane(..)
+        ) {
+          self.push_val(V128);
+        }
+      }
+
+      Instr::V128Const(_) => self.push_val(V128),
+      Instr::I8X16Shuffle(lanes) => {
+        for lane in lanes {
+          self.check_lane(*lane, 32)?;
+        }
+        self.op(&[V128, V128], &[V128])?
+      }
+      Instr::I8X16Splat | Instr::I16X8Splat | Instr::I32X4Splat => self.op(&[I32], &[V128])?,
+      Instr::I64X2Splat => self.op(&[I64], &[V128])?,
+      Instr::F32X4Splat => self.op(&[F32], &[V128])?,
+      Instr::F64X2Splat => self.op(&[F64], &[V128])?,
+      Instr::I8X16ExtractLaneS(lane) | Instr::I8X16ExtractLaneU(lane) => {
+        self.check_lane(*lane, 16)?;
+        self.op(&[V128], &[I32])?
+      }
+      Instr::I16X8ExtractLaneS(lane) | Instr::I16X8ExtractLaneU(lane) => {
+        self.check_lane(*lane, 8)?;
+        self.op(&[V128], &[I32])?
+      }
+      Instr::I32X4ExtractLane(lane) => {
+        self.check_lane(*lane, 4)?;
+        self.op(&[V128], &[I32])?
+      }
+      Instr::I64X2ExtractLane(lane) => {
+        self.check_lane(*lane, 2)?;
+        self.op(&[V128], &[I64])?
+      }
+      Instr::F32X4ExtractLane(lane) => {
+        self.check_lane(*lane, 4)?;
+        self.op(&[V128], &[F32])?
+      }
+      Instr::F64X2ExtractLane(lane) => {
+        self.check_lane(*lane, 2)?;
+        self.op(&[V128], &[F64])?
+      }
+      Instr::I8X16ReplaceLane(lane) => {
+        self.check_lane(*lane, 16)?;
+        self.op(&[V128, I32], &[V128])?
+      }
+      Instr::I16X8ReplaceLane(lane) => {
+        self.check_lane(*lane, 8)?;
+        self.op(&[V128, I32], &[V128])?
+      }
+      Instr::I32X4ReplaceLane(lane) => {
+        self.check_lane(*lane, 4)?;
+        self.op(&[V128, I32], &[V128])?
+      }
+      Instr::I64X2ReplaceLane(lane) => {
+        self.check_lane(*lane, 2)?;
+        self.op(&[V128, I64], &[V128])?
+      }
+      Instr::F32X4ReplaceLane(lane) => {
+        self.check_lane(*lane, 4)?;
+        self.op(&[V128, F32], &[V128])?
+      }
+      Instr::F64X2ReplaceLane(lane) => {
+        self.check_lane(*lane, 2)?;
+        self.op(&[V128, F64], &[V128])?
+      }
+
+      Instr::V128Not
+      | Instr::F32X4DemoteF64X2Zero
+      | Instr::F64X2PromoteLowF32X4
+      | Instr::I8X16Abs
+      | Instr::I8X16Neg
+      | Instr::I8X16Popcnt
+      | Instr::F32X4Ceil
+      | Instr::F32X4Floor
+      | Instr::F32X4Trunc
+      | Instr::F32X4Nearest
+      | Instr::F64X2Ceil
+      | Instr::F64X2Floor
+      | Instr::F64X2Trunc
+      | Instr::I16X8ExtaddPairwiseI8X16S
+      | Instr::I16X8ExtaddPairwiseI8X16U
+      | Instr::I32X4ExtaddPairwiseI16X8S
+      | Instr::I32X4ExtaddPairwiseI16X8U
+      | Instr::I16X8Abs
+      | Instr::I16X8Neg
+      | Instr::I16X8ExtendLowI8X16S
+      | Instr::I16X8ExtendHighI8X16S
+      | Instr::I16X8ExtendLowI8X16U
+      | Instr::I16X8ExtendHighI8X16U
+      | Instr::F64X2Nearest
+      | Instr::I32X4Abs
+      | Instr::I32X4Neg
+      | Instr::I32X4ExtendLowI16X8S
+      | Instr::I32X4ExtendHighI16X8S
+      | Instr::I32X4ExtendLowI16X8U
+      | Instr::I32X4ExtendHighI16X8U
+      | Instr::I64X2Abs
+      | Instr::I64X2Neg
+      | Instr::I64X2ExtendLowI32X4S
+      | Instr::I64X2ExtendHighI32X4S
+      | Instr::I64X2ExtendLowI32X4U
+      | Instr::I64X2ExtendHighI32X4U
+      | Instr::F32X4Abs
+      | Instr::F32X4Neg
+      | Instr::F32X4Sqrt
+      | Instr::F64X2Abs
+      | Instr::F64X2Neg
+      | Instr::F64X2Sqrt
+      | Instr::I32X4TruncSatF32X4S
+      | Instr::I32X4TruncSatF32X4U
+      | Instr::F32X4ConvertI32X4S
+      | Instr::F32X4ConvertI32X4U
+      | Instr::I32X4TruncSatF64X2SZero
+      | Instr::I32X4TruncSatF64X2UZero
+      | Instr::F64X2ConvertLowI32X4S
+      | Instr::F64X2ConvertLowI32X4U
+      | Instr::I32X4RelaxedTruncF32X4S
+      | Instr::I32X4RelaxedTruncF32X4U
+      | Instr::I32X4RelaxedTruncF64X2SZero
+      | Instr::I32X4RelaxedTruncF64X2UZero => self.op(&[V128], &[V128])?,
+      Instr::I8X16Swizzle
+      | Instr::I8X16Eq
+      | Instr::I8X16Ne
+      | Instr::I8X16LtS
+      | Instr::I8X16LtU
+      | Instr::I8X16GtS
+      | Instr::I8X16GtU
+      | Instr::I8X16LeS
+      | Instr::I8X16LeU
+      | Instr::I8X16GeS
+      | Instr::I8X16GeU
+      | Instr::I16X8Eq
+      | Instr::I16X8Ne
+      | Instr::I16X8LtS
+      | Instr::I16X8LtU
+      | Instr::I16X8GtS
+      | Instr::I16X8GtU
+      | Instr::I16X8LeS
+      | Instr::I16X8LeU
+      | Instr::I16X8GeS
+      | Instr::I16X8GeU
+      | Instr::I32X4Eq
+      | Instr::I32X4Ne
+      | Instr::I32X4LtS
+      | Instr::I32X4LtU
+      | Instr::I32X4GtS
+      | Instr::I32X4GtU
+      | Instr::I32X4LeS
+      | Instr::I32X4LeU
+      | Instr::I32X4GeS
+      | Instr::I32X4GeU
+      | Instr::F32X4Eq
+      | Instr::F32X4Ne
+      | Instr::F32X4Lt
+      | Instr::F32X4Gt
+      | Instr::F32X4Le
+      | Instr::F32X4Ge
+      | Instr::F64X2Eq
+      | Instr::F64X2Ne
+      | Instr::F64X2Lt
+      | Instr::F64X2Gt
+      | Instr::F64X2Le
+      | Instr::F64X2Ge
+      | Instr::V128And
+      | Instr::V128Andnot
+      | Instr::V128Or
+      | Instr::V128Xor
+      | Instr::I8X16NarrowI16X8S
+      | Instr::I8X16NarrowI16X8U
+      | Instr::I8X16Add
+      | Instr::I8X16AddSatS
+      | Instr::I8X16AddSatU
+      | Instr::I8X16Sub
+      | Instr::I8X16SubSatS
+      | Instr::I8X16SubSatU
+      | Instr::I8X16MinS
+      | Instr::I8X16MinU
+      | Instr::I8X16MaxS
+      | Instr::I8X16MaxU
+      | Instr::I8X16AvgrU
+      | Instr::I16X8Q15MulrSatS
+      | Instr::I16X8NarrowI32X4S
+      | Instr::I16X8NarrowI32X4U
+      | Instr::I16X8Add
+      | Instr::I16X8AddSatS
+      | Instr::I16X8AddSatU
+      | Instr::I16X8Sub
+      | Instr::I16X8SubSatS
+      | Instr::I16X8SubSatU
+      | Instr::I16X8Mul
+      | Instr::I16X8MinS
+      | Instr::I16X8MinU
+      | Instr::I16X8MaxS
+      | Instr::I16X8MaxU
+      | Instr::I16X8AvgrU
+      | Instr::I16X8ExtmulLowI8X16S
+      | Instr::I16X8ExtmulHighI8X16S
+      | Instr::I16X8ExtmulLowI8X16U
+      | Instr::I16X8ExtmulHighI8X16U
+      | Instr::I32X4Add
+      | Instr::I32X4Sub
+      | Instr::I32X4Mul
+      | Instr::I32X4MinS
+      | Instr::I32X4MinU
+      | Instr::I32X4MaxS
+      | Instr::I32X4MaxU
+      | Instr::I32X4DotI16X8S
+      | Instr::I32X4ExtmulLowI16X8S
+      | Instr::I32X4ExtmulHighI16X8S
+      | Instr::I32X4ExtmulLowI16X8U
+      | Instr::I32X4ExtmulHighI16X8U
+      | Instr::I64X2Add
+      | Instr::I64X2Sub
+      | Instr::I64X2Mul
+      | Instr::I64X2Eq
+      | Instr::I64X2Ne
+      | Instr::I64X2LtS
+      | Instr::I64X2GtS
+      | Instr::I64X2LeS
+      | Instr::I64X2GeS
+      | Instr::I64X2ExtmulLowI32X4S
+      | Instr::I64X2ExtmulHighI32X4S
+      | Instr::I64X2ExtmulLowI32X4U
+      | Instr::I64X2ExtmulHighI32X4U
+      | Instr::F32X4Add
+      | Instr::F32X4Sub
+      | Instr::F32X4Mul
+      | Instr::F32X4Div
+      | Instr::F32X4Min
+      | Instr::F32X4Max
+      | Instr::F32X4Pmin
+      | Instr::F32X4Pmax
+      | Instr::F64X2Add
+      | Instr::F64X2Sub
+      | Instr::F64X2Mul
+      | Instr::F64X2Div
+      | Instr::F64X2Min
+      | Instr::F64X2Max
+      | Instr::F64X2Pmin
+      | Instr::F64X2Pmax
+      | Instr::I8X16RelaxedSwizzle
+      | Instr::F32X4RelaxedMin
+      | Instr::F32X4RelaxedMax
+      | Instr::F64X2RelaxedMin
+      | Instr::F64X2RelaxedMax
+      | Instr::I16X8RelaxedQ15MulrS
+      | Instr::I16X8RelaxedDotI8X16I7X16S => self.op(&[V128, V128], &[V128])?,
+      Instr::V128Bitselect
+      | Instr::F32X4RelaxedMadd
+      | Instr::F32X4RelaxedNmadd
+      | Instr::F64X2RelaxedMadd
+      | Instr::F64X2RelaxedNmadd
+      | Instr::I8X16RelaxedLaneselect
+      | Instr::I16X8RelaxedLaneselect
+      | Instr::I32X4RelaxedLaneselect
+      | Instr::I64X2RelaxedLaneselect
+      | Instr::I32X4RelaxedDotI8X16I7X16AddS => self.op(&[V128, V128, V128], &[V128])?,
+      Instr::V128AnyTrue
+      | Instr::I8X16AllTrue
+      | Instr::I8X16Bitmask
+      | Instr::I16X8AllTrue
+      | Instr::I16X8Bitmask
+      | Instr::I32X4AllTrue
+      | Instr::I32X4Bitmask
+      | Instr::I64X2AllTrue
+      | Instr::I64X2Bitmask => self.op(&[V128], &[I32])?,
+      Instr::I8X16Shl
+      | Instr::I8X16ShrS
+      | Instr::I8X16ShrU
+      | Instr::I16X8Shl
+      | Instr::I16X8ShrS
+      | Instr::I16X8ShrU
+      | Instr::I32X4Shl
+      | Instr::I32X4ShrS
+      | Instr::I32X4ShrU
+      | Instr::I64X2Shl
+      | Instr::I64X2ShrS
+      | Instr::I64X2ShrU => self.op(&[V128, I32], &[V128])?,
+    }
+
+    Ok(())
+  }
+}
@@ -6,6 +6,8 @@ pub mod io;
 pub mod random;
 pub mod sockets;
 
+/// WASI `errno` codes, kept for the host functions that will report them.
+#[allow(dead_code, reason = "no WASI host function is implemented yet")]
 pub(crate) enum Error {
   /// No error occurred. System call completed successfully.
   Success = 0,
@@ -1,10 +1,37 @@
 use std::{
+  cell::Cell,
   ffi::OsStr,
   fs,
   io::Result,
+  rc::Rc,
 };
 
-use wagyu_runtime::*;
+use wagyu_runtime::{
+  config::{
+    Config,
+    RelaxedSimd,
+  },
+  instance::{
+    GlobalRef,
+    MemoryRef,
+    ModuleInstance,
+    TableRef,
+  },
+  linker::Linker,
+  module::{
+    types::ExternType,
+    value::{
+      GlobalMut,
+      IndexType,
+      RefType,
+      V128Value,
+      ValType,
+      Value,
+    },
+  },
+  trap::Trap,
+  *,
+};
 
 #[test]
 /// # Panics
@@ -41,3 +68,1697 @@ fn parse_wasm_files() {
   let err_msg = err_messages.join("\n\n");
   assert!(err_msg.is_empty(), "{err_msg}");
 }
+
+fn instantiate_file(file_name: &str) -> ModuleInstance {
+  instantiate_file_with(file_name, &Linker::new()).unwrap_or_else(|err| panic!("{err}"))
+}
+
+fn instantiate_file_with(file_name: &str, linker: &Linker) -> std::result::Result<ModuleInstance, instance::Error> {
+  let buffer = fs::read(format!("tests/wasm/{file_name}")).expect("failed to read a file");
+
+  instantiate(&buffer, linker)
+}
+
+#[test]
+fn call_exported_funcs() {
+  let mut instance = instantiate_file("sample1.wasm");
+
+  let add_two = instance.get_func("addTwo").expect("`addTwo` must be exported");
+  let results = add_two.call(&mut instance, &[Value::I32(40), Value::I32(2)]);
+  assert!(matches!(results.as_deref(), Ok([Value::I32(42)])));
+  assert_eq!(
+    add_two
+      .call(&mut instance, &[Value::I32(40)])
+      .err()
+      .map(Trap::into_error),
+    Some(executor::Error::TypeMismatch)
+  );
+
+  assert!(instance.get_func("missing").is_none());
+}
+
+#[test]
+fn call_typed_funcs() {
+  let mut instance = instantiate_file("fib.wasm");
+
+  let fib = instance.get_func("fib").expect("`fib` must be exported");
+  assert!(fib.typed::<(i32, i32), i64>(&instance).is_err());
+  assert!(fib.typed::<i64, ()>(&instance).is_err());
+
+  let fib = fib.typed::<i64, i64>(&instance).expect("signature must match");
+  assert_eq!(fib.call(&mut instance, 20).ok(), Some(10_946));
+
+  let mut instance = instantiate_file("arith.wasm");
+  let div_s = instance
+    .get_func("divS")
+    .and_then(|func| func.typed::<(i32, i32), i32>(&instance).ok())
+    .expect("`divS` must be exported");
+  assert_eq!(div_s.call(&mut instance, (7, 2)).ok(), Some(3));
+  assert_eq!(
+    div_s.call(&mut instance, (7, 0)).err().map(Trap::into_error),
+    Some(executor::Error::IntegerDivideByZero)
+  );
+
+  // handles only call into the instance they were obtained from, even one of the same module
+  let mut other = instantiate_file("arith.wasm");
+  assert_eq!(
+    div_s.call(&mut other, (7, 2)).err().map(Trap::into_error),
+    Some(executor::Error::ForeignFunc)
+  );
+  assert_eq!(
+    div_s
+      .func()
+      .call(&mut other, &[Value::I32(7), Value::I32(2)])
+      .err()
+      .map(Trap::into_error),
+    Some(executor::Error::ForeignFunc)
+  );
+  assert_eq!(div_s.func().ty(&other).err(), Some(executor::Error::ForeignFunc));
+  assert!(div_s.func().typed::<(i32, i32), i32>(&other).is_err());
+}
+
+fn host_linker(started: Rc<Cell<bool>>) -> Linker {
+  let mut linker = Linker::new();
+  linker
+    .func_wrap("env", "add", |(a, b): (i32, i32)| a + b)
+    .func_new("env", "poke", &[ValType::I32, ValType::I32], &[], |caller, args| {
+      let (Value::I32(addr), Value::I32(byte)) = (args[0], args[1]) else {
+        return Err(executor::Error::TypeMismatch);
+      };
+      let memory = caller.memory(0).ok_or(executor::Error::OutOfBoundMemoryAccess)?;
+      memory.write(addr as u64, &[byte as u8])?;
+
+      Ok(vec![])
+    })
+    .func_new("env", "peek", &[ValType::I32], &[ValType::I32], |caller, args| {
+      let Value::I32(addr) = args[0] else {
+        return Err(executor::Error::TypeMismatch);
+      };
+      let memory = caller.memory(0).ok_or(executor::Error::OutOfBoundMemoryAccess)?;
+      let mut byte = [0];
+      memory.read(addr as u64, &mut byte)?;
+
+      Ok(vec![Value::I32(byte[0].into())])
+    })
+    .func_wrap("env", "started", move |()| started.set(true));
+
+  linker
+}
+
+#[test]
+fn call_host_funcs() {
+  let started = Rc::new(Cell::new(false));
+  let mut instance =
+    instantiate_file_with("host.wasm", &host_linker(Rc::clone(&started))).unwrap_or_else(|err| panic!("{err}"));
+  assert!(started.get(), "the start function must run on instantiation");
+
+  let add_twice = instance
+    .get_func("addTwice")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`addTwice` must be exported");
+  assert_eq!(add_twice.call(&mut instance, 7).ok(), Some(21));
+
+  let poke_and_load = instance
+    .get_func("pokeAndLoad")
+    .and_then(|func| func.typed::<(i32, i32), i32>(&instance).ok())
+    .expect("`pokeAndLoad` must be exported");
+  assert_eq!(poke_and_load.call(&mut instance, (100, 42)).ok(), Some(42));
+  assert_eq!(
+    poke_and_load
+      .call(&mut instance, (65_536, 1))
+      .err()
+      .map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+
+  // an error reported by the host unwinds through the wasm caller
+  let mut linker = host_linker(Rc::new(Cell::new(false)));
+  linker.func_new("env", "add", &[ValType::I32, ValType::I32], &[ValType::I32], |_, _| {
+    Err(executor::Error::Host(String::from("add is disabled")))
+  });
+  let mut instance = instantiate_file_with("host.wasm", &linker).unwrap_or_else(|err| panic!("{err}"));
+  let add_twice = instance
+    .get_func("addTwice")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`addTwice` must be exported");
+  let Err(trap) = add_twice.call(&mut instance, 7) else {
+    panic!("`addTwice` must trap when `add` fails");
+  };
+  assert_eq!(trap.error(), &executor::Error::Host(String::from("add is disabled")));
+  let frames: Vec<_> = trap.backtrace().iter().map(|frame| frame.func_idx()).collect();
+  assert_eq!(frames, [5]);
+  assert!(trap
+    .to_string()
+    .starts_with("Runtime error: add is disabled\nwasm backtrace:\n  0: function 5 at"));
+}
+
+#[test]
+fn report_link_errors() {
+  let mut linker = host_linker(Rc::new(Cell::new(false)));
+
+  linker.func_wrap("env", "add", |a: i64| a);
+  let err = instantiate_file_with("host.wasm", &linker).err();
+  assert!(
+    matches!(&err, Some(instance::Error::IncompatibleImport { module, name, .. }) if module == "env" && name == "add"),
+    "{err:?}"
+  );
+
+  linker.define(
+    "env",
+    "add",
+    MemoryRef::new(1, None).expect("failed to create a memory"),
+  );
+  let err = instantiate_file_with("host.wasm", &linker).err();
+  assert!(
+    matches!(&err, Some(instance::Error::IncompatibleImport { message, .. }) if message == "expected function, found memory"),
+    "{err:?}"
+  );
+
+  let err = instantiate_file_with("host.wasm", &Linker::new()).err();
+  assert!(
+    matches!(&err, Some(instance::Error::MissingImport { module, name }) if module == "env" && name == "add"),
+    "{err:?}"
+  );
+}
+
+#[test]
+fn limit_stack_depth() {
+  // the start function recurses 64 calls deep
+  assert!(instantiate_file_with("start_recursion.wasm", &Linker::new()).is_ok());
+
+  let mut config = Config::new();
+  config.stack_limits(1024, 32);
+  let Err(instance::Error::Trap(trap)) = instantiate_file_with("start_recursion.wasm", &Linker::with_config(config))
+  else {
+    panic!("the start function must exceed the call depth");
+  };
+  assert_eq!(trap.error(), &executor::Error::StackOverflow);
+  assert_eq!(trap.backtrace().len(), 32);
+}
+
+#[test]
+fn call_through_tables() {
+  let mut instance = instantiate_file("table.wasm");
+
+  let setup = instance
+    .get_func("setup")
+    .and_then(|func| func.typed::<(), ()>(&instance).ok())
+    .expect("`setup` must be exported");
+  let apply = instance
+    .get_func("apply")
+    .and_then(|func| func.typed::<(i32, i32, i32), i32>(&instance).ok())
+    .expect("`apply` must be exported");
+  let grow = instance
+    .get_func("grow")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`grow` must be exported");
+  let grow_spare = instance
+    .get_func("growSpare")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`growSpare` must be exported");
+  let size = instance
+    .get_func("size")
+    .and_then(|func| func.typed::<(), i32>(&instance).ok())
+    .expect("`size` must be exported");
+  let is_null = instance
+    .get_func("isNull")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`isNull` must be exported");
+
+  assert_eq!(
+    apply.call(&mut instance, (0, 1, 2)).err().map(Trap::into_error),
+    Some(executor::Error::UninitializedElement)
+  );
+
+  setup.call(&mut instance, ()).expect("`setup` must not trap");
+  assert_eq!(apply.call(&mut instance, (0, 5, 3)).ok(), Some(8));
+  assert_eq!(apply.call(&mut instance, (1, 5, 3)).ok(), Some(2));
+  assert_eq!(
+    apply.call(&mut instance, (2, 5, 3)).err().map(Trap::into_error),
+    Some(executor::Error::IndirectCallTypeMismatch)
+  );
+  assert_eq!(
+    apply.call(&mut instance, (4, 5, 3)).err().map(Trap::into_error),
+    Some(executor::Error::UndefinedElement)
+  );
+
+  assert_eq!(is_null.call(&mut instance, 0).ok(), Some(0));
+  assert_eq!(is_null.call(&mut instance, 3).ok(), Some(1));
+  assert_eq!(
+    is_null.call(&mut instance, 4).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundTableAccess)
+  );
+
+  assert_eq!(grow.call(&mut instance, 2).ok(), Some(4));
+  assert_eq!(size.call(&mut instance, ()).ok(), Some(6));
+  assert_eq!(grow.call(&mut instance, 3).ok(), Some(-1));
+  assert_eq!(is_null.call(&mut instance, 5).ok(), Some(1));
+
+  // a table without a maximum still stops at the implementation limit
+  assert_eq!(grow_spare.call(&mut instance, 0x7FFF_FFFF).ok(), Some(-1));
+  assert_eq!(grow_spare.call(&mut instance, 2).ok(), Some(0));
+}
+
+#[test]
+fn fail_table_allocation() {
+  // the table is only allocated at instantiation, so a huge declared size does not abort the parser
+  let buffer = fs::read("tests/wasm/huge_table.wasm").expect("failed to read a file");
+  let module = compile(&buffer).unwrap_or_else(|err| panic!("{err}"));
+  assert_eq!(module.imports().count(), 0);
+
+  let err = instantiate_file_with("huge_table.wasm", &Linker::new()).err();
+  assert!(
+    matches!(err, Some(instance::Error::TableAllocation { elements: u32::MAX })),
+    "{err:?}"
+  );
+  assert_eq!(
+    TableRef::new(Value::FuncRef(None), u32::MAX, None).err(),
+    Some(executor::Error::MemoryExhaustion)
+  );
+}
+
+#[test]
+fn init_tables_from_elems() {
+  let mut instance = instantiate_file("elem.wasm");
+
+  let call = instance
+    .get_func("call")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`call` must be exported");
+  let call_more = instance
+    .get_func("callMore")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`callMore` must be exported");
+  let init = instance
+    .get_func("init")
+    .and_then(|func| func.typed::<(i32, i32, i32), ()>(&instance).ok())
+    .expect("`init` must be exported");
+  let init_exprs = instance
+    .get_func("initExprs")
+    .and_then(|func| func.typed::<(i32, i32, i32), ()>(&instance).ok())
+    .expect("`initExprs` must be exported");
+  let drop = instance
+    .get_func("drop")
+    .and_then(|func| func.typed::<(), ()>(&instance).ok())
+    .expect("`drop` must be exported");
+
+  // active segments are applied on instantiation
+  assert_eq!(call.call(&mut instance, 0).ok(), Some(1));
+  assert_eq!(call.call(&mut instance, 1).ok(), Some(2));
+  assert_eq!(call.call(&mut instance, 2).ok(), Some(3));
+  assert_eq!(
+    call.call(&mut instance, 3).err().map(Trap::into_error),
+    Some(executor::Error::UninitializedElement)
+  );
+  assert_eq!(call_more.call(&mut instance, 1).ok(), Some(4));
+
+  // passive segments are copied by `table.init`
+  init.call(&mut instance, (4, 0, 2)).expect("`init` must not trap");
+  assert_eq!(call.call(&mut instance, 4).ok(), Some(3));
+  assert_eq!(call.call(&mut instance, 5).ok(), Some(4));
+  init_exprs
+    .call(&mut instance, (5, 0, 3))
+    .expect("`initExprs` must not trap");
+  assert_eq!(call.call(&mut instance, 5).ok(), Some(2));
+  assert_eq!(
+    call.call(&mut instance, 6).err().map(Trap::into_error),
+    Some(executor::Error::UninitializedElement)
+  );
+  assert_eq!(call.call(&mut instance, 7).ok(), Some(1));
+  assert_eq!(
+    init.call(&mut instance, (7, 0, 2)).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundTableAccess)
+  );
+  assert_eq!(
+    init.call(&mut instance, (0, 1, 2)).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundTableAccess)
+  );
+
+  // a dropped segment only supports empty copies
+  drop.call(&mut instance, ()).expect("`drop` must not trap");
+  init
+    .call(&mut instance, (0, 0, 0))
+    .expect("an empty copy must not trap");
+  assert_eq!(
+    init.call(&mut instance, (0, 0, 1)).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundTableAccess)
+  );
+
+  let ref_two = instance.get_func("ref").expect("`ref` must be exported");
+  let results = ref_two.call(&mut instance, &[]);
+  assert!(matches!(results.as_deref(), Ok([Value::FuncRef(Some(1))])));
+}
+
+#[test]
+fn init_globals() {
+  let mut instance = instantiate_file("globals.wasm");
+
+  for (name, expected) in [
+    ("answer", Value::I32(42)),
+    ("big", Value::I64(-5_000_000_000)),
+    ("pi", Value::F32(3.5)),
+    ("e", Value::F64(2.25)),
+    ("bump", Value::I32(11)),
+    ("bump", Value::I32(12)),
+    ("offset", Value::I32(2)),
+    ("wide", Value::I64(-1)),
+    ("wraps", Value::I32(0)),
+    ("fnIsNull", Value::I32(0)),
+    ("noneIsNull", Value::I32(1)),
+  ] {
+    let func = instance
+      .get_func(name)
+      .unwrap_or_else(|| panic!("`{name}` must be exported"));
+    let results = func
+      .call(&mut instance, &[])
+      .unwrap_or_else(|err| panic!("{name}: {err}"));
+    assert_eq!(format!("{results:?}"), format!("{:?}", [expected]), "{name}");
+  }
+
+  // the element segment is placed at an offset computed by an extended constant expression
+  let call = instance
+    .get_func("call")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`call` must be exported");
+  assert_eq!(call.call(&mut instance, 1).ok(), Some(7));
+}
+
+#[test]
+fn init_memories_from_data() {
+  let mut linker = Linker::new();
+  linker.func_new(
+    "env",
+    "peek",
+    &[ValType::I32, ValType::I32],
+    &[ValType::I32],
+    |caller, args| {
+      let (Value::I32(mem_idx), Value::I32(addr)) = (args[0], args[1]) else {
+        return Err(executor::Error::TypeMismatch);
+      };
+      let memory = caller
+        .memory(mem_idx as u32)
+        .ok_or(executor::Error::OutOfBoundMemoryAccess)?;
+      let mut byte = [0];
+      memory.read(addr as u64, &mut byte)?;
+
+      Ok(vec![Value::I32(byte[0].into())])
+    },
+  );
+  let mut instance = instantiate_file_with("data.wasm", &linker).unwrap_or_else(|err| panic!("{err}"));
+
+  let load = instance
+    .get_func("load")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`load` must be exported");
+  let load_aux = instance
+    .get_func("loadAux")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`loadAux` must be exported");
+
+  // segments are raw bytes, which need not be valid UTF-8
+  assert_eq!(load.call(&mut instance, 0).ok(), Some(0xFF));
+  assert_eq!(load.call(&mut instance, 1).ok(), Some(0x00));
+  assert_eq!(load.call(&mut instance, 2).ok(), Some(0x80));
+  assert_eq!(load.call(&mut instance, 272).ok(), Some(0));
+  assert_eq!(load_aux.call(&mut instance, 272).ok(), Some(0xC3));
+  assert_eq!(load_aux.call(&mut instance, 273).ok(), Some(0x28));
+
+  // segments that do not fit in their memory trap on instantiation
+  assert!(matches!(
+    instantiate_file_with("data_oob.wasm", &Linker::new()),
+    Err(instance::Error::Trap(trap)) if *trap.error() == executor::Error::OutOfBoundMemoryAccess && trap.backtrace().is_empty()
+  ));
+}
+
+#[test]
+fn access_memory() {
+  let mut instance = instantiate_file("memory.wasm");
+  macro_rules! func {
+    ($name:literal, $params:ty, $results:ty) => {
+      instance
+        .get_func($name)
+        .and_then(|func| func.typed::<$params, $results>(&instance).ok())
+        .expect(concat!("`", $name, "` must be exported"))
+    };
+  }
+  let store = func!("store", (i32, i64), ());
+  let load8_s = func!("load8_s", i32, i32);
+  let load8_u = func!("load8_u", i32, i32);
+  let load16_s = func!("load16_s", i32, i64);
+  let load32_u = func!("load32_u", i32, i64);
+  let load_f64 = func!("load_f64", i32, f64);
+  let load_aux = func!("load_aux", i32, i32);
+  let store_aux = func!("store_aux", (i32, i32), ());
+  let size = func!("size", (), i32);
+  let fill = func!("fill", (i32, i32, i32), ());
+  let copy = func!("copy", (i32, i32, i32), ());
+  let init = func!("init", (i32, i32, i32), ());
+  let drop = func!("drop", (), ());
+  let peek = func!("peek", i32, i32);
+
+  // values are stored little-endian at the address plus the static offset
+  store
+    .call(&mut instance, (0, 0x8877_6655_4433_22F1_u64 as i64))
+    .expect("the store must be in bounds");
+  assert_eq!(peek.call(&mut instance, 8).ok(), Some(0xF1));
+  assert_eq!(load8_s.call(&mut instance, 0).ok(), Some(-15));
+  assert_eq!(load8_u.call(&mut instance, 0).ok(), Some(0xF1));
+  assert_eq!(load16_s.call(&mut instance, 0).ok(), Some(0x22F1));
+  assert_eq!(load16_s.call(&mut instance, 6).ok(), Some(-0x7789));
+  assert_eq!(load32_u.call(&mut instance, 4).ok(), Some(0x8877_6655));
+  store
+    .call(&mut instance, (16, 1.5_f64.to_bits() as i64))
+    .expect("the store must be in bounds");
+  assert_eq!(load_f64.call(&mut instance, 16).ok(), Some(1.5));
+
+  // the effective address does not wrap around
+  assert_eq!(load32_u.call(&mut instance, 65_536 - 12).ok(), Some(0));
+  assert_eq!(
+    load32_u.call(&mut instance, 65_536 - 11).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+  assert_eq!(
+    load8_u.call(&mut instance, -1).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+
+  // an explicit memory index selects the second memory
+  store_aux
+    .call(&mut instance, (3, 0x1FF))
+    .expect("the store must be in bounds");
+  assert_eq!(load_aux.call(&mut instance, 0).ok(), Some(0xFF00_0000_u32 as i32));
+  assert_eq!(peek.call(&mut instance, 3).ok(), Some(0));
+  assert_eq!(size.call(&mut instance, ()).ok(), Some(1));
+
+  fill
+    .call(&mut instance, (100, 0xAB, 4))
+    .expect("the fill must be in bounds");
+  assert_eq!(peek.call(&mut instance, 103).ok(), Some(0xAB));
+  assert_eq!(peek.call(&mut instance, 104).ok(), Some(0));
+  assert_eq!(
+    fill.call(&mut instance, (65_535, 0, 2)).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+  copy
+    .call(&mut instance, (101, 100, 4))
+    .expect("the copy must be in bounds");
+  assert_eq!(peek.call(&mut instance, 104).ok(), Some(0xAB));
+
+  init
+    .call(&mut instance, (300, 1, 3))
+    .expect("the segment range must be in bounds");
+  assert_eq!(peek.call(&mut instance, 300).ok(), Some(i32::from(b'e')));
+  assert_eq!(peek.call(&mut instance, 302).ok(), Some(i32::from(b'l')));
+  assert_eq!(
+    init.call(&mut instance, (300, 3, 3)).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+  drop.call(&mut instance, ()).expect("`data.drop` must not trap");
+  assert!(init.call(&mut instance, (300, 0, 0)).is_ok());
+  assert_eq!(
+    init.call(&mut instance, (300, 0, 1)).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+}
+
+#[test]
+fn grow_memories() {
+  let mut instance = instantiate_file("memory.wasm");
+  let grow_aux = instance
+    .get_func("grow_aux")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`grow_aux` must be exported");
+  let store_aux = instance
+    .get_func("store_aux")
+    .and_then(|func| func.typed::<(i32, i32), ()>(&instance).ok())
+    .expect("`store_aux` must be exported");
+  let load_aux = instance
+    .get_func("load_aux")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`load_aux` must be exported");
+  let size = instance
+    .get_func("size")
+    .and_then(|func| func.typed::<(), i32>(&instance).ok())
+    .expect("`size` must be exported");
+
+  store_aux
+    .call(&mut instance, (65_535, 7))
+    .expect("the store must be in bounds");
+  assert_eq!(grow_aux.call(&mut instance, 0).ok(), Some(1));
+  assert_eq!(grow_aux.call(&mut instance, 1).ok(), Some(1));
+  assert_eq!(size.call(&mut instance, ()).ok(), Some(2));
+  // the contents are kept and the new page is zero-filled
+  assert_eq!(load_aux.call(&mut instance, 65_532).ok(), Some(7 << 24));
+  assert_eq!(load_aux.call(&mut instance, 65_536).ok(), Some(0));
+  assert_eq!(load_aux.call(&mut instance, 131_068).ok(), Some(0));
+  assert_eq!(
+    load_aux.call(&mut instance, 131_069).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+
+  // growing past the declared maximum fails without changing the memory
+  assert_eq!(grow_aux.call(&mut instance, 2).ok(), Some(-1));
+  assert_eq!(grow_aux.call(&mut instance, -1).ok(), Some(-1));
+  assert_eq!(grow_aux.call(&mut instance, 1).ok(), Some(2));
+  assert_eq!(size.call(&mut instance, ()).ok(), Some(3));
+
+  // without a maximum, a memory grows up to the 4 GiB address space
+  let memory = MemoryRef::new(0, None).expect("failed to create a memory");
+  assert_eq!(memory.grow(65_537), None);
+  assert_eq!(memory.grow(u64::MAX), None);
+  assert_eq!(memory.grow(2), Some(0));
+  let mut bytes = [0xFF; 4];
+  memory.read(131_068, &mut bytes).expect("the memory must be readable");
+  assert_eq!(bytes, [0; 4]);
+  let memory = MemoryRef::new(1, Some(70_000)).expect("failed to create a memory");
+  assert_eq!(memory.grow(65_536), None);
+}
+
+#[test]
+fn access_memory64() {
+  let buffer = fs::read("tests/wasm/memory64.wasm").expect("failed to read a file");
+  let module = compile(&buffer).unwrap_or_else(|err| panic!("{err}"));
+  let imports: Vec<_> = module.imports().collect();
+  assert!(matches!(imports[0].ty, ExternType::Memory(ty) if ty.idx_type() == IndexType::I64));
+
+  let mut linker = Linker::new();
+  linker.define(
+    "env",
+    "heap",
+    MemoryRef::new(1, None).expect("failed to create a memory"),
+  );
+  let Err(err) = instantiate_file_with("memory64.wasm", &linker) else {
+    panic!("a 32-bit memory must not satisfy a 64-bit import");
+  };
+  assert!(matches!(err, instance::Error::IncompatibleImport { .. }), "{err}");
+
+  let heap = MemoryRef::new64(1, None).expect("failed to create a memory");
+  linker.define("env", "heap", heap.clone());
+  let mut instance = instantiate_file_with("memory64.wasm", &linker).unwrap_or_else(|err| panic!("{err}"));
+  macro_rules! func {
+    ($name:literal, $params:ty, $results:ty) => {
+      instance
+        .get_func($name)
+        .and_then(|func| func.typed::<$params, $results>(&instance).ok())
+        .expect(concat!("`", $name, "` must be exported"))
+    };
+  }
+  let load = func!("load", i64, i64);
+  let store = func!("store", (i64, i32), ());
+  let peek = func!("peek", i64, i32);
+  let size = func!("size", (), i64);
+  let grow = func!("grow", i64, i64);
+  let fill = func!("fill", (i64, i32, i64), ());
+  let poke_heap = func!("poke_heap", (i64, i32), ());
+
+  assert_eq!(peek.call(&mut instance, 65_530).ok(), Some(i32::from(b'w')));
+  store
+    .call(&mut instance, (65_535, 7))
+    .expect("the store must be in bounds");
+  assert_eq!(peek.call(&mut instance, 65_535).ok(), Some(7));
+  assert_eq!(
+    store.call(&mut instance, (1 << 32, 7)).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+  // the static offset of `load` is 4 GiB, and the effective address does not wrap around
+  assert_eq!(
+    load.call(&mut instance, 0).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+  assert_eq!(
+    load.call(&mut instance, -(1 << 32)).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+
+  assert_eq!(size.call(&mut instance, ()).ok(), Some(1));
+  assert_eq!(grow.call(&mut instance, 1).ok(), Some(1));
+  assert_eq!(peek.call(&mut instance, 131_071).ok(), Some(0));
+  assert_eq!(grow.call(&mut instance, 70_000).ok(), Some(-1));
+  assert_eq!(size.call(&mut instance, ()).ok(), Some(2));
+
+  fill
+    .call(&mut instance, (131_070, 0xAA, 2))
+    .expect("the fill must be in bounds");
+  assert_eq!(peek.call(&mut instance, 131_071).ok(), Some(0xAA));
+  assert_eq!(
+    fill.call(&mut instance, (131_071, 0, 2)).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+
+  poke_heap
+    .call(&mut instance, (3, 9))
+    .expect("the store must be in bounds");
+  let mut byte = [0];
+  heap.read(3, &mut byte).expect("the memory must be readable");
+  assert_eq!(byte, [9]);
+  assert_eq!(heap.ty().idx_type(), IndexType::I64);
+  assert_eq!(heap.grow(1 << 48), None);
+}
+
+#[test]
+fn fail_memory_allocation() {
+  // 2^40 pages are far beyond the address space of any host
+  let err = MemoryRef::new64(1 << 40, None).err();
+  assert!(
+    matches!(err, Some(instance::Error::MemoryAllocation { pages: 0x100_0000_0000 })),
+    "{err:?}"
+  );
+  let err = instantiate_file_with("huge_memory.wasm", &Linker::new()).err();
+  assert!(
+    matches!(err, Some(instance::Error::MemoryAllocation { pages: 0x100_0000_0000 })),
+    "{err:?}"
+  );
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64", feature = "mmap-memory"))]
+#[test]
+fn grow_memory64_past_reservation() {
+  let memory = MemoryRef::new64(1, None).expect("failed to create a memory");
+  memory.write(0, b"low").expect("the memory must be writable");
+
+  // past the 4 GiB of a 32-bit address, which the memory still grows into in place
+  assert_eq!(memory.grow(65_536), Some(1));
+  memory.write(1 << 32, b"high").expect("the memory must be writable");
+
+  // past the pages reserved up front, so the memory moves
+  assert_eq!(memory.grow(1 << 17), Some(65_537));
+  let mut bytes = [0xFF; 4];
+  memory.read(0, &mut bytes).expect("the memory must be readable");
+  assert_eq!(&bytes, b"low\0");
+  memory.read(1 << 32, &mut bytes).expect("the memory must be readable");
+  assert_eq!(&bytes, b"high");
+  memory
+    .read(memory.size() * 65_536 - 4, &mut bytes)
+    .expect("the memory must be readable");
+  assert_eq!(bytes, [0; 4]);
+  assert!(memory.read(memory.size() * 65_536, &mut bytes).is_err());
+}
+
+#[test]
+fn copy_between_memories() {
+  let shared = MemoryRef::new(1, None).expect("failed to create a memory");
+  let alias = MemoryRef::new(1, None).expect("failed to create a memory");
+  let mut linker = Linker::new();
+  linker
+    .define("env", "shared", shared.clone())
+    .define("env", "alias", alias.clone());
+  let mut instance = instantiate_file_with("multi_memory.wasm", &linker).unwrap_or_else(|err| panic!("{err}"));
+  macro_rules! func {
+    ($name:literal, $params:ty, $results:ty) => {
+      instance
+        .get_func($name)
+        .and_then(|func| func.typed::<$params, $results>(&instance).ok())
+        .expect(concat!("`", $name, "` must be exported"))
+    };
+  }
+  let publish = func!("publish", (i32, i32, i32), ());
+  let mirror = func!("mirror", (i32, i32, i32), ());
+  let widen = func!("widen", (i64, i32, i32), ());
+  let peek_private = func!("peek_private", i32, i32);
+  let peek_wide = func!("peek_wide", i64, i32);
+  let sizes = func!("sizes", (), (i32, i32, i64));
+
+  assert_eq!(sizes.call(&mut instance, ()).ok(), Some((1, 1, 1)));
+  // the host only sees what the module copies out of its private memory
+  let mut bytes = [0; 6];
+  shared.read(10, &mut bytes).expect("the memory must be readable");
+  assert_eq!(&bytes, &[0; 6]);
+  publish
+    .call(&mut instance, (10, 0, 6))
+    .expect("the copy must be in bounds");
+  shared.read(10, &mut bytes).expect("the memory must be readable");
+  assert_eq!(&bytes, b"secret");
+  assert_eq!(
+    publish.call(&mut instance, (65_535, 0, 2)).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+  assert_eq!(
+    publish.call(&mut instance, (0, 65_535, 2)).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+  assert_eq!(peek_private.call(&mut instance, 10).ok(), Some(0));
+
+  mirror
+    .call(&mut instance, (0, 10, 6))
+    .expect("the copy must be in bounds");
+  alias.read(0, &mut bytes).expect("the memory must be readable");
+  assert_eq!(&bytes, b"secret");
+
+  widen
+    .call(&mut instance, (100, 1, 5))
+    .expect("the copy must be in bounds");
+  assert_eq!(peek_wide.call(&mut instance, 100).ok(), Some(i32::from(b'e')));
+
+  // the same memory imported twice copies within itself
+  linker.define("env", "alias", shared.clone());
+  let mut instance = instantiate_file_with("multi_memory.wasm", &linker).unwrap_or_else(|err| panic!("{err}"));
+  let mirror = instance
+    .get_func("mirror")
+    .and_then(|func| func.typed::<(i32, i32, i32), ()>(&instance).ok())
+    .expect("`mirror` must be exported");
+  mirror
+    .call(&mut instance, (12, 10, 6))
+    .expect("the copy must be in bounds");
+  let mut bytes = [0; 8];
+  shared.read(10, &mut bytes).expect("the memory must be readable");
+  assert_eq!(&bytes, b"sesecret");
+}
+
+#[test]
+fn canonicalize_nans() {
+  let signaling_nan = f32::from_bits(0x7FA0_0001);
+
+  // NaN results are quiet, but the payload is left to the host
+  let mut instance = instantiate_file("nan.wasm");
+  macro_rules! func {
+    ($name:literal, $params:ty, $results:ty) => {
+      instance
+        .get_func($name)
+        .and_then(|func| func.typed::<$params, $results>(&instance).ok())
+        .expect(concat!("`", $name, "` must be exported"))
+    };
+  }
+  let add = func!("add", (f32, f32), f32);
+  let min = func!("min", (f32, f32), f32);
+  let start_sum = func!("start_sum", (), f32);
+  let sum = add
+    .call(&mut instance, (signaling_nan, 1.0))
+    .expect("the add must not trap");
+  assert!(sum.is_nan() && sum.to_bits() & (1 << 22) != 0);
+  let least = min
+    .call(&mut instance, (1.0, signaling_nan))
+    .expect("the min must not trap");
+  assert_eq!(least.to_bits(), 0x7FE0_0001);
+  let sum = start_sum.call(&mut instance, ()).expect("the getter must not trap");
+  assert!(sum.is_nan() && sum.to_bits() & (1 << 22) != 0);
+
+  let mut config = Config::new();
+  config.nan_canonicalization(true);
+  let mut instance =
+    instantiate_file_with("nan.wasm", &Linker::with_config(config)).unwrap_or_else(|err| panic!("{err}"));
+  macro_rules! func {
+    ($name:literal, $params:ty, $results:ty) => {
+      instance
+        .get_func($name)
+        .and_then(|func| func.typed::<$params, $results>(&instance).ok())
+        .expect(concat!("`", $name, "` must be exported"))
+    };
+  }
+  let add = func!("add", (f32, f32), f32);
+  let min = func!("min", (f32, f32), f32);
+  let neg = func!("neg", f32, f32);
+  let div = func!("div", (f64, f64), f64);
+  let start_sum = func!("start_sum", (), f32);
+  // the start function already runs in deterministic mode
+  let sum = start_sum.call(&mut instance, ()).expect("the getter must not trap");
+  assert_eq!(sum.to_bits(), 0x7FC0_0000);
+  let sum = add
+    .call(&mut instance, (signaling_nan, 1.0))
+    .expect("the add must not trap");
+  assert_eq!(sum.to_bits(), 0x7FC0_0000);
+  let least = min
+    .call(&mut instance, (1.0, signaling_nan))
+    .expect("the min must not trap");
+  assert_eq!(least.to_bits(), 0x7FC0_0000);
+  let quotient = div.call(&mut instance, (0.0, 0.0)).expect("the div must not trap");
+  assert_eq!(quotient.to_bits(), 0x7FF8_0000_0000_0000);
+  assert_eq!(div.call(&mut instance, (1.0, 4.0)).ok(), Some(0.25));
+  // sign operators only flip bits, so the payload survives
+  let negated = neg.call(&mut instance, signaling_nan).expect("the neg must not trap");
+  assert_eq!(negated.to_bits(), 0xFFA0_0001);
+}
+
+#[test]
+fn convert_numbers() {
+  let mut instance = instantiate_file("conversions.wasm");
+  macro_rules! func {
+    ($name:literal, $params:ty, $results:ty) => {
+      instance
+        .get_func($name)
+        .and_then(|func| func.typed::<$params, $results>(&instance).ok())
+        .expect(concat!("`", $name, "` must be exported"))
+    };
+  }
+  let i32_trunc_f32_s = func!("i32.trunc_f32_s", f32, i32);
+  let i32_trunc_f64_u = func!("i32.trunc_f64_u", f64, i32);
+  let i64_trunc_f64_s = func!("i64.trunc_f64_s", f64, i64);
+  let i32_trunc_sat_f32_u = func!("i32.trunc_sat_f32_u", f32, i32);
+  let i64_trunc_sat_f64_s = func!("i64.trunc_sat_f64_s", f64, i64);
+  let i32_wrap_i64 = func!("i32.wrap_i64", i64, i32);
+  let i64_extend_i32_u = func!("i64.extend_i32_u", i32, i64);
+  let f32_convert_i64_u = func!("f32.convert_i64_u", i64, f32);
+  let f64_convert_i32_s = func!("f64.convert_i32_s", i32, f64);
+  let f32_demote_f64 = func!("f32.demote_f64", f64, f32);
+  let f64_promote_f32 = func!("f64.promote_f32", f32, f64);
+  let i32_reinterpret_f32 = func!("i32.reinterpret_f32", f32, i32);
+  let f64_reinterpret_i64 = func!("f64.reinterpret_i64", i64, f64);
+  let i32_extend8_s = func!("i32.extend8_s", i32, i32);
+  let i64_extend32_s = func!("i64.extend32_s", i64, i64);
+
+  assert_eq!(i32_trunc_f32_s.call(&mut instance, -7.9).ok(), Some(-7));
+  assert_eq!(
+    i32_trunc_f32_s
+      .call(&mut instance, 2_147_483_648.0)
+      .err()
+      .map(Trap::into_error),
+    Some(executor::Error::IntegerOverflow)
+  );
+  assert_eq!(
+    i32_trunc_f32_s
+      .call(&mut instance, f32::NAN)
+      .err()
+      .map(Trap::into_error),
+    Some(executor::Error::InvalidConversionToInteger)
+  );
+  assert_eq!(i32_trunc_f64_u.call(&mut instance, 4_294_967_295.5).ok(), Some(-1));
+  assert_eq!(
+    i32_trunc_f64_u.call(&mut instance, -1.0).err().map(Trap::into_error),
+    Some(executor::Error::IntegerOverflow)
+  );
+  assert_eq!(
+    i64_trunc_f64_s
+      .call(&mut instance, 9_223_372_036_854_775_808.0)
+      .err()
+      .map(Trap::into_error),
+    Some(executor::Error::IntegerOverflow)
+  );
+  assert_eq!(i32_trunc_sat_f32_u.call(&mut instance, -5.0).ok(), Some(0));
+  assert_eq!(i32_trunc_sat_f32_u.call(&mut instance, f32::INFINITY).ok(), Some(-1));
+  assert_eq!(i64_trunc_sat_f64_s.call(&mut instance, f64::NAN).ok(), Some(0));
+  assert_eq!(i64_trunc_sat_f64_s.call(&mut instance, -1e300).ok(), Some(i64::MIN));
+
+  assert_eq!(i32_wrap_i64.call(&mut instance, 0x1_0000_0005).ok(), Some(5));
+  assert_eq!(i64_extend_i32_u.call(&mut instance, -1).ok(), Some(0xFFFF_FFFF));
+  assert_eq!(
+    f32_convert_i64_u.call(&mut instance, -1).ok(),
+    Some(18_446_744_073_709_551_616.0)
+  );
+  assert_eq!(
+    f64_convert_i32_s.call(&mut instance, i32::MIN).ok(),
+    Some(-2_147_483_648.0)
+  );
+  // ties round to even
+  assert_eq!(
+    f32_demote_f64
+      .call(&mut instance, 1.0 + f64::from(f32::EPSILON) / 2.0)
+      .ok(),
+    Some(1.0)
+  );
+  assert_eq!(f32_demote_f64.call(&mut instance, 1e40).ok(), Some(f32::INFINITY));
+  assert_eq!(f64_promote_f32.call(&mut instance, 0.1).ok(), Some(f64::from(0.1f32)));
+  assert_eq!(i32_reinterpret_f32.call(&mut instance, -0.0).ok(), Some(i32::MIN));
+  let nan = f64_reinterpret_i64
+    .call(&mut instance, 0x7FF0_0000_0000_0001)
+    .expect("the reinterpret must not trap");
+  assert_eq!(nan.to_bits(), 0x7FF0_0000_0000_0001);
+  assert_eq!(i32_extend8_s.call(&mut instance, 0x180).ok(), Some(-128));
+  assert_eq!(
+    i64_extend32_s.call(&mut instance, 0x8000_0000).ok(),
+    Some(-2_147_483_648)
+  );
+}
+
+#[test]
+fn execute_vector_instrs() {
+  let mut instance = instantiate_file("simd.wasm");
+  macro_rules! func {
+    ($name:literal, $params:ty, $results:ty) => {
+      instance
+        .get_func($name)
+        .and_then(|func| func.typed::<$params, $results>(&instance).ok())
+        .expect(concat!("`", $name, "` must be exported"))
+    };
+  }
+  macro_rules! call {
+    ($func:expr, $params:expr) => {
+      $func.call(&mut instance, $params).expect("the call must not trap")
+    };
+  }
+  let load = func!("v128.load", i32, V128Value);
+  let load8x8_s = func!("v128.load8x8_s", i32, V128Value);
+  let load32_splat = func!("v128.load32_splat", i32, V128Value);
+  let load64_zero = func!("v128.load64_zero", i32, V128Value);
+  let load16_lane = func!("v128.load16_lane", (i32, V128Value), V128Value);
+  let store = func!("v128.store", (i32, V128Value), ());
+  let store32_lane = func!("v128.store32_lane", (i32, V128Value), ());
+  let v128_const = func!("v128.const", (), V128Value);
+  let shuffle = func!("i8x16.shuffle", (V128Value, V128Value), V128Value);
+  let swizzle = func!("i8x16.swizzle", (V128Value, V128Value), V128Value);
+  let i16x8_splat = func!("i16x8.splat", i32, V128Value);
+  let extract_lane_s = func!("i8x16.extract_lane_s", V128Value, i32);
+  let extract_lane_u = func!("i8x16.extract_lane_u", V128Value, i32);
+  let replace_lane = func!("f64x2.replace_lane", (V128Value, f64), V128Value);
+  let add_sat_u = func!("i8x16.add_sat_u", (V128Value, V128Value), V128Value);
+  let i16x8_mul = func!("i16x8.mul", (V128Value, V128Value), V128Value);
+  let i32x4_shl = func!("i32x4.shl", (V128Value, i32), V128Value);
+  let i64x2_shr_s = func!("i64x2.shr_s", (V128Value, i32), V128Value);
+  let i32x4_lt_s = func!("i32x4.lt_s", (V128Value, V128Value), V128Value);
+  let bitmask = func!("i8x16.bitmask", V128Value, i32);
+  let any_true = func!("v128.any_true", V128Value, i32);
+  let all_true = func!("i32x4.all_true", V128Value, i32);
+  let bitselect = func!("v128.bitselect", (V128Value, V128Value, V128Value), V128Value);
+  let narrow_u = func!("i8x16.narrow_i16x8_u", (V128Value, V128Value), V128Value);
+  let extmul_high = func!("i32x4.extmul_high_i16x8_s", (V128Value, V128Value), V128Value);
+  let dot = func!("i32x4.dot_i16x8_s", (V128Value, V128Value), V128Value);
+  let q15mulr = func!("i16x8.q15mulr_sat_s", (V128Value, V128Value), V128Value);
+  let f32x4_min = func!("f32x4.min", (V128Value, V128Value), V128Value);
+  let f32x4_pmin = func!("f32x4.pmin", (V128Value, V128Value), V128Value);
+  let f64x2_nearest = func!("f64x2.nearest", V128Value, V128Value);
+  let trunc_sat_s = func!("i32x4.trunc_sat_f32x4_s", V128Value, V128Value);
+  let trunc_sat_u_zero = func!("i32x4.trunc_sat_f64x2_u_zero", V128Value, V128Value);
+  let convert_low_u = func!("f64x2.convert_low_i32x4_u", V128Value, V128Value);
+  let demote_zero = func!("f32x4.demote_f64x2_zero", V128Value, V128Value);
+
+  // memory holds 01 02 .. 08 F9 FA .. FF 00 at offset 16
+  assert_eq!(
+    call!(load, 0).to_le_bytes(),
+    [1, 2, 3, 4, 5, 6, 7, 8, 0xF9, 0xFA, 0xFB, 0xFC, 0xFD, 0xFE, 0xFF, 0]
+  );
+  assert_eq!(call!(load8x8_s, 8).lanes::<[i16; 8]>(), [-7, -6, -5, -4, -3, -2, -1, 0]);
+  assert_eq!(call!(load32_splat, 0).lanes::<[u32; 4]>(), [0x0403_0201; 4]);
+  assert_eq!(call!(load64_zero, 8).lanes::<[u64; 2]>(), [0x00FF_FEFD_FCFB_FAF9, 0]);
+  assert_eq!(
+    call!(load16_lane, (0, V128Value::default())).lanes::<[u16; 8]>(),
+    [0, 0, 0, 0, 0, 0, 0, 0x0201]
+  );
+  assert_eq!(
+    load.call(&mut instance, 65_536 - 16 - 15).err().map(Trap::into_error),
+    Some(executor::Error::OutOfBoundMemoryAccess)
+  );
+  call!(store, (16, V128Value::from_lanes([1i32, 2, 3, 4])));
+  call!(store32_lane, (32, V128Value::from_lanes([5i32, 6, 7, 8])));
+  assert_eq!(call!(load, 0).lanes::<[i32; 4]>(), [1, 2, 3, 4]);
+  assert_eq!(call!(load, 16).lanes::<[i32; 4]>()[0], 7);
+  assert_eq!(call!(v128_const, ()).lanes::<[i32; 4]>(), [1, -2, 3, -4]);
+
+  let low = V128Value::from_lanes::<[u8; 16]>(core::array::from_fn(|i| i as u8));
+  let high = V128Value::from_lanes::<[u8; 16]>(core::array::from_fn(|i| i as u8 + 16));
+  assert_eq!(
+    call!(shuffle, (low, high)).lanes::<[u8; 16]>(),
+    [0, 16, 1, 17, 2, 18, 3, 19, 4, 20, 5, 21, 6, 22, 7, 23]
+  );
+  let indices = V128Value::from_lanes([0u8, 15, 16, 255, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]);
+  assert_eq!(
+    call!(swizzle, (high, indices)).lanes::<[u8; 16]>(),
+    [16, 31, 0, 0, 17, 17, 17, 17, 17, 17, 17, 17, 17, 17, 17, 17]
+  );
+  assert_eq!(call!(i16x8_splat, 0x12345).lanes::<[u16; 8]>(), [0x2345; 8]);
+  let bytes = V128Value::from_lanes([0x80u8; 16]);
+  assert_eq!(call!(extract_lane_s, bytes), -128);
+  assert_eq!(call!(extract_lane_u, bytes), 128);
+  assert_eq!(
+    call!(replace_lane, (V128Value::from_lanes([1.0f64, 2.0]), 3.5)).lanes::<[f64; 2]>(),
+    [1.0, 3.5]
+  );
+
+  let saturated = call!(
+    add_sat_u,
+    (V128Value::from_lanes([250u8; 16]), V128Value::from_lanes([10u8; 16]))
+  );
+  assert_eq!(saturated.lanes::<[u8; 16]>(), [255; 16]);
+  let product = call!(
+    i16x8_mul,
+    (V128Value::from_lanes([300i16; 8]), V128Value::from_lanes([300i16; 8]))
+  );
+  assert_eq!(product.lanes::<[i16; 8]>(), [24_464; 8]);
+  // shift counts are taken modulo the lane width
+  assert_eq!(
+    call!(i32x4_shl, (V128Value::from_lanes([1i32, 2, 3, 4]), 33)).lanes::<[i32; 4]>(),
+    [2, 4, 6, 8]
+  );
+  assert_eq!(
+    call!(i64x2_shr_s, (V128Value::from_lanes([-16i64, 16]), 66)).lanes::<[i64; 2]>(),
+    [-4, 4]
+  );
+  let mask = call!(
+    i32x4_lt_s,
+    (V128Value::from_lanes([1i32, -2, 3, -4]), V128Value::default())
+  );
+  assert_eq!(mask.lanes::<[i32; 4]>(), [0, -1, 0, -1]);
+  assert_eq!(
+    call!(bitmask, V128Value::from_bits(0x00FF_00FF_00FF_00FF_00FF_00FF_00FF_00FF)),
+    0x5555
+  );
+  assert_eq!(call!(any_true, V128Value::default()), 0);
+  assert_eq!(call!(any_true, V128Value::from_bits(1 << 127)), 1);
+  assert_eq!(call!(all_true, V128Value::from_lanes([1i32, 1, 0, 1])), 0);
+  assert_eq!(call!(all_true, V128Value::from_lanes([1i32, 2, 3, 4])), 1);
+  let selected = call!(
+    bitselect,
+    (
+      V128Value::from_bits(u128::MAX),
+      V128Value::default(),
+      V128Value::from_bits(0x0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F)
+    )
+  );
+  assert_eq!(selected.to_bits(), 0x0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F_0F0F);
+
+  let narrowed = call!(
+    narrow_u,
+    (
+      V128Value::from_lanes([-1i16, 0, 255, 256, 300, 1, 2, 3]),
+      V128Value::from_lanes([4i16; 8])
+    )
+  );
+  assert_eq!(
+    narrowed.lanes::<[u8; 16]>(),
+    [0, 0, 255, 255, 255, 1, 2, 3, 4, 4, 4, 4, 4, 4, 4, 4]
+  );
+  let widened = call!(
+    extmul_high,
+    (
+      V128Value::from_lanes([0i16, 0, 0, 0, -2, 3, i16::MIN, i16::MAX]),
+      V128Value::from_lanes([0i16, 0, 0, 0, 5, 5, i16::MIN, i16::MAX])
+    )
+  );
+  assert_eq!(widened.lanes::<[i32; 4]>(), [-10, 15, 1 << 30, 1_073_676_289]);
+  let sums = call!(
+    dot,
+    (
+      V128Value::from_lanes([1i16, 2, 3, 4, 0, 0, i16::MIN, i16::MIN]),
+      V128Value::from_lanes([5i16, 6, 7, 8, 0, 0, i16::MIN, i16::MIN])
+    )
+  );
+  assert_eq!(sums.lanes::<[i32; 4]>(), [17, 53, 0, i32::MIN]);
+  let rounded = call!(
+    q15mulr,
+    (
+      V128Value::from_lanes([i16::MIN, 0x4000, -0x4000, 1, 0, 0, 0, 0]),
+      V128Value::from_lanes([i16::MIN, 0x4000, 0x4000, 1, 0, 0, 0, 0])
+    )
+  );
+  assert_eq!(rounded.lanes::<[i16; 8]>(), [i16::MAX, 0x2000, -0x2000, 0, 0, 0, 0, 0]);
+
+  let least = call!(
+    f32x4_min,
+    (
+      V128Value::from_lanes([0.0f32, -0.0, 1.0, f32::NAN]),
+      V128Value::from_lanes([-0.0f32, 0.0, 2.0, 1.0])
+    )
+  );
+  assert_eq!(
+    least.lanes::<[u32; 4]>(),
+    [0x8000_0000, 0x8000_0000, 1.0f32.to_bits(), 0x7FC0_0000]
+  );
+  // pseudo-minimum is `b < a ? b : a`, which neither orders zeros nor propagates NaN from `b`
+  let least = call!(
+    f32x4_pmin,
+    (
+      V128Value::from_lanes([0.0f32, f32::NAN, 3.0, 1.0]),
+      V128Value::from_lanes([-0.0f32, 1.0, 2.0, f32::NAN])
+    )
+  );
+  assert_eq!(
+    least.lanes::<[u32; 4]>(),
+    [0, 0x7FC0_0000, 2.0f32.to_bits(), 1.0f32.to_bits()]
+  );
+  assert_eq!(
+    call!(f64x2_nearest, V128Value::from_lanes([2.5f64, -3.5])).lanes::<[f64; 2]>(),
+    [2.0, -4.0]
+  );
+  assert_eq!(
+    call!(trunc_sat_s, V128Value::from_lanes([f32::NAN, 1e10, -1e10, -1.9])).lanes::<[i32; 4]>(),
+    [0, i32::MAX, i32::MIN, -1]
+  );
+  assert_eq!(
+    call!(trunc_sat_u_zero, V128Value::from_lanes([-1.0f64, 5e9])).lanes::<[u32; 4]>(),
+    [0, u32::MAX, 0, 0]
+  );
+  assert_eq!(
+    call!(convert_low_u, V128Value::from_lanes([-1i32, 2, 3, 4])).lanes::<[f64; 2]>(),
+    [4_294_967_295.0, 2.0]
+  );
+  assert_eq!(
+    call!(demote_zero, V128Value::from_lanes([0.1f64, 1e40])).lanes::<[f32; 4]>(),
+    [0.1, f32::INFINITY, 0.0, 0.0]
+  );
+}
+
+#[test]
+fn execute_relaxed_vector_instrs() {
+  macro_rules! func {
+    ($instance:ident, $name:literal, $params:ty) => {
+      $instance
+        .get_func($name)
+        .and_then(|func| func.typed::<$params, V128Value>(&$instance).ok())
+        .expect(concat!("`", $name, "` must be exported"))
+    };
+  }
+  macro_rules! funcs {
+    ($instance:ident) => {
+      (
+        func!($instance, "i8x16.relaxed_swizzle", (V128Value, V128Value)),
+        func!($instance, "i32x4.relaxed_trunc_f32x4_s", V128Value),
+        func!($instance, "i32x4.relaxed_trunc_f64x2_u_zero", V128Value),
+        func!($instance, "f32x4.relaxed_madd", (V128Value, V128Value, V128Value)),
+        func!($instance, "f64x2.relaxed_nmadd", (V128Value, V128Value, V128Value)),
+        func!($instance, "i32x4.relaxed_laneselect", (V128Value, V128Value, V128Value)),
+        func!($instance, "f32x4.relaxed_min", (V128Value, V128Value)),
+        func!($instance, "f64x2.relaxed_max", (V128Value, V128Value)),
+        func!($instance, "i16x8.relaxed_q15mulr_s", (V128Value, V128Value)),
+        func!($instance, "i16x8.relaxed_dot_i8x16_i7x16_s", (V128Value, V128Value)),
+        func!(
+          $instance,
+          "i32x4.relaxed_dot_i8x16_i7x16_add_s",
+          (V128Value, V128Value, V128Value)
+        ),
+      )
+    };
+  }
+  let mut instance = instantiate_file("relaxed_simd.wasm");
+  let (
+    swizzle,
+    trunc_s,
+    trunc_u_zero,
+    f32x4_madd,
+    f64x2_nmadd,
+    laneselect,
+    f32x4_min,
+    f64x2_max,
+    q15mulr,
+    dot,
+    dot_add,
+  ) = funcs!(instance);
+
+  let bytes = V128Value::from_lanes::<[u8; 16]>(core::array::from_fn(|i| i as u8 + 16));
+  let indices = V128Value::from_lanes([0u8, 15, 16, 0x81, 0x11, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]);
+  let floats = V128Value::from_lanes([f32::NAN, 1e10, -1e10, -1.9]);
+  let doubles = V128Value::from_lanes([-1.0f64, 5e9]);
+  // the product of `1 + eps` by itself is only exact before rounding
+  let f32_operands = (
+    V128Value::from_lanes([1.0 + f32::EPSILON; 4]),
+    V128Value::from_lanes([1.0 + f32::EPSILON; 4]),
+    V128Value::from_lanes([-(1.0 + 2.0 * f32::EPSILON); 4]),
+  );
+  let f64_operands = (
+    V128Value::from_lanes([1.0 + f64::EPSILON; 2]),
+    V128Value::from_lanes([1.0 + f64::EPSILON; 2]),
+    V128Value::from_lanes([1.0 + 2.0 * f64::EPSILON; 2]),
+  );
+  let masks = (
+    V128Value::from_bits(u128::MAX),
+    V128Value::default(),
+    V128Value::from_lanes([-1i32, 0, i32::MIN, i32::MAX]),
+  );
+  let mins = (
+    V128Value::from_lanes([0.0f32, f32::NAN, 1.0, -0.0]),
+    V128Value::from_lanes([-0.0f32, 1.0, f32::NAN, 0.0]),
+  );
+  let maxs = (
+    V128Value::from_lanes([f64::NAN, 1.0]),
+    V128Value::from_lanes([2.0f64, -0.0]),
+  );
+  let q15 = V128Value::from_lanes([i16::MIN, 0x4000, 0, 0, 0, 0, 0, 0]);
+  // the last pair exceeds the 7-bit range of the second operand
+  let dot_operands = (
+    V128Value::from_lanes([1i8, 2, 3, 4, 127, 127, -128, -128, 0, 0, 0, 0, 0, 0, 0, 0]),
+    V128Value::from_lanes([5i8, 6, 7, 8, 127, 127, -1, -1, 0, 0, 0, 0, 0, 0, 0, 0]),
+  );
+  let addends = V128Value::from_lanes([1000i32, 0, 0, i32::MAX]);
+
+  macro_rules! call {
+    ($func:expr, $params:expr) => {
+      $func.call(&mut instance, $params).expect("the call must not trap")
+    };
+  }
+
+  // the deterministic results match the non-relaxed instructions and fused multiply-add
+  assert_eq!(
+    call!(swizzle, (bytes, indices)).lanes::<[u8; 16]>(),
+    [16, 31, 0, 0, 0, 17, 17, 17, 17, 17, 17, 17, 17, 17, 17, 17]
+  );
+  assert_eq!(call!(trunc_s, floats).lanes::<[i32; 4]>(), [0, i32::MAX, i32::MIN, -1]);
+  assert_eq!(call!(trunc_u_zero, doubles).lanes::<[u32; 4]>(), [0, u32::MAX, 0, 0]);
+  assert_eq!(
+    call!(f32x4_madd, f32_operands).lanes::<[f32; 4]>(),
+    [f32::EPSILON * f32::EPSILON; 4]
+  );
+  assert_eq!(
+    call!(f64x2_nmadd, f64_operands).lanes::<[f64; 2]>(),
+    [-f64::EPSILON * f64::EPSILON; 2]
+  );
+  assert_eq!(
+    call!(laneselect, masks).lanes::<[i32; 4]>(),
+    [-1, 0, i32::MIN, i32::MAX]
+  );
+  assert_eq!(
+    call!(f32x4_min, mins).lanes::<[u32; 4]>(),
+    [0x8000_0000, 0x7FC0_0000, 0x7FC0_0000, 0x8000_0000]
+  );
+  assert_eq!(
+    call!(f64x2_max, maxs).lanes::<[u64; 2]>(),
+    [0x7FF8_0000_0000_0000, 1.0f64.to_bits()]
+  );
+  assert_eq!(
+    call!(q15mulr, (q15, q15)).lanes::<[i16; 8]>(),
+    [i16::MAX, 0x2000, 0, 0, 0, 0, 0, 0]
+  );
+  assert_eq!(
+    call!(dot, dot_operands).lanes::<[i16; 8]>(),
+    [17, 53, 32_258, 256, 0, 0, 0, 0]
+  );
+  assert_eq!(
+    call!(dot_add, (dot_operands.0, dot_operands.1, addends)).lanes::<[i32; 4]>(),
+    [1070, 32_514, 0, i32::MAX]
+  );
+
+  // native results follow the vector instructions of the host
+  let mut config = Config::new();
+  config.relaxed_simd(RelaxedSimd::Native);
+  let mut instance =
+    instantiate_file_with("relaxed_simd.wasm", &Linker::with_config(config)).unwrap_or_else(|err| panic!("{err}"));
+  let (
+    swizzle,
+    trunc_s,
+    trunc_u_zero,
+    f32x4_madd,
+    f64x2_nmadd,
+    laneselect,
+    f32x4_min,
+    f64x2_max,
+    q15mulr,
+    dot,
+    dot_add,
+  ) = funcs!(instance);
+  macro_rules! call {
+    ($func:expr, $params:expr) => {
+      $func.call(&mut instance, $params).expect("the call must not trap")
+    };
+  }
+  if cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
+    assert_eq!(
+      call!(swizzle, (bytes, indices)).lanes::<[u8; 16]>(),
+      [16, 31, 16, 0, 17, 17, 17, 17, 17, 17, 17, 17, 17, 17, 17, 17]
+    );
+    assert_eq!(
+      call!(trunc_s, floats).lanes::<[i32; 4]>(),
+      [i32::MIN, i32::MIN, i32::MIN, -1]
+    );
+    assert_eq!(
+      call!(trunc_u_zero, doubles).lanes::<[u32; 4]>(),
+      [u32::MAX, u32::MAX, 0, 0]
+    );
+    assert_eq!(call!(f32x4_madd, f32_operands).lanes::<[f32; 4]>(), [0.0; 4]);
+    assert_eq!(call!(f64x2_nmadd, f64_operands).lanes::<[f64; 2]>(), [0.0; 2]);
+    assert_eq!(call!(laneselect, masks).lanes::<[i32; 4]>(), [-1, 0, -1, 0]);
+    assert_eq!(
+      call!(f32x4_min, mins).lanes::<[u32; 4]>(),
+      [0x8000_0000, 1.0f32.to_bits(), 0x7FC0_0000, 0]
+    );
+    assert_eq!(call!(f64x2_max, maxs).lanes::<[f64; 2]>(), [2.0, 1.0]);
+    assert_eq!(
+      call!(q15mulr, (q15, q15)).lanes::<[i16; 8]>(),
+      [i16::MIN, 0x2000, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(
+      call!(dot, dot_operands).lanes::<[i16; 8]>(),
+      [17, 53, 32_258, i16::MIN, 0, 0, 0, 0]
+    );
+    assert_eq!(
+      call!(dot_add, (dot_operands.0, dot_operands.1, addends)).lanes::<[i32; 4]>(),
+      [1070, -510, 0, i32::MAX]
+    );
+  } else {
+    assert_eq!(call!(trunc_s, floats).lanes::<[i32; 4]>(), [0, i32::MAX, i32::MIN, -1]);
+    assert_eq!(
+      call!(f32x4_madd, f32_operands).lanes::<[f32; 4]>(),
+      [f32::EPSILON * f32::EPSILON; 4]
+    );
+  }
+}
+
+#[test]
+fn share_tables_with_host() {
+  let table = TableRef::new(Value::FuncRef(None), 2, Some(4)).expect("failed to create a table");
+  assert_eq!(table.size(), 2);
+  assert!(matches!(table.get(1), Ok(Value::FuncRef(None))));
+  assert_eq!(table.set(0, Value::I32(1)), Err(executor::Error::TypeMismatch));
+  assert_eq!(table.grow(2, Value::FuncRef(None)), Ok(Some(2)));
+  assert_eq!(table.grow(1, Value::FuncRef(None)), Ok(None));
+  let table = TableRef::new(Value::ExternRef(None), 0, None).expect("failed to create a table");
+  assert_eq!(table.grow(0x7FFF_FFFF, Value::ExternRef(None)), Ok(None));
+  assert!(matches!(table.get(4), Err(executor::Error::OutOfBoundTableAccess)));
+  assert!(TableRef::new(Value::I64(0), 1, None).is_err());
+}
+
+#[test]
+fn copy_between_tables() {
+  let shared = TableRef::new(Value::ExternRef(None), 4, None).expect("failed to create a table");
+  let alias = TableRef::new(Value::ExternRef(None), 4, None).expect("failed to create a table");
+  let mut linker = Linker::new();
+  linker
+    .define("env", "shared", shared.clone())
+    .define("env", "alias", alias.clone());
+  let mut instance = instantiate_file_with("multi_table.wasm", &linker).unwrap_or_else(|err| panic!("{err}"));
+  let mirror = instance
+    .get_func("mirror")
+    .and_then(|func| func.typed::<(i32, i32, i32), ()>(&instance).ok())
+    .expect("`mirror` must be exported");
+  alias
+    .set(0, Value::ExternRef(Some(7)))
+    .expect("the table must be writable");
+  mirror
+    .call(&mut instance, (2, 0, 1))
+    .expect("the copy must be in bounds");
+  assert!(matches!(shared.get(2), Ok(Value::ExternRef(Some(7)))));
+
+  // the same table imported twice copies within itself
+  linker.define("env", "alias", shared.clone());
+  let mut instance = instantiate_file_with("multi_table.wasm", &linker).unwrap_or_else(|err| panic!("{err}"));
+  let mirror = instance
+    .get_func("mirror")
+    .and_then(|func| func.typed::<(i32, i32, i32), ()>(&instance).ok())
+    .expect("`mirror` must be exported");
+  mirror
+    .call(&mut instance, (1, 2, 2))
+    .expect("the copy must be in bounds");
+  assert!(matches!(shared.get(1), Ok(Value::ExternRef(Some(7)))));
+  assert!(matches!(shared.get(2), Ok(Value::ExternRef(None))));
+}
+
+#[test]
+fn share_imported_entities() {
+  let table = TableRef::new(Value::FuncRef(None), 2, Some(8)).expect("failed to create a table");
+  let memory = MemoryRef::new(1, None).expect("failed to create a memory");
+  let counter = GlobalRef::new(Value::I32(5), true);
+  let mut linker = Linker::new();
+  linker
+    .define("env", "table", table.clone())
+    .define("env", "memory", memory.clone())
+    .define("env", "base", GlobalRef::new(Value::I32(1), false))
+    .define("env", "counter", counter.clone());
+  let mut instance = instantiate_file_with("imports.wasm", &linker).unwrap_or_else(|err| panic!("{err}"));
+
+  // active segments are applied to the imported entities
+  assert!(matches!(table.get(0), Ok(Value::FuncRef(None))));
+  assert!(matches!(table.get(1), Ok(Value::FuncRef(Some(0)))));
+  let mut bytes = [0; 6];
+  memory.read(0, &mut bytes).expect("the memory must be readable");
+  assert_eq!(&bytes, b"\0wagyu");
+
+  let bump = instance
+    .get_func("bump")
+    .and_then(|func| func.typed::<(), i32>(&instance).ok())
+    .expect("`bump` must be exported");
+  assert_eq!(bump.call(&mut instance, ()).ok(), Some(6));
+  assert!(matches!(counter.get(), Value::I32(6)));
+  counter.set(Value::I32(10)).expect("the counter must be mutable");
+  assert_eq!(bump.call(&mut instance, ()).ok(), Some(11));
+
+  let grow = instance
+    .get_func("grow")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`grow` must be exported");
+  assert_eq!(grow.call(&mut instance, 3).ok(), Some(2));
+  assert_eq!(table.size(), 5);
+  assert_eq!(table.grow(1, Value::FuncRef(None)), Ok(Some(5)));
+  let size = instance
+    .get_func("size")
+    .and_then(|func| func.typed::<(), i32>(&instance).ok())
+    .expect("`size` must be exported");
+  assert_eq!(size.call(&mut instance, ()).ok(), Some(6));
+
+  linker.define(
+    "env",
+    "memory",
+    MemoryRef::new(0, None).expect("failed to create a memory"),
+  );
+  let Err(err) = instantiate_file_with("imports.wasm", &linker) else {
+    panic!("a memory smaller than its import must not link");
+  };
+  assert!(matches!(err, instance::Error::IncompatibleImport { .. }), "{err}");
+}
+
+#[test]
+fn read_custom_sections() {
+  let buffer = fs::read("tests/wasm/custom.wasm").expect("failed to read a file");
+  let module = compile(&buffer).unwrap_or_else(|err| panic!("{err}"));
+
+  let meta: Vec<_> = module.custom_sections("wagyu.meta").collect();
+  assert_eq!(meta, [&b"first"[..], &[0x00, 0xFF]]);
+  assert_eq!(module.custom_sections("producers").collect::<Vec<_>>(), [&[0x00]]);
+  assert_eq!(module.custom_sections("missing").count(), 0);
+
+  // a custom section whose name runs past the end of the section
+  let buffer = [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x05, 0x61];
+  let err = compile(&buffer).expect_err("a truncated name must not compile");
+  assert!(matches!(err.kind, parse::ErrorKind::UnexpectedEof));
+}
+
+#[test]
+fn decode_names() {
+  let buffer = fs::read("tests/wasm/names.wasm").expect("failed to read a file");
+  let module = compile(&buffer).unwrap_or_else(|err| panic!("{err}"));
+  let names = module.names();
+
+  assert_eq!(names.module_name(), Some("demo"));
+  assert_eq!(names.func_name(0), Some("check"));
+  assert_eq!(names.func_name(1), None);
+  assert_eq!(names.local_name(0, 0), Some("value"));
+  assert_eq!(names.local_name(0, 1), Some("doubled"));
+  assert_eq!(names.label_name(0, 0), Some("done"));
+  assert_eq!(names.type_name(0), Some("unary"));
+  assert_eq!(names.table_name(0), Some("tab"));
+  assert_eq!(names.memory_name(0), Some("mem"));
+  assert_eq!(names.global_name(0), Some("limit"));
+  assert_eq!(names.elem_name(0), Some("fns"));
+  assert_eq!(names.data_name(0), Some("bytes"));
+
+  let mut instance = instantiate_file("names.wasm");
+  let check = instance
+    .get_func("check")
+    .and_then(|func| func.typed::<i32, i32>(&instance).ok())
+    .expect("`check` must be exported");
+  assert_eq!(check.call(&mut instance, 4).ok(), Some(8));
+  assert_eq!(
+    check.call(&mut instance, 10).err().map(Trap::into_error),
+    Some(executor::Error::Unreachable)
+  );
+  let Err(trap) = check.call(&mut instance, 10) else {
+    panic!("`check` must trap above the limit");
+  };
+  let frames: Vec<_> = trap
+    .backtrace()
+    .iter()
+    .map(|frame| (frame.func_idx(), frame.func_name(), frame.code_offset()))
+    .collect();
+  assert_eq!(frames, [(0, Some("check"), Some(0x4C)), (1, None, Some(0x5A))]);
+  assert_eq!(instance.describe_func(0), "0 ($check)");
+  assert_eq!(instance.describe_func(1), "1");
+
+  let Err(err) = instantiate_file_with("start_trap.wasm", &Linker::new()) else {
+    panic!("the start function must trap");
+  };
+  assert_eq!(
+    err.to_string(),
+    "Runtime error: unreachable executed\n\
+     wasm backtrace:\n  \
+     0: function 0 ($fail) at 0x000001B\n  \
+     1: function 1 ($init) at 0x000001F"
+  );
+
+  // a malformed name section is ignored rather than rejecting the module
+  let buffer = [
+    0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x00, 0x07, 0x04, 0x6E, 0x61, 0x6D, 0x65, 0x01, 0x7F,
+  ];
+  let module = compile(&buffer).unwrap_or_else(|err| panic!("{err}"));
+  assert_eq!(module.names().func_name(0), None);
+}
+
+#[test]
+fn inspect_module_abi() {
+  let buffer = fs::read("tests/wasm/abi.wasm").expect("failed to read a file");
+  let module = compile(&buffer).unwrap_or_else(|err| panic!("{err}"));
+
+  let imports: Vec<_> = module.imports().collect();
+  assert_eq!(imports.len(), 2);
+  assert_eq!((imports[0].module, imports[0].name), ("env", "log"));
+  let ExternType::Func(ty) = &imports[0].ty else {
+    panic!("`log` must be a function import");
+  };
+  assert_eq!((ty.params(), ty.results()), (&[ValType::I32][..], &[][..]));
+  assert_eq!((imports[1].module, imports[1].name), ("env", "now"));
+
+  let exports: Vec<_> = module.exports().map(|export| (export.name, export.ty)).collect();
+  let names: Vec<_> = exports.iter().map(|(name, _)| *name).collect();
+  assert_eq!(names, ["table", "memory", "counter", "version", "tick", "now"]);
+  assert!(
+    matches!(exports[0].1, ExternType::Table(RefType::FuncRef, limit) if (limit.min(), limit.max()) == (2, Some(8)))
+  );
+  assert!(matches!(exports[1].1, ExternType::Memory(limit) if (limit.min(), limit.max()) == (1, None)));
+  assert_eq!(exports[2].1, ExternType::Global(ValType::I64, GlobalMut::Var));
+  assert_eq!(exports[3].1, ExternType::Global(ValType::I32, GlobalMut::Const));
+  let ExternType::Func(ty) = &exports[4].1 else {
+    panic!("`tick` must be a function export");
+  };
+  assert_eq!((ty.params(), ty.results()), (&[ValType::I32][..], &[ValType::I64][..]));
+  // re-exported imports resolve through the function index space
+  let ExternType::Func(ty) = &exports[5].1 else {
+    panic!("`now` must be a function export");
+  };
+  assert_eq!((ty.params(), ty.results()), (&[][..], &[ValType::I64][..]));
+}
+
+#[test]
+fn validate_modules() {
+  for file_name in [
+    "arith.wasm",
+    "control.wasm",
+    "conversions.wasm",
+    "data.wasm",
+    "elem.wasm",
+    "fac.wasm",
+    "fib.wasm",
+    "globals.wasm",
+    "host.wasm",
+    "memory.wasm",
+    "memory64.wasm",
+    "multi_memory.wasm",
+    "relaxed_simd.wasm",
+    "sample1.wasm",
+    "simd.wasm",
+    "table.wasm",
+  ] {
+    let buffer = fs::read(format!("tests/wasm/{file_name}")).expect("failed to read a file");
+    if let Err(err) = validate(&buffer) {
+      panic!("{file_name}: {err}");
+    }
+  }
+
+  let buffer = fs::read("tests/wasm/invalid_type.wasm").expect("failed to read a file");
+  let Err(instance::Error::Validation(err)) = validate(&buffer) else {
+    panic!("`invalid_type.wasm` must not validate");
+  };
+  assert_eq!(err.kind, validator::ErrorKind::TypeMismatch);
+  assert_eq!(err.func_idx, Some(1));
+  assert_eq!(err.offset, Some(0x2E));
+  assert_eq!(
+    err.to_string(),
+    "Validation error: type mismatch: expected f32, found i32 in function 1 at 0x000002E"
+  );
+
+  assert!(matches!(
+    instantiate_file_with("invalid_type.wasm", &Linker::new()),
+    Err(instance::Error::Validation(_))
+  ));
+
+  // constant expressions may only read imported globals
+  let buffer = fs::read("tests/wasm/invalid_const.wasm").expect("failed to read a file");
+  let Err(instance::Error::Validation(err)) = validate(&buffer) else {
+    panic!("`invalid_const.wasm` must not validate");
+  };
+  assert_eq!(err.kind, validator::ErrorKind::UnknownGlobal);
+  assert_eq!(err.func_idx, None);
+
+  // only 64-bit memories take offsets beyond 4 GiB
+  let buffer = fs::read("tests/wasm/invalid_offset.wasm").expect("failed to read a file");
+  let Err(instance::Error::Validation(err)) = validate(&buffer) else {
+    panic!("`invalid_offset.wasm` must not validate");
+  };
+  assert_eq!(err.kind, validator::ErrorKind::OffsetOutOfRange);
+
+  // lane indices are immediates, so they are checked against the shape ahead of execution
+  let buffer = fs::read("tests/wasm/invalid_lane.wasm").expect("failed to read a file");
+  let Err(instance::Error::Validation(err)) = validate(&buffer) else {
+    panic!("`invalid_lane.wasm` must not validate");
+  };
+  assert_eq!(err.kind, validator::ErrorKind::InvalidLaneIndex);
+  assert_eq!(err.func_idx, Some(0));
+}
+
+#[test]
+fn reject_malformed_files() {
+  for file_name in [
+    "arith.wasm",
+    "control.wasm",
+    "elem.wasm",
+    "fib.wasm",
+    "host.wasm",
+    "sample1.wasm",
+  ] {
+    let buffer = fs::read(format!("tests/wasm/{file_name}")).expect("failed to read a file");
+
+    // truncated input must be rejected with an error rather than a panic
+    for len in 0..buffer.len() {
+      let _ = compile(&buffer[..len]);
+    }
+  }
+
+  let err = compile(&[0x00, 0x61, 0x73]).expect_err("a 3-byte file must not compile");
+  assert!(matches!(err.kind, parse::ErrorKind::UnexpectedEof));
+  assert_eq!(err.offset, 0);
+
+  // the type section declares more bytes than its single type occupies
+  let buffer = [
+    0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x00, 0x00,
+  ];
+  let err = compile(&buffer).expect_err("a padded section must not compile");
+  assert!(matches!(err.kind, parse::ErrorKind::SectionSizeMismatch));
+  assert_eq!(err.offset, 10);
+
+  // the code section declares more bytes than the input holds
+  let buffer = [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x0A, 0x10, 0x01];
+  let err = compile(&buffer).expect_err("a truncated section must not compile");
+  assert!(matches!(err.kind, parse::ErrorKind::UnexpectedEof));
+
+  // a LEB128 integer whose continuation bit is never cleared
+  let buffer = [
+    0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x01, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+  ];
+  let err = compile(&buffer).expect_err("an unterminated integer must not compile");
+  assert!(matches!(
+    err.kind,
+    parse::ErrorKind::InvalidValue | parse::ErrorKind::UnexpectedEof
+  ));
+
+  // a u32 takes at most 5 bytes, whose last one only holds 4 bits
+  for (count, message) in [
+    (
+      &[0x81, 0x80, 0x80, 0x80, 0x80, 0x00][..],
+      "integer representation too long",
+    ),
+    (&[0x81, 0x80, 0x80, 0x80, 0x10][..], "integer too large"),
+  ] {
+    let mut buffer = vec![
+      0x00,
+      0x61,
+      0x73,
+      0x6D,
+      0x01,
+      0x00,
+      0x00,
+      0x00,
+      0x01,
+      count.len() as u8 + 3,
+    ];
+    buffer.extend_from_slice(count);
+    buffer.extend_from_slice(&[0x60, 0x00, 0x00]);
+    let err = compile(&buffer).expect_err("an over-long count must not compile");
+    assert!(matches!(err.kind, parse::ErrorKind::InvalidValue));
+    assert_eq!((err.offset, err.message.as_str()), (10, message));
+  }
+
+  // so does the s32 of `i32.const`, whose last byte repeats the sign bit in its unused bits
+  for (val, message) in [
+    (
+      &[0x80, 0x80, 0x80, 0x80, 0x80, 0x00][..],
+      "integer representation too long",
+    ),
+    (
+      &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F][..],
+      "integer representation too long",
+    ),
+    (&[0x80, 0x80, 0x80, 0x80, 0x70][..], "integer too large"),
+    (&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F][..], "integer too large"),
+  ] {
+    let mut buffer = vec![
+      0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02, 0x01, 0x00, 0x0A,
+    ];
+    buffer.extend_from_slice(&[val.len() as u8 + 6, 0x01, val.len() as u8 + 4, 0x00, 0x41]);
+    buffer.extend_from_slice(val);
+    buffer.extend_from_slice(&[0x1A, 0x0B]);
+    let err = compile(&buffer).expect_err("an over-long constant must not compile");
+    assert!(matches!(err.kind, parse::ErrorKind::InvalidValue));
+    assert_eq!((err.offset, err.message.as_str()), (24, message));
+  }
+}
@@ -0,0 +1,12 @@
+(module
+  (import "env" "log" (func $log (param i32)))
+  (import "env" "now" (func $now (result i64)))
+  (table $tab (export "table") 2 8 funcref)
+  (memory (export "memory") 1)
+  (global $counter (export "counter") (mut i64) (i64.const 0))
+  (global (export "version") i32 (i32.const 3))
+  (func $tick (export "tick") (param i32) (result i64)
+    local.get 0
+    call $log
+    call $now)
+  (export "now" (func $now)))
@@ -0,0 +1,36 @@
+(module
+  (func (export "divS") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.div_s
+  )
+  (func (export "remU") (param i64 i64) (result i64)
+    local.get 0
+    local.get 1
+    i64.rem_u
+  )
+  (func (export "min") (param f32 f32) (result f32)
+    local.get 0
+    local.get 1
+    f32.min
+  )
+  (func (export "nearest") (param f64) (result f64)
+    local.get 0
+    f64.nearest
+  )
+  (func (export "choose") (param i32 i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    local.get 2
+    select
+  )
+  (func (export "selfDiv") (param i32) (result i32) (local i32)
+    local.get 0
+    local.tee 1
+    local.get 1
+    call 0
+  )
+  (func (export "trap")
+    unreachable
+  )
+)
@@ -0,0 +1,71 @@
+(module
+  (type $pair (func (param i32) (result i32 i32)))
+  (func (export "sum") (param i32) (result i32) (local i32)
+    block
+      loop
+        local.get 0
+        i32.eqz
+        br_if 1
+        local.get 1
+        local.get 0
+        i32.add
+        local.set 1
+        local.get 0
+        i32.const 1
+        i32.sub
+        local.set 0
+        br 0
+      end
+    end
+    local.get 1
+  )
+  (func (export "switch") (param i32) (result i32)
+    block
+      block
+        block
+          local.get 0
+          br_table 0 1 2
+        end
+        i32.const 100
+        return
+      end
+      i32.const 200
+      return
+    end
+    i32.const 300
+  )
+  (func (export "sign") (param i32) (result i32)
+    local.get 0
+    i32.const 0
+    i32.lt_s
+    if (result i32)
+      i32.const -1
+    else
+      local.get 0
+      if (result i32)
+        i32.const 1
+      else
+        i32.const 0
+      end
+    end
+  )
+  (func (export "dup") (param i32) (result i32)
+    local.get 0
+    block (type $pair)
+      local.get 0
+    end
+    i32.add
+  )
+  (func (export "early") (param i32) (result i32)
+    block (result i32)
+      i32.const 7
+      local.get 0
+      br_if 0
+      drop
+      i32.const 9
+    end
+  )
+  (func $forever (export "forever") (result i32)
+    call $forever
+  )
+)
@@ -0,0 +1,5 @@
+(module
+  (@custom "wagyu.meta" "first")
+  (@custom "producers" (after func) "\00")
+  (@custom "wagyu.meta" (after last) "\00\ff")
+  (func (export "nop")))
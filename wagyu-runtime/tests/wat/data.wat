@@ -0,0 +1,15 @@
+(module
+  (import "env" "peek" (func $peek (param i32 i32) (result i32)))
+  (memory $mem 1)
+  (memory $aux 1)
+  (data (i32.const 0) "\ff\00\80")
+  (data (memory $aux) (i32.add (i32.const 256) (i32.const 16)) "\c3\28")
+  (data "passive")
+  (func (export "load") (param i32) (result i32)
+    i32.const 0
+    local.get 0
+    call $peek)
+  (func (export "loadAux") (param i32) (result i32)
+    i32.const 1
+    local.get 0
+    call $peek))
@@ -0,0 +1,41 @@
+(module
+  (type $nullary (func (result i32)))
+  (table $funcs 8 funcref)
+  (table $more 4 funcref)
+  (table $externs 2 externref)
+  (func $one (result i32)
+    i32.const 1)
+  (func $two (result i32)
+    i32.const 2)
+  (func $three (result i32)
+    i32.const 3)
+  (func $four (result i32)
+    i32.const 4)
+  (elem (i32.const 0) $one $two)
+  (elem $passive func $three $four)
+  (elem (table $more) (i32.const 1) func $four)
+  (elem declare func $one)
+  (elem (i32.const 2) funcref (ref.func $three) (ref.null func))
+  (elem $passive_exprs funcref (ref.func $two) (ref.null func) (ref.func $one))
+  (elem (table $externs) (i32.const 1) externref (ref.null extern))
+  (elem declare funcref (ref.func $two))
+  (func (export "call") (param i32) (result i32)
+    local.get 0
+    call_indirect $funcs (type $nullary))
+  (func (export "callMore") (param i32) (result i32)
+    local.get 0
+    call_indirect $more (type $nullary))
+  (func (export "init") (param i32 i32 i32)
+    local.get 0
+    local.get 1
+    local.get 2
+    table.init $funcs $passive)
+  (func (export "initExprs") (param i32 i32 i32)
+    local.get 0
+    local.get 1
+    local.get 2
+    table.init $funcs $passive_exprs)
+  (func (export "drop")
+    elem.drop $passive)
+  (func (export "ref") (result funcref)
+    ref.func $two))
@@ -0,0 +1,45 @@
+(module
+  (type $nullary (func (result i32)))
+  (table $funcs 4 funcref)
+  (global $answer i32 (i32.const 42))
+  (global $big i64 (i64.const -5000000000))
+  (global $pi f32 (f32.const 3.5))
+  (global $e f64 (f64.const 2.25))
+  (global $counter (mut i32) (i32.const 10))
+  (global $offset i32 (i32.add (i32.mul (i32.const 2) (i32.const 3)) (i32.const -4)))
+  (global $wide i64 (i64.sub (i64.const 1) (i64.const 2)))
+  (global $wraps i32 (i32.mul (i32.const 0x40000000) (i32.const 4)))
+  (global $fn funcref (ref.func $seven))
+  (global $none externref (ref.null extern))
+  (func $seven (result i32)
+    i32.const 7)
+  (elem (i32.sub (i32.const 5) (i32.const 4)) func $seven)
+  (func (export "answer") (result i32)
+    global.get $answer)
+  (func (export "big") (result i64)
+    global.get $big)
+  (func (export "pi") (result f32)
+    global.get $pi)
+  (func (export "e") (result f64)
+    global.get $e)
+  (func (export "bump") (result i32)
+    global.get $counter
+    i32.const 1
+    i32.add
+    global.set $counter
+    global.get $counter)
+  (func (export "offset") (result i32)
+    global.get $offset)
+  (func (export "wide") (result i64)
+    global.get $wide)
+  (func (export "wraps") (result i32)
+    global.get $wraps)
+  (func (export "fnIsNull") (result i32)
+    global.get $fn
+    ref.is_null)
+  (func (export "noneIsNull") (result i32)
+    global.get $none
+    ref.is_null)
+  (func (export "call") (param i32) (result i32)
+    local.get 0
+    call_indirect $funcs (type $nullary)))
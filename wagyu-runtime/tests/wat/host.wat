@@ -0,0 +1,21 @@
+(module
+  (import "env" "add" (func $add (param i32 i32) (result i32)))
+  (import "env" "poke" (func $poke (param i32 i32)))
+  (import "env" "peek" (func $peek (param i32) (result i32)))
+  (import "env" "started" (func $started))
+  (memory 1)
+  (func $init
+    call $started)
+  (start $init)
+  (func (export "addTwice") (param i32) (result i32)
+    local.get 0
+    local.get 0
+    call $add
+    local.get 0
+    call $add)
+  (func (export "pokeAndLoad") (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    call $poke
+    local.get 0
+    call $peek))
@@ -0,0 +1,2 @@
+(module
+  (memory i64 0x100_0000_0000))
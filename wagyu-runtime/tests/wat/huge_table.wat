@@ -0,0 +1,2 @@
+(module
+  (table 0xffff_ffff funcref))
@@ -0,0 +1,23 @@
+(module
+  (import "env" "table" (table $tab 2 8 funcref))
+  (import "env" "memory" (memory $mem 1))
+  (import "env" "base" (global $base i32))
+  (import "env" "counter" (global $counter (mut i32)))
+  (table $own 1 funcref)
+  (memory $scratch 1)
+  (global $next (mut i32) (global.get $base))
+  (elem (table $tab) (global.get $base) func $bump)
+  (elem (table $own) (i32.const 0) func $bump)
+  (data (memory $mem) (global.get $base) "wagyu")
+  (func $bump (export "bump") (result i32)
+    global.get $counter
+    global.get $next
+    i32.add
+    global.set $counter
+    global.get $counter)
+  (func (export "grow") (param i32) (result i32)
+    ref.null func
+    local.get 0
+    table.grow $tab)
+  (func (export "size") (result i32)
+    table.size $tab))
@@ -0,0 +1,6 @@
+(module
+  (func (param i32) (result i32)
+    local.get 0)
+  (func (export "bad") (result i32)
+    i32.const 1
+    f32.neg))
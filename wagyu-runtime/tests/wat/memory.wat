@@ -0,0 +1,55 @@
+(module
+  (memory $heap 1)
+  (memory $aux 1 3)
+  (data $greeting "hello")
+  (func (export "store") (param i32 i64)
+    local.get 0
+    local.get 1
+    i64.store offset=8)
+  (func (export "load8_s") (param i32) (result i32)
+    local.get 0
+    i32.load8_s offset=8)
+  (func (export "load8_u") (param i32) (result i32)
+    local.get 0
+    i32.load8_u offset=8)
+  (func (export "load16_s") (param i32) (result i64)
+    local.get 0
+    i64.load16_s offset=8 align=1)
+  (func (export "load32_u") (param i32) (result i64)
+    local.get 0
+    i64.load32_u offset=8)
+  (func (export "load_f64") (param i32) (result f64)
+    local.get 0
+    f64.load offset=8)
+  (func (export "load_aux") (param i32) (result i32)
+    local.get 0
+    i32.load $aux)
+  (func (export "store_aux") (param i32 i32)
+    local.get 0
+    local.get 1
+    i32.store8 $aux)
+  (func (export "size") (result i32)
+    memory.size $aux)
+  (func (export "fill") (param i32 i32 i32)
+    local.get 0
+    local.get 1
+    local.get 2
+    memory.fill)
+  (func (export "copy") (param i32 i32 i32)
+    local.get 0
+    local.get 1
+    local.get 2
+    memory.copy)
+  (func (export "init") (param i32 i32 i32)
+    local.get 0
+    local.get 1
+    local.get 2
+    memory.init $greeting)
+  (func (export "drop")
+    data.drop $greeting)
+  (func (export "peek") (param i32) (result i32)
+    local.get 0
+    i32.load8_u)
+  (func (export "grow_aux") (param i32) (result i32)
+    local.get 0
+    memory.grow $aux))
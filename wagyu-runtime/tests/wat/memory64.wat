@@ -0,0 +1,28 @@
+(module
+  (import "env" "heap" (memory $heap i64 1))
+  (memory $big i64 1 70000)
+  (data (memory $big) (i64.const 65530) "wagyu")
+  (func (export "load") (param i64) (result i64)
+    local.get 0
+    i64.load $big offset=4294967296)
+  (func (export "store") (param i64 i32)
+    local.get 0
+    local.get 1
+    i32.store8 $big)
+  (func (export "peek") (param i64) (result i32)
+    local.get 0
+    i32.load8_u $big)
+  (func (export "size") (result i64)
+    memory.size $big)
+  (func (export "grow") (param i64) (result i64)
+    local.get 0
+    memory.grow $big)
+  (func (export "fill") (param i64 i32 i64)
+    local.get 0
+    local.get 1
+    local.get 2
+    memory.fill $big)
+  (func (export "poke_heap") (param i64 i32)
+    local.get 0
+    local.get 1
+    i32.store8 $heap))
@@ -0,0 +1,31 @@
+(module
+  (import "env" "shared" (memory $shared 1))
+  (import "env" "alias" (memory $alias 1))
+  (memory $private 1)
+  (memory $wide i64 1)
+  (data (memory $private) (i32.const 0) "secret")
+  (func (export "publish") (param i32 i32 i32)
+    local.get 0
+    local.get 1
+    local.get 2
+    memory.copy $shared $private)
+  (func (export "mirror") (param i32 i32 i32)
+    local.get 0
+    local.get 1
+    local.get 2
+    memory.copy $alias $shared)
+  (func (export "widen") (param i64 i32 i32)
+    local.get 0
+    local.get 1
+    local.get 2
+    memory.copy $wide $private)
+  (func (export "peek_private") (param i32) (result i32)
+    local.get 0
+    i32.load8_u $private)
+  (func (export "peek_wide") (param i64) (result i32)
+    local.get 0
+    i32.load8_u $wide)
+  (func (export "sizes") (result i32 i32 i64)
+    memory.size $shared
+    memory.size $private
+    memory.size $wide))
@@ -0,0 +1,5 @@
+(module
+  (import "env" "shared" (table $shared 4 externref))
+  (import "env" "alias" (table $alias 4 externref))
+  (func (export "mirror") (param i32 i32 i32)
+    (table.copy $shared $alias (local.get 0) (local.get 1) (local.get 2))))
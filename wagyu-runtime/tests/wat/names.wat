@@ -0,0 +1,23 @@
+(module $demo
+  (type $unary (func (param i32) (result i32)))
+  (table $tab 1 funcref)
+  (memory $mem 1)
+  (global $limit i32 (i32.const 10))
+  (elem $fns (i32.const 0) $check)
+  (data $bytes "abc")
+  (func $check (type $unary) (param $value i32) (result i32)
+    (local $doubled i32)
+    block $done
+      local.get $value
+      global.get $limit
+      i32.lt_u
+      br_if $done
+      unreachable
+    end
+    local.get $value
+    local.get $value
+    i32.add
+    local.tee $doubled)
+  (func (export "check") (param i32) (result i32)
+    local.get 0
+    call $check))
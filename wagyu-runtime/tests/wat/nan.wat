@@ -0,0 +1,15 @@
+(module
+  (global $start_sum (mut f32) (f32.const 0))
+  (func $start
+    (global.set $start_sum (f32.add (f32.const nan:0x200001) (f32.const 1))))
+  (start $start)
+  (func (export "start_sum") (result f32)
+    (global.get $start_sum))
+  (func (export "add") (param f32 f32) (result f32)
+    (f32.add (local.get 0) (local.get 1)))
+  (func (export "min") (param f32 f32) (result f32)
+    (f32.min (local.get 0) (local.get 1)))
+  (func (export "neg") (param f32) (result f32)
+    (f32.neg (local.get 0)))
+  (func (export "div") (param f64 f64) (result f64)
+    (f64.div (local.get 0) (local.get 1))))
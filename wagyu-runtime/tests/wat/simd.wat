@@ -0,0 +1,76 @@
+(module
+  (memory 1)
+  (data (i32.const 16) "\01\02\03\04\05\06\07\08\f9\fa\fb\fc\fd\fe\ff\00")
+
+  (func (export "v128.load") (param i32) (result v128)
+    (v128.load offset=16 (local.get 0)))
+  (func (export "v128.load8x8_s") (param i32) (result v128)
+    (v128.load8x8_s offset=16 (local.get 0)))
+  (func (export "v128.load32_splat") (param i32) (result v128)
+    (v128.load32_splat offset=16 (local.get 0)))
+  (func (export "v128.load64_zero") (param i32) (result v128)
+    (v128.load64_zero offset=16 (local.get 0)))
+  (func (export "v128.load16_lane") (param i32 v128) (result v128)
+    (v128.load16_lane offset=16 7 (local.get 0) (local.get 1)))
+  (func (export "v128.store") (param i32 v128)
+    (v128.store (local.get 0) (local.get 1)))
+  (func (export "v128.store32_lane") (param i32 v128)
+    (v128.store32_lane 2 (local.get 0) (local.get 1)))
+  (func (export "v128.const") (result v128)
+    (v128.const i32x4 1 -2 3 -4))
+
+  (func (export "i8x16.shuffle") (param v128 v128) (result v128)
+    (i8x16.shuffle 0 16 1 17 2 18 3 19 4 20 5 21 6 22 7 23 (local.get 0) (local.get 1)))
+  (func (export "i8x16.swizzle") (param v128 v128) (result v128)
+    (i8x16.swizzle (local.get 0) (local.get 1)))
+  (func (export "i16x8.splat") (param i32) (result v128)
+    (i16x8.splat (local.get 0)))
+  (func (export "i8x16.extract_lane_s") (param v128) (result i32)
+    (i8x16.extract_lane_s 15 (local.get 0)))
+  (func (export "i8x16.extract_lane_u") (param v128) (result i32)
+    (i8x16.extract_lane_u 15 (local.get 0)))
+  (func (export "f64x2.replace_lane") (param v128 f64) (result v128)
+    (f64x2.replace_lane 1 (local.get 0) (local.get 1)))
+
+  (func (export "i8x16.add_sat_u") (param v128 v128) (result v128)
+    (i8x16.add_sat_u (local.get 0) (local.get 1)))
+  (func (export "i16x8.mul") (param v128 v128) (result v128)
+    (i16x8.mul (local.get 0) (local.get 1)))
+  (func (export "i32x4.shl") (param v128 i32) (result v128)
+    (i32x4.shl (local.get 0) (local.get 1)))
+  (func (export "i64x2.shr_s") (param v128 i32) (result v128)
+    (i64x2.shr_s (local.get 0) (local.get 1)))
+  (func (export "i32x4.lt_s") (param v128 v128) (result v128)
+    (i32x4.lt_s (local.get 0) (local.get 1)))
+  (func (export "i8x16.bitmask") (param v128) (result i32)
+    (i8x16.bitmask (local.get 0)))
+  (func (export "v128.any_true") (param v128) (result i32)
+    (v128.any_true (local.get 0)))
+  (func (export "i32x4.all_true") (param v128) (result i32)
+    (i32x4.all_true (local.get 0)))
+  (func (export "v128.bitselect") (param v128 v128 v128) (result v128)
+    (v128.bitselect (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "i8x16.narrow_i16x8_u") (param v128 v128) (result v128)
+    (i8x16.narrow_i16x8_u (local.get 0) (local.get 1)))
+  (func (export "i32x4.extmul_high_i16x8_s") (param v128 v128) (result v128)
+    (i32x4.extmul_high_i16x8_s (local.get 0) (local.get 1)))
+  (func (export "i32x4.dot_i16x8_s") (param v128 v128) (result v128)
+    (i32x4.dot_i16x8_s (local.get 0) (local.get 1)))
+  (func (export "i16x8.q15mulr_sat_s") (param v128 v128) (result v128)
+    (i16x8.q15mulr_sat_s (local.get 0) (local.get 1)))
+
+  (func (export "f32x4.min") (param v128 v128) (result v128)
+    (f32x4.min (local.get 0) (local.get 1)))
+  (func (export "f32x4.pmin") (param v128 v128) (result v128)
+    (f32x4.pmin (local.get 0) (local.get 1)))
+  (func (export "f64x2.nearest") (param v128) (result v128)
+    (f64x2.nearest (local.get 0)))
+  (func (export "i32x4.trunc_sat_f32x4_s") (param v128) (result v128)
+    (i32x4.trunc_sat_f32x4_s (local.get 0)))
+  (func (export "i32x4.trunc_sat_f64x2_u_zero") (param v128) (result v128)
+    (i32x4.trunc_sat_f64x2_u_zero (local.get 0)))
+  (func (export "f64x2.convert_low_i32x4_u") (param v128) (result v128)
+    (f64x2.convert_low_i32x4_u (local.get 0)))
+  (func (export "f32x4.demote_f64x2_zero") (param v128) (result v128)
+    (f32x4.demote_f64x2_zero (local.get 0)))
+)
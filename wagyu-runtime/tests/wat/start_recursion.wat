@@ -0,0 +1,7 @@
+(module
+  (global $depth (mut i32) (i32.const 64))
+  (func $recurse
+    (global.set $depth (i32.sub (global.get $depth) (i32.const 1)))
+    (if (global.get $depth)
+      (then (call $recurse))))
+  (start $recurse))
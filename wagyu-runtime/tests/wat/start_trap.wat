@@ -0,0 +1,6 @@
+(module
+  (func $fail
+    unreachable)
+  (func $init
+    call $fail)
+  (start $init))
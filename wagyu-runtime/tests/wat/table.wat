@@ -0,0 +1,46 @@
+(module
+  (type $binop (func (param i32 i32) (result i32)))
+  (table $ops 4 8 funcref)
+  (table $spare 0 funcref)
+  (func $add (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.add)
+  (func $sub (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.sub)
+  (func $neg (param i32) (result i32)
+    i32.const 0
+    local.get 0
+    i32.sub)
+  (elem declare func $add $sub $neg)
+  (func (export "setup")
+    i32.const 0
+    ref.func $add
+    table.set $ops
+    i32.const 1
+    ref.func $sub
+    table.set $ops
+    i32.const 2
+    ref.func $neg
+    table.set $ops)
+  (func (export "apply") (param i32 i32 i32) (result i32)
+    local.get 1
+    local.get 2
+    local.get 0
+    call_indirect $ops (type $binop))
+  (func (export "grow") (param i32) (result i32)
+    ref.null func
+    local.get 0
+    table.grow $ops)
+  (func (export "growSpare") (param i32) (result i32)
+    ref.null func
+    local.get 0
+    table.grow $spare)
+  (func (export "size") (result i32)
+    table.size $ops)
+  (func (export "isNull") (param i32) (result i32)
+    local.get 0
+    table.get $ops
+    ref.is_null))